@@ -6,7 +6,7 @@ use external::system::SystemModules;
 
 fn main() {
 	println!("Drivers with 0x20 flag set:");
-	for sm in &SystemModules::query() {
+	for sm in &SystemModules::query().unwrap() {
 		if sm.flags() & 0x20 != 0 {
 			println!("{:#018x}: {:#?}", sm.image_base(), sm.file_name());
 		}
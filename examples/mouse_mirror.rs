@@ -23,7 +23,7 @@ unsafe fn mouse_move(context: &mut external::hook::MouseLL) {
 				if oldx != 0 && oldy != 0 {
 					let dx = context.pt_x() - oldx;
 					let dy = context.pt_y() - oldy;
-					MouseInput::mouse_move(dx, dy).send();
+					let _ = MouseInput::mouse_move(dx, dy).send();
 					print!("\rdx:{} dy:{}                  ", dx, dy);
 					context.cancel();
 				}
@@ -48,7 +48,7 @@ fn main() {
 		let mut dx = 1;
 		loop {
 			dx = -dx;
-			MouseInput::mouse_move(dx, 0).send();
+			let _ = MouseInput::mouse_move(dx, 0).send();
 			sleep(1);
 		}
 	});
@@ -0,0 +1,25 @@
+/*!
+Print the raw mouse deltas, unaffected by pointer speed and acceleration.
+!*/
+
+use external::input::{RawEvent, RawInput, RawInputFlags, RawKind};
+use external::wndclass::{message_window, run};
+
+const WM_INPUT: u32 = 0x00ff;
+
+fn main() {
+	let window = message_window(|_, msg, _, lparam| {
+		if msg == WM_INPUT {
+			if let Ok(RawEvent::Mouse(mouse)) = RawInput::parse(lparam) {
+				let (dx, dy) = mouse.delta();
+				println!("dx: {:>4} dy: {:>4} wheel: {:>4}", dx, dy, mouse.wheel());
+			}
+		}
+		// Let DefWindowProc perform the cleanup
+		None
+	}).unwrap();
+
+	// Receive the input even while other windows are in the foreground
+	RawInput::register(&[RawKind::Mouse], window.window(), RawInputFlags::INPUTSINK).unwrap();
+	run();
+}
@@ -0,0 +1,51 @@
+/*!
+Opens a window with a closure as its window procedure.
+
+Paints a rectangle and closes when Escape is pressed.
+!*/
+
+use std::mem;
+
+use winapi::um::winuser::*;
+use winapi::um::wingdi::*;
+use winapi::shared::windef::RECT;
+
+use external::IntoInner;
+use external::wndclass::{WindowBuilder, pump_thread, post_quit_message};
+
+fn main() {
+	let window = WindowBuilder::new()
+		.class_name("WindowBuilderExample")
+		.title("Press Escape to close")
+		.size(400, 300)
+		.wndproc(|window, msg, wparam, _lparam| {
+			match msg {
+				WM_PAINT => unsafe {
+					let hwnd = window.into_inner();
+					let mut ps = mem::zeroed();
+					let hdc = BeginPaint(hwnd, &mut ps);
+					let brush = CreateSolidBrush(RGB(200, 40, 40));
+					let rect = RECT { left: 50, top: 50, right: 250, bottom: 150 };
+					FillRect(hdc, &rect, brush);
+					DeleteObject(brush as *mut _);
+					EndPaint(hwnd, &ps);
+					Some(0)
+				},
+				WM_KEYDOWN if wparam == VK_ESCAPE as usize => {
+					// Posted so WM_DESTROY reaches this closure once it has returned
+					window.close().unwrap();
+					Some(0)
+				},
+				WM_DESTROY => {
+					println!("Window destroyed");
+					post_quit_message(0);
+					Some(0)
+				},
+				_ => None,
+			}
+		})
+		.create()
+		.unwrap();
+	window.show(SW_SHOW);
+	pump_thread();
+}
@@ -0,0 +1,246 @@
+/*!
+Drawing onto windows using GDI.
+!*/
+
+use std::ptr;
+use crate::winapi::*;
+use crate::window::Window;
+use crate::snap::{Color, Rect};
+use crate::error::ErrorCode;
+use crate::{Result, IntoInner};
+
+//----------------------------------------------------------------
+
+/// Drawing context.
+///
+/// Draws onto a device context with the currently selected pen and font.
+/// A device context obtained from a window is released on drop.
+#[derive(Debug)]
+pub struct Canvas {
+	hdc: HDC,
+	owner: Option<Window>,
+}
+impl Drop for Canvas {
+	fn drop(&mut self) {
+		if let Some(wnd) = self.owner {
+			unsafe {
+				ReleaseDC(wnd.into_inner(), self.hdc);
+			}
+		}
+	}
+}
+impl Canvas {
+	/// Create a drawing context for the client area of the window.
+	///
+	/// See [GetDC function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd144871.aspx) for more information.
+	pub fn new(wnd: Window) -> Result<Canvas> {
+		let hdc = unsafe { GetDC(wnd.into_inner()) };
+		if hdc.is_null() {
+			Err(ErrorCode::last())
+		}
+		else {
+			Ok(Canvas { hdc, owner: Some(wnd) })
+		}
+	}
+	/// Create a drawing context for an existing device context, eg. a memory DC.
+	///
+	/// # Safety
+	///
+	/// The device context is not released on drop and must outlive the canvas.
+	pub unsafe fn from_hdc(hdc: HDC) -> Canvas {
+		Canvas { hdc, owner: None }
+	}
+	/// Get the window drawn on, if the device context was obtained from a window.
+	pub fn window(&self) -> Option<Window> {
+		self.owner
+	}
+	/// Get the raw device context.
+	pub fn hdc(&self) -> HDC {
+		self.hdc
+	}
+	/// Draws a line from `(x0, y0)` up to but not including `(x1, y1)`.
+	///
+	/// See [LineTo function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd145029.aspx) for more information.
+	pub fn line(&self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color, width: i32) -> Result<()> {
+		let pen = GdiObject::pen(color, width)?;
+		let _pen = Selected::new(self.hdc, pen.0);
+		unsafe {
+			if MoveToEx(self.hdc, x0, y0, ptr::null_mut()) == FALSE || LineTo(self.hdc, x1, y1) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Draws the outline of a rectangle.
+	///
+	/// See [Polyline function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd162815.aspx) for more information.
+	pub fn rect_outline(&self, rect: &Rect, color: Color, width: i32) -> Result<()> {
+		let pen = GdiObject::pen(color, width)?;
+		let _pen = Selected::new(self.hdc, pen.0);
+		let right = rect.left + rect.width;
+		let bottom = rect.top + rect.height;
+		unsafe {
+			let points = [
+				POINT { x: rect.left, y: rect.top },
+				POINT { x: right, y: rect.top },
+				POINT { x: right, y: bottom },
+				POINT { x: rect.left, y: bottom },
+				POINT { x: rect.left, y: rect.top },
+			];
+			if Polyline(self.hdc, points.as_ptr(), points.len() as i32) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Fills a rectangle.
+	///
+	/// See [FillRect function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd162719.aspx) for more information.
+	pub fn rect_filled(&self, rect: &Rect, color: Color) -> Result<()> {
+		let brush = GdiObject::brush(color)?;
+		let rc = RECT { left: rect.left, top: rect.top, right: rect.left + rect.width, bottom: rect.top + rect.height };
+		unsafe {
+			if FillRect(self.hdc, &rc, brush.0 as HBRUSH) == 0 {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Draws the outline of a circle centered at `(x, y)`.
+	///
+	/// See [Ellipse function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd162510.aspx) for more information.
+	pub fn circle(&self, x: i32, y: i32, radius: i32, color: Color, width: i32) -> Result<()> {
+		let pen = GdiObject::pen(color, width)?;
+		let _pen = Selected::new(self.hdc, pen.0);
+		// Stock objects are not owned and must not be deleted
+		let _brush = Selected::new(self.hdc, unsafe { GetStockObject(NULL_BRUSH as i32) });
+		unsafe {
+			if Ellipse(self.hdc, x - radius, y - radius, x + radius + 1, y + radius + 1) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Draws text with its top left corner at `(x, y)` using the default font with the given height in pixels.
+	///
+	/// The text is drawn with a transparent background and without antialiasing, so it stays crisp on color keyed overlays.
+	///
+	/// See [TextOut function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd145133.aspx) for more information.
+	pub fn text(&self, x: i32, y: i32, text: &str, color: Color, font_size: i32) -> Result<()> {
+		let font = GdiObject::font(font_size)?;
+		let _font = Selected::new(self.hdc, font.0);
+		let text: Vec<u16> = text.encode_utf16().collect();
+		unsafe {
+			let old_color = SetTextColor(self.hdc, colorref(color));
+			let old_mode = SetBkMode(self.hdc, TRANSPARENT as i32);
+			let success = TextOutW(self.hdc, x, y, text.as_ptr(), text.len() as i32) != FALSE;
+			let err = ErrorCode::last();
+			SetBkMode(self.hdc, old_mode);
+			SetTextColor(self.hdc, old_color);
+			if success { Ok(()) } else { Err(err) }
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+fn colorref(color: Color) -> COLORREF {
+	color.red as COLORREF | (color.green as COLORREF) << 8 | (color.blue as COLORREF) << 16
+}
+
+// Owned GDI object, deleted on drop.
+// Every process is limited to 10000 GDI objects, leaking them on error or panic will eventually make all drawing fail.
+struct GdiObject(HGDIOBJ);
+impl GdiObject {
+	fn pen(color: Color, width: i32) -> Result<GdiObject> {
+		GdiObject::new(unsafe { CreatePen(PS_SOLID as i32, width, colorref(color)) as HGDIOBJ })
+	}
+	fn brush(color: Color) -> Result<GdiObject> {
+		GdiObject::new(unsafe { CreateSolidBrush(colorref(color)) as HGDIOBJ })
+	}
+	fn font(height: i32) -> Result<GdiObject> {
+		GdiObject::new(unsafe {
+			CreateFontW(
+				-height, 0, 0, 0, FW_NORMAL, FALSE as DWORD, FALSE as DWORD, FALSE as DWORD,
+				DEFAULT_CHARSET, OUT_DEFAULT_PRECIS, CLIP_DEFAULT_PRECIS, NONANTIALIASED_QUALITY, DEFAULT_PITCH | FF_DONTCARE,
+				ptr::null(),
+			) as HGDIOBJ
+		})
+	}
+	fn new(handle: HGDIOBJ) -> Result<GdiObject> {
+		if handle.is_null() {
+			Err(ErrorCode::last())
+		}
+		else {
+			Ok(GdiObject(handle))
+		}
+	}
+}
+impl Drop for GdiObject {
+	fn drop(&mut self) {
+		unsafe {
+			DeleteObject(self.0);
+		}
+	}
+}
+
+// Selects an object into the device context, restores the previous object on drop.
+struct Selected {
+	hdc: HDC,
+	old: HGDIOBJ,
+}
+impl Selected {
+	fn new(hdc: HDC, object: HGDIOBJ) -> Selected {
+		let old = unsafe { SelectObject(hdc, object) };
+		Selected { hdc, old }
+	}
+}
+impl Drop for Selected {
+	fn drop(&mut self) {
+		unsafe {
+			SelectObject(self.hdc, self.old);
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_no_gdi_leaks() {
+	extern "system" {
+		fn GetGuiResources(hProcess: HANDLE, uiFlags: DWORD) -> DWORD;
+	}
+	const GR_GDIOBJECTS: DWORD = 0;
+	unsafe {
+		let hdc = CreateCompatibleDC(ptr::null_mut());
+		let hbmp = CreateCompatibleBitmap(hdc, 64, 64);
+		let old = SelectObject(hdc, hbmp as HGDIOBJ);
+		let before = GetGuiResources(GetCurrentProcess(), GR_GDIOBJECTS);
+		let canvas = Canvas::from_hdc(hdc);
+		let color = Color { red: 255, ..Color::default() };
+		let rect = Rect { left: 4, top: 4, width: 20, height: 10 };
+		for _ in 0..1000 {
+			canvas.line(0, 0, 63, 63, color, 2).unwrap();
+			canvas.rect_outline(&rect, color, 1).unwrap();
+			canvas.rect_filled(&rect, color).unwrap();
+			canvas.circle(32, 32, 10, color, 1).unwrap();
+			canvas.text(0, 0, "Hello", color, 12).unwrap();
+		}
+		// The count is process wide and other tests create GDI objects in parallel,
+		// a leak creates at least one object per iteration which is well beyond what the other tests hold
+		let after = GetGuiResources(GetCurrentProcess(), GR_GDIOBJECTS);
+		assert!(after < before + 200, "GDI objects grew from {} to {}", before, after);
+		drop(canvas);
+		SelectObject(hdc, old);
+		DeleteObject(hbmp as HGDIOBJ);
+		DeleteDC(hdc);
+	}
+}
@@ -2,7 +2,7 @@
 Windows error codes.
 !*/
 
-use std::{fmt, error};
+use std::{fmt, error, ptr};
 use crate::winapi::*;
 
 /// Windows error code.
@@ -25,6 +25,29 @@ impl ErrorCode {
 	pub fn last() -> ErrorCode {
 		ErrorCode(unsafe { GetLastError() })
 	}
+	/// Converts an `NTSTATUS` code to its equivalent system error code.
+	///
+	/// See [RtlNtStatusToDosError function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms680600.aspx) for more information.
+	pub fn from_nt(status: NTSTATUS) -> ErrorCode {
+		ErrorCode(unsafe { RtlNtStatusToDosError(status) })
+	}
+}
+impl ErrorCode {
+	/// Returns the system message describing this error code, eg. `"Access is denied."`.
+	///
+	/// Returns an empty string if the system has no message for the error code.
+	///
+	/// See [FormatMessage function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms679351.aspx) for more information.
+	pub fn message(self) -> String {
+		let mut buf = [0u16; 512];
+		let len = unsafe {
+			FormatMessageW(
+				FORMAT_MESSAGE_FROM_SYSTEM | FORMAT_MESSAGE_IGNORE_INSERTS,
+				ptr::null(), self.0, 0,
+				buf.as_mut_ptr(), buf.len() as DWORD, ptr::null_mut())
+		};
+		String::from_utf16_lossy(&buf[..len as usize]).trim_end().to_string()
+	}
 }
 impl fmt::Display for ErrorCode {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -41,3 +64,9 @@ impl error::Error for ErrorCode {
 		"system error code"
 	}
 }
+
+#[test]
+fn test_message() {
+	assert!(!ErrorCode::from(ERROR_ACCESS_DENIED).message().is_empty());
+	assert!(!ErrorCode::from(ERROR_HOOK_NEEDS_HMOD).message().ends_with('\n'));
+}
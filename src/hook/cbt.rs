@@ -0,0 +1,111 @@
+/*!
+Computer-based training hook details.
+!*/
+
+use std::{ptr, fmt};
+use crate::winapi::*;
+use crate::window::Window;
+use crate::FromInner;
+use super::HookContext;
+
+//----------------------------------------------------------------
+
+/// Decoded computer-based training hook event.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CbtEvent {
+	/// A window is about to be created, `HCBT_CREATEWND`.
+	CreateWnd { window: Window },
+	/// A window is about to be activated, `HCBT_ACTIVATE`.
+	Activate { window: Window, mouse: bool, previous: Option<Window> },
+	/// A window is about to be destroyed, `HCBT_DESTROYWND`.
+	DestroyWnd { window: Window },
+	/// A window is about to be minimized or maximized, `HCBT_MINMAX`.
+	MinMax { window: Window, show_cmd: i32 },
+	/// A window is about to receive the keyboard focus, `HCBT_SETFOCUS`.
+	SetFocus { window: Window, previous: Option<Window> },
+	/// Any other event with its raw parameters.
+	Other { code: i32, wparam: usize, lparam: isize },
+}
+
+/// Computer-based training hook callback context.
+///
+/// Called before windows are created, activated, destroyed, minimized, maximized, moved, sized or focused on the hooked thread.
+/// Use [`prevent`](Cbt::prevent) to stop the operation from happening.
+///
+/// See documentation for
+/// [CBTProc](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644977.aspx)
+/// for more information.
+#[repr(C)]
+pub struct Cbt {
+	code: c_int,
+	w_param: WPARAM,
+	l_param: LPARAM,
+	prevent: bool,
+}
+impl Cbt {
+	/// Prevents the operation, eg. the window is not created or activated.
+	pub fn prevent(&mut self) {
+		self.prevent = true;
+	}
+	pub fn code(&self) -> i32 {
+		self.code
+	}
+	pub fn event(&self) -> CbtEvent {
+		let window = |hwnd: WPARAM| unsafe { Window::from_inner(hwnd as HWND) };
+		let optional = |hwnd: HWND| if hwnd.is_null() { None } else { Some(unsafe { Window::from_inner(hwnd) }) };
+		match self.code {
+			HCBT_CREATEWND => CbtEvent::CreateWnd { window: window(self.w_param) },
+			HCBT_ACTIVATE => {
+				let info = unsafe { &*(self.l_param as *const CBTACTIVATESTRUCT) };
+				CbtEvent::Activate { window: window(self.w_param), mouse: info.fMouse != FALSE, previous: optional(info.hWndActive) }
+			},
+			HCBT_DESTROYWND => CbtEvent::DestroyWnd { window: window(self.w_param) },
+			HCBT_MINMAX => CbtEvent::MinMax { window: window(self.w_param), show_cmd: self.l_param as i32 },
+			HCBT_SETFOCUS => CbtEvent::SetFocus { window: window(self.w_param), previous: optional(self.l_param as HWND) },
+			code => CbtEvent::Other { code, wparam: self.w_param, lparam: self.l_param },
+		}
+	}
+}
+impl fmt::Debug for Cbt {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Cbt")
+			.field("event", &self.event())
+			.field("prevent", &self.prevent)
+			.finish()
+	}
+}
+unsafe impl HookContext for Cbt {
+	fn hook_type() -> c_int {
+		WH_CBT
+	}
+	unsafe fn from_raw(code: c_int, w_param: WPARAM, l_param: LPARAM) -> Self {
+		Cbt { code, w_param, l_param, prevent: false }
+	}
+	unsafe fn call_next_hook(&self) -> LRESULT {
+		if self.prevent {
+			1
+		}
+		else {
+			CallNextHookEx(ptr::null_mut(), self.code, self.w_param, self.l_param)
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_cbt_prevent_create() {
+	use crate::thread::ThreadId;
+	use crate::wndclass::WindowBuilder;
+	windows_hook! {
+		fn my_hook(context: &mut Cbt) for thread {
+			if let CbtEvent::CreateWnd { .. } = context.event() {
+				context.prevent();
+			}
+		}
+	}
+	let hook = my_hook(ThreadId::current()).unwrap();
+	assert!(WindowBuilder::new().create().is_err());
+	drop(hook);
+	WindowBuilder::new().create().unwrap().destroy().unwrap();
+}
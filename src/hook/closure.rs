@@ -0,0 +1,255 @@
+/*!
+Closure based hook registration.
+!*/
+
+use std::{fmt, panic};
+use std::cell::RefCell;
+use std::sync::mpsc;
+use std::marker::PhantomData;
+use std::thread::LocalKey;
+use crate::error::ErrorCode;
+use super::{Hook, KeyboardEvent, KeyboardLL, MouseEvent, MouseLL, WindowsHook};
+
+//----------------------------------------------------------------
+
+type Callback<C> = Box<dyn FnMut(&mut C)>;
+
+struct Registry<C> {
+	hook: Option<Hook>,
+	next_id: usize,
+	callbacks: Vec<(usize, Option<Callback<C>>)>,
+}
+impl<C> Registry<C> {
+	const fn new() -> Registry<C> {
+		Registry { hook: None, next_id: 0, callbacks: Vec::new() }
+	}
+}
+
+/// Low level hooks which dispatch to the closures registered on the current thread.
+trait ClosureHook: WindowsHook {
+	fn registry() -> &'static LocalKey<RefCell<Registry<Self::Context>>>;
+}
+
+thread_local! {
+	static KEYBOARD_LL: RefCell<Registry<KeyboardLL>> = const { RefCell::new(Registry::new()) };
+	static MOUSE_LL: RefCell<Registry<MouseLL>> = const { RefCell::new(Registry::new()) };
+}
+
+enum KeyboardLLClosures {}
+impl WindowsHook for KeyboardLLClosures {
+	type Context = KeyboardLL;
+	fn invoke(context: &mut KeyboardLL) {
+		dispatch::<Self>(context)
+	}
+}
+impl ClosureHook for KeyboardLLClosures {
+	fn registry() -> &'static LocalKey<RefCell<Registry<KeyboardLL>>> {
+		&KEYBOARD_LL
+	}
+}
+
+enum MouseLLClosures {}
+impl WindowsHook for MouseLLClosures {
+	type Context = MouseLL;
+	fn invoke(context: &mut MouseLL) {
+		dispatch::<Self>(context)
+	}
+}
+impl ClosureHook for MouseLLClosures {
+	fn registry() -> &'static LocalKey<RefCell<Registry<MouseLL>>> {
+		&MOUSE_LL
+	}
+}
+
+fn dispatch<H: ClosureHook>(context: &mut H::Context) where H::Context: 'static {
+	let mut index = 0;
+	loop {
+		// Take the closure out of the registry while calling it, allowing it to register or unregister hooks
+		let (id, mut f) = match H::registry().with(|registry| {
+			let mut registry = registry.borrow_mut();
+			registry.callbacks.get_mut(index).map(|(id, f)| (*id, f.take()))
+		}) {
+			Some((id, Some(f))) => (id, f),
+			Some((_, None)) => { index += 1; continue; },
+			None => break,
+		};
+		let result = panic::catch_unwind(panic::AssertUnwindSafe(|| f(context)));
+		// Put it back unless it was unregistered in the meantime
+		let f = H::registry().with(|registry| {
+			let mut registry = registry.borrow_mut();
+			match registry.callbacks.iter().position(|&(other, _)| other == id) {
+				Some(position) => {
+					registry.callbacks[position].1 = Some(f);
+					index = position + 1;
+					None
+				},
+				None => Some(f),
+			}
+		});
+		drop(f);
+		if let Err(payload) = result {
+			panic::resume_unwind(payload);
+		}
+	}
+}
+
+fn register<H: ClosureHook>(f: Callback<H::Context>) -> Result<HookGuard, ErrorCode> where H::Context: 'static {
+	H::registry().with(|registry| {
+		let mut registry = registry.borrow_mut();
+		if registry.hook.is_none() {
+			registry.hook = Some(H::register()?);
+		}
+		let id = registry.next_id;
+		registry.next_id += 1;
+		registry.callbacks.push((id, Some(f)));
+		Ok(HookGuard { id, unregister: unregister::<H>, _marker: PhantomData })
+	})
+}
+
+fn unregister<H: ClosureHook>(id: usize) where H::Context: 'static {
+	let (f, hook) = H::registry().with(|registry| {
+		let mut registry = registry.borrow_mut();
+		let f = registry.callbacks.iter().position(|&(other, _)| other == id).map(|position| registry.callbacks.remove(position));
+		let hook = if registry.callbacks.is_empty() { registry.hook.take() } else { None };
+		(f, hook)
+	});
+	// Drop the closure and unhook outside of the borrow
+	drop(f);
+	drop(hook);
+}
+
+//----------------------------------------------------------------
+
+/// Closure hook registration.
+///
+/// Unregisters the closure when this instance goes out of scope, the hook is unhooked after its last closure is unregistered.
+/// Must be dropped on the thread which registered it.
+pub struct HookGuard {
+	id: usize,
+	unregister: fn(usize),
+	_marker: PhantomData<*const ()>,
+}
+impl Drop for HookGuard {
+	fn drop(&mut self) {
+		(self.unregister)(self.id);
+	}
+}
+impl fmt::Debug for HookGuard {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("HookGuard")
+			.field("id", &self.id)
+			.finish()
+	}
+}
+
+/// Registers a closure as low level keyboard hook.
+///
+/// The closures registered on the same thread stack: they share a single hook and are all called in registration order.
+/// Any closure may cancel the event, the closures after it are still called.
+///
+/// The registering thread must pump messages, see the [hook module](index.html)'s documentation.
+pub fn keyboard_ll<F>(f: F) -> Result<HookGuard, ErrorCode> where F: FnMut(&mut KeyboardLL) + 'static {
+	register::<KeyboardLLClosures>(Box::new(f))
+}
+
+/// Registers a closure as low level mouse hook.
+///
+/// The closures registered on the same thread stack: they share a single hook and are all called in registration order.
+/// Any closure may cancel the event, the closures after it are still called.
+///
+/// The registering thread must pump messages, see the [hook module](index.html)'s documentation.
+pub fn mouse_ll<F>(f: F) -> Result<HookGuard, ErrorCode> where F: FnMut(&mut MouseLL) + 'static {
+	register::<MouseLLClosures>(Box::new(f))
+}
+
+/// Registers a low level keyboard hook which sends a copy of every event over a channel.
+///
+/// The hook only copies the event and returns immediately, do the actual work on the receiving end.
+/// Low level hooks which take too long are silently removed by the system.
+/// The events cannot be modified or cancelled this way.
+///
+/// The registering thread must keep pumping messages while the events are received elsewhere.
+/// Dropping the guard unregisters the hook and closes the channel, the receiver still gets the events sent before.
+pub fn keyboard_ll_channel() -> Result<(HookGuard, mpsc::Receiver<KeyboardEvent>), ErrorCode> {
+	let (tx, rx) = mpsc::channel();
+	let guard = keyboard_ll(move |context| {
+		let _ = tx.send(context.to_event());
+	})?;
+	Ok((guard, rx))
+}
+
+/// Registers a low level mouse hook which sends a copy of every event over a channel.
+///
+/// See [`keyboard_ll_channel`] for more information.
+pub fn mouse_ll_channel() -> Result<(HookGuard, mpsc::Receiver<MouseEvent>), ErrorCode> {
+	let (tx, rx) = mpsc::channel();
+	let guard = mouse_ll(move |context| {
+		let _ = tx.send(context.to_event());
+	})?;
+	Ok((guard, rx))
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_keyboard_ll_stack() {
+	use std::{mem, rc::Rc};
+	use std::cell::Cell;
+	use crate::winapi::*;
+
+	let calls = Rc::new(Cell::new(0));
+	let first = keyboard_ll({ let calls = calls.clone(); move |_| calls.set(calls.get() + 1) }).unwrap();
+	let second = keyboard_ll({ let calls = calls.clone(); move |_| calls.set(calls.get() + 10) }).unwrap();
+	KEYBOARD_LL.with(|registry| assert!(registry.borrow().hook.is_some()));
+	// Keep dispatching the input of the system while the global hook is installed
+	crate::wndclass::pump_once();
+
+	let mut info: KBDLLHOOKSTRUCT = unsafe { mem::zeroed() };
+	info.vkCode = VK_F24 as DWORD;
+	let fire = |info: &mut KBDLLHOOKSTRUCT| unsafe {
+		<KeyboardLLClosures as WindowsHook>::thunk(0, WM_KEYDOWN as WPARAM, info as *mut _ as LPARAM);
+	};
+	let base = calls.get();
+	fire(&mut info);
+	assert_eq!(calls.get(), base + 11);
+	crate::wndclass::pump_once();
+
+	drop(first);
+	let base = calls.get();
+	fire(&mut info);
+	assert_eq!(calls.get(), base + 10);
+	crate::wndclass::pump_once();
+
+	drop(second);
+	KEYBOARD_LL.with(|registry| {
+		let registry = registry.borrow();
+		assert!(registry.hook.is_none());
+		assert!(registry.callbacks.is_empty());
+	});
+}
+
+#[test]
+fn test_mouse_ll_channel() {
+	use std::mem;
+	use crate::winapi::*;
+	use super::MouseData;
+
+	let (guard, events) = mouse_ll_channel().unwrap();
+	// Keep dispatching the input of the system while the global hook is installed
+	crate::wndclass::pump_once();
+	let mut info: MSLLHOOKSTRUCT = unsafe { mem::zeroed() };
+	info.pt.x = 12;
+	info.mouseData = (120 << 16) as DWORD;
+	// Tells the fake event apart from real mouse input
+	info.dwExtraInfo = 0x4d4c4c;
+	unsafe {
+		<MouseLLClosures as WindowsHook>::thunk(0, WM_MOUSEWHEEL as WPARAM, &mut info as *mut _ as LPARAM);
+	}
+	crate::wndclass::pump_once();
+	drop(guard);
+	let events: Vec<MouseEvent> = std::thread::spawn(move || events.iter().collect()).join().unwrap();
+	let events: Vec<&MouseEvent> = events.iter().filter(|event| event.extra_info() == 0x4d4c4c).collect();
+	assert_eq!(events.len(), 1);
+	assert_eq!(events[0].pt_x(), 12);
+	assert_eq!(events[0].mouse_data(), MouseData::Wheel(120));
+}
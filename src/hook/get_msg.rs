@@ -0,0 +1,124 @@
+/*!
+Get message hook details.
+!*/
+
+use std::{ptr, fmt};
+use crate::winapi::*;
+use crate::window::Window;
+use crate::{FromInner, IntoInner};
+use super::HookContext;
+
+//----------------------------------------------------------------
+
+/// Get message hook callback context.
+///
+/// Called whenever a message is about to be returned from `GetMessage` or `PeekMessage` on the hooked thread.
+/// The message can be modified before it is returned, use [`cancel`](GetMsg::cancel) to eat it.
+///
+/// See documentation for
+/// [GetMsgProc](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644981.aspx)
+/// and
+/// [MSG](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644958.aspx)
+/// for more information.
+#[repr(C)]
+pub struct GetMsg {
+	code: c_int,
+	remove: WPARAM,
+	msg: *mut MSG,
+}
+impl GetMsg {
+	/// Eats the message by replacing it with `WM_NULL`.
+	pub fn cancel(&mut self) {
+		self.msg_mut().message = WM_NULL;
+	}
+
+	fn msg_mut(&mut self) -> &mut MSG {
+		unsafe { &mut *self.msg }
+	}
+	fn msg(&self) -> &MSG {
+		unsafe { &*self.msg }
+	}
+
+	/// Returns if the message is being removed from the queue, `false` if it is only peeked at with `PM_NOREMOVE`.
+	pub fn removed(&self) -> bool {
+		self.remove == PM_REMOVE as WPARAM
+	}
+	pub fn window(&self) -> Window {
+		unsafe { Window::from_inner(self.msg().hwnd) }
+	}
+	pub fn set_window(&mut self, window: Window) {
+		self.msg_mut().hwnd = window.into_inner();
+	}
+	pub fn message(&self) -> u32 {
+		self.msg().message
+	}
+	pub fn set_message(&mut self, message: u32) {
+		self.msg_mut().message = message;
+	}
+	pub fn wparam(&self) -> usize {
+		self.msg().wParam
+	}
+	pub fn set_wparam(&mut self, wparam: usize) {
+		self.msg_mut().wParam = wparam;
+	}
+	pub fn lparam(&self) -> isize {
+		self.msg().lParam
+	}
+	pub fn set_lparam(&mut self, lparam: isize) {
+		self.msg_mut().lParam = lparam;
+	}
+	pub fn time(&self) -> u32 {
+		self.msg().time
+	}
+	pub fn pt(&self) -> (i32, i32) {
+		(self.msg().pt.x, self.msg().pt.y)
+	}
+}
+impl fmt::Debug for GetMsg {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("GetMsg")
+			.field("removed", &self.removed())
+			.field("window", &self.window())
+			.field("message", &format_args!("{:#x}", self.message()))
+			.field("wparam", &format_args!("{:#x}", self.wparam()))
+			.field("lparam", &format_args!("{:#x}", self.lparam()))
+			.field("time", &self.time())
+			.field("pt", &self.pt())
+			.finish()
+	}
+}
+unsafe impl HookContext for GetMsg {
+	fn hook_type() -> c_int {
+		WH_GETMESSAGE
+	}
+	unsafe fn from_raw(code: c_int, w_param: WPARAM, l_param: LPARAM) -> Self {
+		GetMsg { code, remove: w_param, msg: l_param as *mut MSG }
+	}
+	unsafe fn call_next_hook(&self) -> LRESULT {
+		CallNextHookEx(ptr::null_mut(), self.code, self.remove, self.msg as LPARAM)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_get_msg() {
+	use crate::thread::ThreadId;
+	use crate::wndclass::pump_once;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	static SEEN: AtomicUsize = AtomicUsize::new(0);
+	windows_hook! {
+		fn my_hook(context: &mut GetMsg) for thread {
+			if context.message() == WM_USER && context.removed() {
+				SEEN.store(context.wparam(), Ordering::Relaxed);
+				context.cancel();
+			}
+		}
+	}
+	let hook = my_hook(ThreadId::current()).unwrap();
+	unsafe { PostThreadMessageW(GetCurrentThreadId(), WM_USER, 42, 0); }
+	pump_once();
+	let seen = SEEN.load(Ordering::Relaxed);
+	assert_eq!(seen, 42);
+	drop(hook);
+}
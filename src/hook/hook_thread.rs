@@ -0,0 +1,103 @@
+/*!
+Dedicated hook thread.
+!*/
+
+use std::{mem, panic, ptr, thread};
+use std::sync::mpsc;
+use crate::winapi::*;
+use crate::error::ErrorCode;
+use crate::thread::ThreadId;
+use crate::IntoInner;
+use super::Hook;
+
+/// Hook registered on a dedicated thread pumping its messages.
+///
+/// Created by [`spawn_hook_thread`].
+/// Dropping it stops the message loop, unhooks the hook on its thread and joins the thread.
+#[derive(Debug)]
+pub struct HookThread {
+	thread_id: ThreadId,
+	handle: Option<thread::JoinHandle<()>>,
+}
+impl HookThread {
+	/// Returns the id of the hook thread.
+	pub fn thread_id(&self) -> ThreadId {
+		self.thread_id
+	}
+}
+impl Drop for HookThread {
+	fn drop(&mut self) {
+		unsafe {
+			PostThreadMessageW(self.thread_id.into_inner(), WM_QUIT, 0, 0);
+		}
+		if let Some(handle) = self.handle.take() {
+			let _ = handle.join();
+		}
+	}
+}
+
+/// Spawns a thread which registers a hook and pumps messages until the returned [`HookThread`] is dropped.
+///
+/// The low level hooks are invoked on the thread which registered them and require it to pump messages,
+/// this frees the calling thread from doing so. Communicate with the hook callbacks through global state or a channel.
+///
+/// Waits for the registration to complete, its error is returned here.
+/// A panic in the registration closure is resumed on the calling thread.
+///
+/// ```no_run
+/// # #[macro_use] extern crate external; fn main() {
+/// windows_hook! {
+///     fn my_hook(context: &mut external::hook::KeyboardLL) {
+///         println!("{:?}", context);
+///     }
+/// }
+/// let hook_thread = external::hook::spawn_hook_thread(my_hook).unwrap();
+/// # drop(hook_thread); }
+/// ```
+pub fn spawn_hook_thread<F>(register: F) -> Result<HookThread, ErrorCode> where F: FnOnce() -> Result<Hook, ErrorCode> + Send + 'static {
+	let (tx, rx) = mpsc::channel();
+	let handle = thread::spawn(move || {
+		unsafe {
+			// Create the message queue before reporting back so the quit message can be posted
+			let mut msg: MSG = mem::zeroed();
+			PeekMessageW(&mut msg, ptr::null_mut(), WM_USER, WM_USER, PM_NOREMOVE);
+		}
+		let hook = match register() {
+			Ok(hook) => hook,
+			Err(err) => {
+				let _ = tx.send(Err(err));
+				return;
+			},
+		};
+		let _ = tx.send(Ok(ThreadId::current()));
+		crate::wndclass::run();
+		drop(hook);
+	});
+	match rx.recv() {
+		Ok(Ok(thread_id)) => Ok(HookThread { thread_id, handle: Some(handle) }),
+		Ok(Err(err)) => {
+			let _ = handle.join();
+			Err(err)
+		},
+		// The registration panicked
+		Err(_) => match handle.join() {
+			Err(payload) => panic::resume_unwind(payload),
+			Ok(()) => unreachable!(),
+		},
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_spawn_hook_thread() {
+	windows_hook! {
+		fn my_hook(_context: &mut super::MouseLL) {}
+	}
+	let hook_thread = spawn_hook_thread(my_hook).unwrap();
+	assert_ne!(hook_thread.thread_id(), ThreadId::current());
+	drop(hook_thread);
+
+	let err = spawn_hook_thread(|| Err(ErrorCode::from(ERROR_ACCESS_DENIED))).unwrap_err();
+	assert_eq!(err, ErrorCode::from(ERROR_ACCESS_DENIED));
+}
@@ -3,12 +3,18 @@ Low level keyboard hook details.
 !*/
 
 use std::{ptr, fmt};
+use std::cell::Cell;
 use crate::winapi::*;
-use crate::vk::VirtualKey;
+use crate::vk::{Modifiers, VirtualKey};
 use super::HookContext;
 
 //----------------------------------------------------------------
 
+thread_local! {
+	// Modifier keys held down as seen by the low level keyboard hooks on this thread.
+	static MODIFIERS: Cell<Modifiers> = const { Cell::new(Modifiers::NONE) };
+}
+
 /// Low level keyboard hook callback context.
 ///
 /// See documentation for
@@ -22,6 +28,7 @@ pub struct KeyboardLL {
 	message: u32,
 	info: *mut KBDLLHOOKSTRUCT,
 	result: LRESULT,
+	modifiers: Modifiers,
 }
 impl KeyboardLL {
 	pub fn cancel(&mut self) {
@@ -99,6 +106,28 @@ impl KeyboardLL {
 	pub fn clear_up(&mut self) {
 		self.info_mut().flags &= !0x80;
 	}
+	/// Returns if this is a key press, the opposite of [`up`](KeyboardLL::up).
+	pub fn is_down(&self) -> bool {
+		!self.up()
+	}
+	/// Returns if this is a key release.
+	pub fn is_up(&self) -> bool {
+		self.up()
+	}
+	/// Returns if this is a key press of the given key with exactly the given modifiers held.
+	///
+	/// See [`Modifiers::matches`] for how the modifiers are matched.
+	pub fn matches(&self, vk: VirtualKey, modifiers: Modifiers) -> bool {
+		self.is_down() && self.vk_code() == vk && self.modifiers.matches(modifiers)
+	}
+	/// Returns the modifier keys held down, including this event.
+	///
+	/// The state is tracked from the previous events seen by the low level keyboard hooks on this thread,
+	/// this avoids calling `GetKeyState` or `GetAsyncKeyState` from within the hook.
+	/// Modifiers held down before the hook was registered are not known until they are released.
+	pub fn modifiers(&self) -> Modifiers {
+		self.modifiers
+	}
 	pub fn time(&self) -> u32 {
 		self.info().time as u32
 	}
@@ -106,12 +135,22 @@ impl KeyboardLL {
 		self.info_mut().time = time;
 	}
 
+	/// Returns if this event was injected with the given tag as its extra info.
+	///
+	/// Tag your own input with [`VirtualKey::down_tagged`](crate::vk::VirtualKey::down_tagged) to recognize and ignore it here.
+	pub fn is_self_injected_by(&self, tag: usize) -> bool {
+		self.injected() && self.info().dwExtraInfo == tag
+	}
 	pub unsafe fn extra_info<T>(&self) -> Option<&T> {
 		(self.info().dwExtraInfo as *const T).as_ref()
 	}
 	pub unsafe fn extra_info_mut<T>(&mut self) -> Option<&mut T> {
 		(self.info().dwExtraInfo as *mut T).as_mut()
 	}
+	/// Copies the event out of the hook.
+	pub fn to_event(&self) -> KeyboardEvent {
+		KeyboardEvent { message: self.message, info: *self.info(), modifiers: self.modifiers }
+	}
 }
 impl fmt::Debug for KeyboardLL {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -124,11 +163,103 @@ impl fmt::Debug for KeyboardLL {
 			.field("injected", &self.injected())
 			.field("altdown", &self.altdown())
 			.field("up", &self.up())
+			.field("modifiers", &self.modifiers())
 			.field("time", &self.time())
 			.field("dwExtraInfo", &(self.info().dwExtraInfo as *const ()))
 			.finish()
 	}
 }
+
+//----------------------------------------------------------------
+
+/// Owned copy of a low level keyboard event.
+///
+/// Unlike [`KeyboardLL`] it can be kept after the hook returns and sent to other threads.
+#[derive(Copy, Clone)]
+pub struct KeyboardEvent {
+	message: u32,
+	info: KBDLLHOOKSTRUCT,
+	modifiers: Modifiers,
+}
+impl KeyboardEvent {
+	pub fn message(&self) -> u32 {
+		self.message
+	}
+	pub fn vk_code(&self) -> VirtualKey {
+		self.info.vkCode.into()
+	}
+	pub fn scan_code(&self) -> u32 {
+		self.info.scanCode
+	}
+	pub fn extended(&self) -> bool {
+		(self.info.flags & 0x01) != 0
+	}
+	pub fn lower_il_injected(&self) -> bool {
+		(self.info.flags & 0x02) != 0
+	}
+	pub fn injected(&self) -> bool {
+		(self.info.flags & 0x10) != 0
+	}
+	pub fn altdown(&self) -> bool {
+		(self.info.flags & 0x20) != 0
+	}
+	pub fn up(&self) -> bool {
+		(self.info.flags & 0x80) != 0
+	}
+	/// Returns if this is a key press.
+	pub fn is_down(&self) -> bool {
+		!self.up()
+	}
+	/// Returns if this is a key release.
+	pub fn is_up(&self) -> bool {
+		self.up()
+	}
+	/// Returns if this is a key press of the given key with exactly the given modifiers held.
+	///
+	/// See [`Modifiers::matches`] for how the modifiers are matched.
+	pub fn matches(&self, vk: VirtualKey, modifiers: Modifiers) -> bool {
+		self.is_down() && self.vk_code() == vk && self.modifiers.matches(modifiers)
+	}
+	/// Returns the modifier keys held down, including this event.
+	///
+	/// See [`KeyboardLL::modifiers`] for more information.
+	pub fn modifiers(&self) -> Modifiers {
+		self.modifiers
+	}
+	pub fn time(&self) -> u32 {
+		self.info.time
+	}
+	/// Returns if this event was injected with the given tag as its extra info.
+	pub fn is_self_injected_by(&self, tag: usize) -> bool {
+		self.injected() && self.info.dwExtraInfo == tag
+	}
+	/// Returns the raw extra info.
+	///
+	/// Pointers stored in the extra info may no longer be valid after the hook returns.
+	pub fn extra_info(&self) -> usize {
+		self.info.dwExtraInfo
+	}
+}
+impl fmt::Debug for KeyboardEvent {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("KeyboardEvent")
+			.field("message", &self.message())
+			.field("vk_code", &self.vk_code())
+			.field("scan_code", &self.scan_code())
+			.field("extended", &self.extended())
+			.field("lower_il_injected", &self.lower_il_injected())
+			.field("injected", &self.injected())
+			.field("altdown", &self.altdown())
+			.field("up", &self.up())
+			.field("modifiers", &self.modifiers())
+			.field("time", &self.time())
+			.field("dwExtraInfo", &(self.info.dwExtraInfo as *const ()))
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
 unsafe impl HookContext for KeyboardLL {
 	fn hook_type() -> c_int {
 		WH_KEYBOARD_LL
@@ -136,7 +267,13 @@ unsafe impl HookContext for KeyboardLL {
 	unsafe fn from_raw(code: c_int, w_param: WPARAM, l_param: LPARAM) -> Self {
 		let message = w_param as u32;
 		let info = l_param as *mut KBDLLHOOKSTRUCT;
-		KeyboardLL { code, message, info, result: 0 }
+		let modifiers = MODIFIERS.with(|modifiers| {
+			let key = Modifiers::from_vk(VirtualKey::from((*info).vkCode));
+			let state = if (*info).flags & 0x80 != 0 { modifiers.get() & !key } else { modifiers.get() | key };
+			modifiers.set(state);
+			state
+		});
+		KeyboardLL { code, message, info, result: 0, modifiers }
 	}
 	unsafe fn call_next_hook(&self) -> LRESULT {
 		if self.result != 0 {
@@ -155,7 +292,9 @@ unsafe impl HookContext for KeyboardLL {
 #[cfg(test)]
 mod tests {
 	use crate::wndclass::{pump_once};
-	use crate::vk::{VirtualKey};
+	use crate::winapi::*;
+	use crate::vk::{Modifiers, VirtualKey};
+	use super::{HookContext, KeyboardLL};
 
 	#[test]
 	fn test_keyboard_ll() {
@@ -175,4 +314,22 @@ mod tests {
 		unsafe { assert_eq!(PRESSED, true); }
 		drop(hook);
 	}
+
+	#[test]
+	fn test_matches() {
+		let mut info: KBDLLHOOKSTRUCT = unsafe { std::mem::zeroed() };
+		let mut event = |vk: VirtualKey, up: bool| {
+			info.vkCode = vk.into();
+			info.flags = if up { 0x80 } else { 0 };
+			unsafe { KeyboardLL::from_raw(0, WM_KEYDOWN as WPARAM, &mut info as *mut _ as LPARAM) }.to_event()
+		};
+		event(VirtualKey::CTRL, false);
+		let press = event(VirtualKey::F1, false);
+		assert!(press.is_down() && press.matches(VirtualKey::F1, Modifiers::CTRL));
+		assert!(!press.matches(VirtualKey::F1, Modifiers::CTRL | Modifiers::SHIFT));
+		let release = event(VirtualKey::F1, true);
+		assert!(release.is_up() && !release.matches(VirtualKey::F1, Modifiers::CTRL));
+		event(VirtualKey::CTRL, true);
+		assert!(event(VirtualKey::F1, false).matches(VirtualKey::F1, Modifiers::NONE));
+	}
 }
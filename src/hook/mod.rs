@@ -15,16 +15,19 @@ This complexity is needed due to the lack of context pointer making the `Fn*` tr
 ```
 # #[macro_use] extern crate external; fn main() {
 windows_hook! {
-	/// A function with the given name which takes no arguments is created.
-	/// This function registers the hook and returns the registration result.
-	/// Doc comments, other attributes and optional `pub` will be applied to this function.
-	///
-	/// The callback type is defined by the argument identifier:
-	/// * `KeyboardLL` means this is a low level keyboard hook.
-	/// * `MouseLL` means this is a low level mouse hook.
-	pub fn my_hook(context: &mut external::hook::KeyboardLL) {
-		println!("{:?}", context);
-	}
+    /// A function with the given name which takes no arguments is created.
+    /// This function registers the hook and returns the registration result.
+    /// Doc comments, other attributes and optional `pub` will be applied to this function.
+    ///
+    /// The callback type is defined by the argument identifier:
+    /// * `KeyboardLL` means this is a low level keyboard hook.
+    /// * `MouseLL` means this is a low level mouse hook.
+    /// * `GetMsg` means this is a get message hook.
+    /// * `Cbt` means this is a computer-based training hook.
+    /// * `Shell` means this is a shell hook.
+    pub fn my_hook(context: &mut external::hook::KeyboardLL) {
+        println!("{:?}", context);
+    }
 }
 # }
 ```
@@ -46,11 +49,79 @@ pub fn my_hook() -> Result<external::hook::Hook, external::error::ErrorCode> {
 ```
 
 Register the hook by simply calling the defined function and unwrapping it.
+
+# Closures
+
+The low level hooks can also be registered with a closure using [`keyboard_ll`] and [`mouse_ll`].
+The closure is kept in thread local storage, it is called on the registering thread and does not need to be `Send`.
+Use [`keyboard_ll_channel`] and [`mouse_ll_channel`] to receive copies of the events on another thread instead.
+
+```no_run
+let hook = external::hook::keyboard_ll(|context| {
+	println!("{:?}", context);
+}).unwrap();
+external::wndclass::run();
+# drop(hook);
+```
+
+# Thread hooks
+
+Follow the argument list with `for thread` to hook a specific thread instead, the defined function then takes the `ThreadId` to hook.
+Low level hooks are always global, registering them for a thread fails with `ERROR_GLOBAL_ONLY_HOOK`.
+
+```
+# #[macro_use] extern crate external; fn main() {
+windows_hook! {
+    pub fn my_thread_hook(context: &mut external::hook::GetMsg) for thread {
+        println!("{:?}", context);
+    }
+}
+# }
+```
+
+# Message pump
+
+The low level hooks are called on the thread which registered them, this thread must pump messages or the callbacks are never invoked.
+Use [`run`](crate::wndclass::run), [`pump_until`](crate::wndclass::pump_until) or [`pump_while`](crate::wndclass::pump_while) which sleep until a message arrives.
+When the thread is busy otherwise, use [`spawn_hook_thread`] to register the hook on a dedicated thread.
+
+# Panics
+
+A panic in a callback is caught before it unwinds into the system and the event is still passed on to the next hook.
+Retrieve the panic with [`take_panic`] after pumping messages.
+
+# Ignoring your own input
+
+A tool which both injects input and hooks it will see its own events come back through the hook.
+Tag the injected input with a value of your choosing and skip the events carrying that tag to avoid feedback loops.
+
+```
+# #[macro_use] extern crate external; fn main() {
+use external::vk::VirtualKey;
+
+const MY_TAG: usize = 0x7A6;
+
+windows_hook! {
+    pub fn my_hook(context: &mut external::hook::KeyboardLL) {
+        if context.is_self_injected_by(MY_TAG) {
+            return;
+        }
+        if context.vk_code() == VirtualKey::F1 && !context.up() {
+            VirtualKey::F2.down_tagged(MY_TAG);
+            VirtualKey::F2.up_tagged(MY_TAG);
+        }
+    }
+}
+# }
+```
 !*/
 
-use std::{ptr};
+use std::{any, panic, ptr};
+use std::cell::RefCell;
 use crate::error::ErrorCode;
+use crate::thread::{Thread, ThreadId, ThreadRights};
 use crate::winapi::*;
+use crate::IntoInner;
 
 pub unsafe trait HookContext: Sized {
 	/// The windows idHook type.
@@ -73,24 +144,77 @@ pub trait WindowsHook: Sized {
 	/// It contains pointers internally that will not outlive the invoke callback.
 	fn invoke(arg: &mut Self::Context);
 	/// Unsafe thunk to your Rust callback.
+	///
+	/// Panics are caught before they unwind into the system, the next hook is still called.
+	/// The panic payload is kept for [`take_panic`].
+	///
+	/// # Safety
+	///
+	/// Must only be called by the system with the arguments of the hook procedure for the hook type of the context.
 	unsafe extern "system" fn thunk(code: c_int, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
 		let mut context = Self::Context::from_raw(code, w_param, l_param);
 		if code >= 0 {
-			Self::invoke(&mut context);
+			if let Err(payload) = panic::catch_unwind(panic::AssertUnwindSafe(|| Self::invoke(&mut context))) {
+				PANIC.with(|slot| *slot.borrow_mut() = Some(payload));
+			}
 		}
 		context.call_next_hook()
 	}
-	/// Registers the hook.
+	/// Registers the hook for all threads on the desktop.
+	///
+	/// Only the low level hooks can be global from an executable.
+	/// Other global hooks are injected into every GUI process and require the callback to live in a DLL,
+	/// the module of this library is passed as the hook module.
+	///
+	/// See [SetWindowsHookEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644990.aspx) for more information.
 	fn register() -> Result<Hook, ErrorCode> {
-		unsafe {
-			let hook = SetWindowsHookExW(Self::Context::hook_type(), Some(Self::thunk), ptr::null_mut(), 0);
-			if hook.is_null() {
-				Err(ErrorCode::last())
-			}
-			else {
-				Ok(Hook(hook))
-			}
-		}
+		let hook_type = Self::Context::hook_type();
+		let module = if hook_type == WH_KEYBOARD_LL || hook_type == WH_MOUSE_LL { ptr::null_mut() } else { crate::module::image_base() };
+		unsafe { register_raw(hook_type, Some(Self::thunk), module, 0) }
+	}
+	/// Registers the hook for a specific thread.
+	///
+	/// Threads of the current process can be hooked from an executable.
+	/// Hooking threads of other processes injects the hook into that process and requires the callback to live in a DLL,
+	/// the module of this library is passed as the hook module.
+	/// Failing that, the error is `ERROR_HOOK_NEEDS_HMOD`, see [`ErrorCode::message`] for a readable description.
+	///
+	/// The low level hooks cannot be registered for a specific thread.
+	///
+	/// See [SetWindowsHookEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644990.aspx) for more information.
+	fn register_for_thread(tid: ThreadId) -> Result<Hook, ErrorCode> {
+		let same_process = Thread::attach_with_process(tid, ThreadRights::query())
+			.map(|(_, pid)| pid == crate::process::ProcessId::current())
+			.unwrap_or(false);
+		let module = if same_process { ptr::null_mut() } else { crate::module::image_base() };
+		unsafe { register_raw(Self::Context::hook_type(), Some(Self::thunk), module, tid.into_inner()) }
+	}
+}
+
+thread_local! {
+	pub(crate) static PANIC: RefCell<Option<Box<dyn any::Any + Send>>> = RefCell::new(None);
+}
+
+/// Takes the payload of the last panic caught in a hook, [`WinEventHook`](crate::winevent::WinEventHook) or [`WindowBuilder::wndproc`](crate::wndclass::WindowBuilder::wndproc) callback on this thread.
+///
+/// A panic cannot unwind across the system calling the hook, it is caught and the event is passed on to the next hook.
+/// Poll this after pumping messages to find out about it, pass the payload to `std::panic::resume_unwind` to propagate it.
+pub fn take_panic() -> Option<Box<dyn any::Any + Send>> {
+	PANIC.with(|slot| slot.borrow_mut().take())
+}
+
+/// Installs the hook procedure with `SetWindowsHookExW`.
+///
+/// # Safety
+///
+/// The thunk must be a hook procedure for the given hook type and live in the given module.
+unsafe fn register_raw(hook_type: c_int, thunk: HOOKPROC, module: HINSTANCE, tid: DWORD) -> Result<Hook, ErrorCode> {
+	let hook = SetWindowsHookExW(hook_type, thunk, module, tid);
+	if hook.is_null() {
+		Err(ErrorCode::last())
+	}
+	else {
+		Ok(Hook(hook))
 	}
 }
 
@@ -113,17 +237,59 @@ macro_rules! windows_hook {
 			<T as $crate::hook::WindowsHook>::register()
 		}
 	};
+	(
+		$(#[$meta:meta])*
+		$vis:vis fn $name:ident($arg:ident: &mut $ty:ty) for thread $body:tt
+	) => {
+		$(#[$meta])*
+		$vis fn $name(tid: $crate::thread::ThreadId) -> Result<$crate::hook::Hook, $crate::error::ErrorCode> {
+			enum T {}
+			impl $crate::hook::WindowsHook for T {
+				type Context = $ty;
+				fn invoke($arg: &mut $ty) $body
+			}
+			<T as $crate::hook::WindowsHook>::register_for_thread(tid)
+		}
+	};
 }
 
 /// The hook registration.
 ///
-/// The hook is unhooked when this instance goes out of scope.
+/// The hook is unhooked when this instance goes out of scope, use [`unhook`](Hook::unhook) to observe the result.
+///
+/// A hook is deliberately not `Send`: the callbacks are delivered on the registering thread's message loop,
+/// keep the registration on that thread and unhook it from there.
 pub struct Hook(HHOOK);
+impl Hook {
+	/// Unhooks the hook, reporting any error.
+	///
+	/// See [UnhookWindowsHookEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644993.aspx) for more information.
+	pub fn unhook(self) -> Result<(), ErrorCode> {
+		let hook = self.into_raw();
+		if unsafe { UnhookWindowsHookEx(hook) } != FALSE {
+			Ok(())
+		}
+		else {
+			Err(ErrorCode::last())
+		}
+	}
+	/// Returns the raw hook handle.
+	pub fn as_raw(&self) -> HHOOK {
+		self.0
+	}
+	/// Returns the raw hook handle without unhooking it.
+	///
+	/// The caller becomes responsible for calling `UnhookWindowsHookEx`.
+	pub fn into_raw(self) -> HHOOK {
+		let hook = self.0;
+		std::mem::forget(self);
+		hook
+	}
+}
 impl Drop for Hook {
 	fn drop(&mut self) {
-		unsafe {
-			UnhookWindowsHookEx(self.0);
-		}
+		let success = unsafe { UnhookWindowsHookEx(self.0) } != FALSE;
+		debug_assert!(success, "UnhookWindowsHookEx failed: {}", ErrorCode::last());
 	}
 }
 
@@ -132,3 +298,67 @@ pub use self::keyboard_ll::*;
 
 mod mouse_ll;
 pub use self::mouse_ll::*;
+
+mod get_msg;
+pub use self::get_msg::*;
+
+mod cbt;
+pub use self::cbt::*;
+
+mod shell;
+pub use self::shell::*;
+
+mod closure;
+pub use self::closure::*;
+
+mod hook_thread;
+pub use self::hook_thread::*;
+
+#[test]
+fn test_unhook() {
+	windows_hook! {
+		fn my_hook(_context: &mut GetMsg) for thread {}
+	}
+	let hook = my_hook(ThreadId::current()).unwrap();
+	assert!(!hook.as_raw().is_null());
+	assert_eq!(hook.unhook(), Ok(()));
+}
+
+#[test]
+fn test_panic_in_hook() {
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	static SEEN: AtomicUsize = AtomicUsize::new(0);
+	windows_hook! {
+		fn my_hook(context: &mut GetMsg) for thread {
+			if context.message() == WM_USER && context.removed() {
+				if context.wparam() == 1 {
+					panic!("panic in hook");
+				}
+				SEEN.store(context.wparam(), Ordering::Relaxed);
+			}
+		}
+	}
+	let hook = my_hook(ThreadId::current()).unwrap();
+	unsafe {
+		PostThreadMessageW(GetCurrentThreadId(), WM_USER, 1, 0);
+		PostThreadMessageW(GetCurrentThreadId(), WM_USER, 2, 0);
+	}
+	crate::wndclass::pump_once();
+	let payload = take_panic().expect("the panic was caught");
+	assert_eq!(payload.downcast_ref::<&str>(), Some(&"panic in hook"));
+	let seen = SEEN.load(Ordering::Relaxed);
+	assert_eq!(seen, 2);
+	assert!(take_panic().is_none());
+	drop(hook);
+}
+
+#[test]
+fn test_register_for_thread_global_only() {
+	windows_hook! {
+		fn my_hook(_context: &mut KeyboardLL) for thread {}
+	}
+	match my_hook(ThreadId::current()) {
+		Ok(_) => panic!("low level hooks are global only"),
+		Err(err) => assert_eq!(err, ErrorCode::from(ERROR_GLOBAL_ONLY_HOOK)),
+	}
+}
@@ -14,12 +14,107 @@ pub enum MouseData {
 	Move,
 	ButtonDown(VirtualKey),
 	ButtonUp(VirtualKey),
+	/// The low level hooks never receive double clicks, this is only produced by [`ClickTracker`].
 	DoubleClick(VirtualKey),
 	Wheel(i16),
 	HWheel(i16),
 	Message,
 }
 
+impl MouseData {
+	fn decode(message: u32, mouse_data: DWORD) -> MouseData {
+		let xbutton = || match (mouse_data >> 16) as u16 {
+			XBUTTON1 => VirtualKey::XBUTTON1,
+			XBUTTON2 => VirtualKey::XBUTTON2,
+			_ => VirtualKey::NONE,
+			// x => panic!("unknown xbutton: {}", x),
+		};
+		let wheel = (mouse_data >> 16) as i16;
+		match message {
+			WM_MOUSEMOVE => MouseData::Move,
+			WM_LBUTTONDOWN => MouseData::ButtonDown(VirtualKey::LBUTTON),
+			WM_LBUTTONUP => MouseData::ButtonUp(VirtualKey::LBUTTON),
+			WM_RBUTTONDOWN => MouseData::ButtonDown(VirtualKey::RBUTTON),
+			WM_RBUTTONUP => MouseData::ButtonUp(VirtualKey::RBUTTON),
+			WM_MBUTTONDOWN => MouseData::ButtonDown(VirtualKey::MBUTTON),
+			WM_MBUTTONUP => MouseData::ButtonUp(VirtualKey::MBUTTON),
+			WM_XBUTTONDOWN => MouseData::ButtonDown(xbutton()),
+			WM_XBUTTONUP => MouseData::ButtonUp(xbutton()),
+			WM_MOUSEWHEEL => MouseData::Wheel(wheel),
+			WM_MOUSEHWHEEL => MouseData::HWheel(wheel),
+			_ => MouseData::Message,
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Detects double clicks in a stream of mouse events.
+///
+/// A button press is reported as a double click when it follows a press of the same button
+/// within the double click time and the double click rectangle, as configured by the user.
+/// The click after a double click starts over.
+///
+/// See [GetDoubleClickTime function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646258.aspx) for more information.
+#[derive(Clone, Debug)]
+pub struct ClickTracker {
+	time: u32,
+	cx: i32,
+	cy: i32,
+	last: Option<(VirtualKey, i32, i32, u32)>,
+}
+impl ClickTracker {
+	/// Creates a new tracker with the current system double click settings.
+	pub fn new() -> ClickTracker {
+		unsafe {
+			ClickTracker {
+				time: GetDoubleClickTime(),
+				cx: GetSystemMetrics(SM_CXDOUBLECLK),
+				cy: GetSystemMetrics(SM_CYDOUBLECLK),
+				last: None,
+			}
+		}
+	}
+	/// Creates a new tracker with the given double click time in milliseconds and rectangle size in pixels.
+	pub fn with_settings(time: u32, cx: i32, cy: i32) -> ClickTracker {
+		ClickTracker { time, cx, cy, last: None }
+	}
+	/// Tracks the mouse event, returns `DoubleClick` instead of `ButtonDown` for the second press of a double click.
+	///
+	/// The position and time are those of the event, eg. `pt_x`, `pt_y` and `time` of [`MouseLL`].
+	pub fn track(&mut self, data: MouseData, x: i32, y: i32, time: u32) -> MouseData {
+		match data {
+			MouseData::ButtonDown(button) => {
+				let double = match self.last {
+					Some((last_button, last_x, last_y, last_time)) => {
+						last_button == button &&
+						time.wrapping_sub(last_time) <= self.time &&
+						(x - last_x).abs() <= self.cx / 2 &&
+						(y - last_y).abs() <= self.cy / 2
+					},
+					None => false,
+				};
+				if double {
+					self.last = None;
+					MouseData::DoubleClick(button)
+				}
+				else {
+					self.last = Some((button, x, y, time));
+					data
+				}
+			},
+			_ => data,
+		}
+	}
+}
+impl Default for ClickTracker {
+	fn default() -> ClickTracker {
+		ClickTracker::new()
+	}
+}
+
+//----------------------------------------------------------------
+
 /// Low level mouse hook callback context.
 ///
 /// See documentation for
@@ -65,30 +160,29 @@ impl MouseLL {
 	pub fn set_pt_y(&mut self, y: i32) {
 		self.info_mut().pt.y = y;
 	}
-	fn mouse_data_xbutton(&self) -> VirtualKey {
-		match (self.info().mouseData >> 16) as u16 {
-			XBUTTON1 => VirtualKey::XBUTTON1,
-			XBUTTON2 => VirtualKey::XBUTTON2,
-			_ => VirtualKey::NONE,
-			// x => panic!("unknown xbutton: {}", x),
-		}
+	pub fn mouse_data(&self) -> MouseData {
+		MouseData::decode(self.message, self.info().mouseData)
 	}
-	fn mouse_data_wheel(&self) -> i16 {
-		(self.info().mouseData >> 16) as i16
+	/// Sets the raw mouse data.
+	pub fn set_mouse_data(&mut self, mouse_data: u32) {
+		self.info_mut().mouseData = mouse_data;
 	}
-	pub fn mouse_data(&self) -> MouseData {
-		match self.message {
-			WM_MOUSEMOVE => MouseData::Move,
-			WM_LBUTTONDOWN => MouseData::ButtonDown(VirtualKey::LBUTTON),
-			WM_LBUTTONUP => MouseData::ButtonUp(VirtualKey::LBUTTON),
-			WM_RBUTTONDOWN => MouseData::ButtonDown(VirtualKey::RBUTTON),
-			WM_RBUTTONUP => MouseData::ButtonUp(VirtualKey::RBUTTON),
-			WM_XBUTTONDOWN => MouseData::ButtonDown(self.mouse_data_xbutton()),
-			WM_XBUTTONUP => MouseData::ButtonUp(self.mouse_data_xbutton()),
-			WM_MOUSEWHEEL => MouseData::Wheel(self.mouse_data_wheel()),
-			WM_MOUSEHWHEEL => MouseData::HWheel(self.mouse_data_wheel()),
-			_ => MouseData::Message,
-		}
+	/// Sets the wheel delta of a wheel event.
+	pub fn set_wheel_delta(&mut self, delta: i16) {
+		let info = self.info_mut();
+		info.mouseData = (info.mouseData & 0xffff) | ((delta as u16 as u32) << 16);
+	}
+	/// Sets which x-button is pressed or released.
+	///
+	/// Panics if the virtual key is not `XBUTTON1` or `XBUTTON2`.
+	pub fn set_xbutton(&mut self, vk: VirtualKey) {
+		let xbutton = match vk {
+			VirtualKey::XBUTTON1 => XBUTTON1,
+			VirtualKey::XBUTTON2 => XBUTTON2,
+			_ => panic!("not an xbutton: {:?}", vk),
+		};
+		let info = self.info_mut();
+		info.mouseData = (info.mouseData & 0xffff) | ((xbutton as u32) << 16);
 	}
 	pub fn injected(&self) -> bool {
 		self.info().flags & 0x01 != 0
@@ -114,12 +208,22 @@ impl MouseLL {
 	pub fn set_time(&mut self, time: u32) {
 		self.info_mut().time = time;
 	}
+	/// Returns if this event was injected with the given tag as its extra info.
+	///
+	/// Tag your own input with [`MouseInput::send_tagged`](crate::mouse::MouseInput::send_tagged) to recognize and ignore it here.
+	pub fn is_self_injected_by(&self, tag: usize) -> bool {
+		self.injected() && self.info().dwExtraInfo == tag
+	}
 	pub unsafe fn extra_info<T>(&self) -> Option<&T> {
 		(self.info().dwExtraInfo as *const T).as_ref()
 	}
 	pub unsafe fn extra_info_mut<T>(&mut self) -> Option<&mut T> {
 		(self.info_mut().dwExtraInfo as *mut T).as_mut()
 	}
+	/// Copies the event out of the hook.
+	pub fn to_event(&self) -> MouseEvent {
+		MouseEvent { message: self.message, info: *self.info() }
+	}
 }
 impl fmt::Debug for MouseLL {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -135,6 +239,67 @@ impl fmt::Debug for MouseLL {
 			.finish()
 	}
 }
+
+//----------------------------------------------------------------
+
+/// Owned copy of a low level mouse event.
+///
+/// Unlike [`MouseLL`] it can be kept after the hook returns and sent to other threads.
+#[derive(Copy, Clone)]
+pub struct MouseEvent {
+	message: u32,
+	info: MSLLHOOKSTRUCT,
+}
+impl MouseEvent {
+	pub fn message(&self) -> u32 {
+		self.message
+	}
+	pub fn pt_x(&self) -> i32 {
+		self.info.pt.x
+	}
+	pub fn pt_y(&self) -> i32 {
+		self.info.pt.y
+	}
+	pub fn mouse_data(&self) -> MouseData {
+		MouseData::decode(self.message, self.info.mouseData)
+	}
+	pub fn injected(&self) -> bool {
+		self.info.flags & 0x01 != 0
+	}
+	pub fn lower_il_injected(&self) -> bool {
+		self.info.flags & 0x02 != 0
+	}
+	pub fn time(&self) -> u32 {
+		self.info.time
+	}
+	/// Returns if this event was injected with the given tag as its extra info.
+	pub fn is_self_injected_by(&self, tag: usize) -> bool {
+		self.injected() && self.info.dwExtraInfo == tag
+	}
+	/// Returns the raw extra info.
+	///
+	/// Pointers stored in the extra info may no longer be valid after the hook returns.
+	pub fn extra_info(&self) -> usize {
+		self.info.dwExtraInfo
+	}
+}
+impl fmt::Debug for MouseEvent {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("MouseEvent")
+			.field("message", &self.message())
+			.field("pt_x", &self.pt_x())
+			.field("pt_y", &self.pt_y())
+			.field("mouse_data", &self.mouse_data())
+			.field("injected", &self.injected())
+			.field("lower_il_injected", &self.lower_il_injected())
+			.field("time", &self.time())
+			.field("dwExtraInfo", &(self.info.dwExtraInfo as *const ()))
+			.finish()
+	}
+}
+
+//----------------------------------------------------------------
+
 unsafe impl HookContext for MouseLL {
 	fn hook_type() -> c_int {
 		WH_MOUSE_LL
@@ -155,3 +320,40 @@ unsafe impl HookContext for MouseLL {
 		}
 	}
 }
+
+#[test]
+fn test_set_mouse_data() {
+	let mut info: MSLLHOOKSTRUCT = unsafe { std::mem::zeroed() };
+	info.mouseData = (120 << 16) as DWORD;
+	let mut context = unsafe { MouseLL::from_raw(0, WM_MOUSEWHEEL as WPARAM, &mut info as *mut _ as LPARAM) };
+	if let MouseData::Wheel(delta) = context.mouse_data() {
+		context.set_wheel_delta(-delta);
+	}
+	assert_eq!(context.mouse_data(), MouseData::Wheel(-120));
+
+	context.set_message(WM_XBUTTONDOWN);
+	context.set_xbutton(VirtualKey::XBUTTON2);
+	assert_eq!(context.mouse_data(), MouseData::ButtonDown(VirtualKey::XBUTTON2));
+
+	context.set_mouse_data(0);
+	assert_eq!(context.mouse_data(), MouseData::ButtonDown(VirtualKey::NONE));
+}
+
+#[test]
+fn test_click_tracker() {
+	let mut tracker = ClickTracker::with_settings(500, 4, 4);
+	let down = MouseData::ButtonDown(VirtualKey::LBUTTON);
+	assert_eq!(tracker.track(down, 10, 10, 1000), down);
+	assert_eq!(tracker.track(MouseData::ButtonUp(VirtualKey::LBUTTON), 10, 10, 1050), MouseData::ButtonUp(VirtualKey::LBUTTON));
+	assert_eq!(tracker.track(down, 11, 9, 1100), MouseData::DoubleClick(VirtualKey::LBUTTON));
+	// The third click starts over
+	assert_eq!(tracker.track(down, 11, 9, 1200), down);
+	// Too slow
+	assert_eq!(tracker.track(down, 11, 9, 1800), down);
+	// Too far
+	assert_eq!(tracker.track(down, 20, 9, 1900), down);
+	// Other button
+	let middle = MouseData::ButtonDown(VirtualKey::MBUTTON);
+	assert_eq!(tracker.track(middle, 20, 9, 2000), middle);
+	assert_eq!(MouseData::decode(WM_MBUTTONUP, 0), MouseData::ButtonUp(VirtualKey::MBUTTON));
+}
@@ -0,0 +1,161 @@
+/*!
+Shell hook details.
+!*/
+
+use std::{ptr, fmt};
+use crate::winapi::*;
+use crate::window::{Window, WindowStyle, WindowExStyle};
+use crate::wndclass::WindowBuilder;
+use crate::error::ErrorCode;
+use crate::{FromInner, IntoInner, Result};
+use super::HookContext;
+
+//----------------------------------------------------------------
+
+/// Decoded shell event.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ShellEvent {
+	/// A top-level, unowned window was created, `HSHELL_WINDOWCREATED`.
+	WindowCreated(Window),
+	/// A top-level, unowned window is about to be destroyed, `HSHELL_WINDOWDESTROYED`.
+	WindowDestroyed(Window),
+	/// The activation changed to a different top-level window, `HSHELL_WINDOWACTIVATED`.
+	WindowActivated { window: Window, fullscreen: bool },
+	/// The activation changed to a fullscreen window, `HSHELL_RUDEAPPACTIVATED`.
+	RudeAppActivated { window: Window, fullscreen: bool },
+	/// Any other event with its raw parameters.
+	Other { code: i32, wparam: usize, lparam: isize },
+}
+impl ShellEvent {
+	/// Decodes a shell event from its raw hook parameters.
+	///
+	/// The `fullscreen` flag is only available from the hook, the shell hook window always reports `false`.
+	pub fn from_raw(code: i32, wparam: usize, lparam: isize) -> ShellEvent {
+		let window = unsafe { Window::from_inner(wparam as HWND) };
+		match code {
+			HSHELL_WINDOWCREATED => ShellEvent::WindowCreated(window),
+			HSHELL_WINDOWDESTROYED => ShellEvent::WindowDestroyed(window),
+			HSHELL_WINDOWACTIVATED => ShellEvent::WindowActivated { window, fullscreen: lparam != 0 },
+			HSHELL_RUDEAPPACTIVATED => ShellEvent::RudeAppActivated { window, fullscreen: lparam != 0 },
+			_ => ShellEvent::Other { code, wparam, lparam },
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Shell hook callback context.
+///
+/// A global shell hook requires the callback to live in a DLL, see [`shell_hook_window`] for an alternative without one.
+///
+/// See documentation for
+/// [ShellProc](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644991.aspx)
+/// for more information.
+#[repr(C)]
+pub struct Shell {
+	code: c_int,
+	w_param: WPARAM,
+	l_param: LPARAM,
+}
+impl Shell {
+	pub fn code(&self) -> i32 {
+		self.code
+	}
+	pub fn event(&self) -> ShellEvent {
+		ShellEvent::from_raw(self.code, self.w_param, self.l_param)
+	}
+}
+impl fmt::Debug for Shell {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Shell")
+			.field("event", &self.event())
+			.finish()
+	}
+}
+unsafe impl HookContext for Shell {
+	fn hook_type() -> c_int {
+		WH_SHELL
+	}
+	unsafe fn from_raw(code: c_int, w_param: WPARAM, l_param: LPARAM) -> Self {
+		Shell { code, w_param, l_param }
+	}
+	unsafe fn call_next_hook(&self) -> LRESULT {
+		CallNextHookEx(ptr::null_mut(), self.code, self.w_param, self.l_param)
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Hidden window receiving shell events.
+///
+/// Created by [`shell_hook_window`], deregistered and destroyed on drop.
+/// Must be dropped on the thread which created it.
+#[derive(Debug)]
+pub struct ShellHookWindow {
+	window: Window,
+}
+impl ShellHookWindow {
+	/// Returns the window handle.
+	pub fn window(&self) -> Window {
+		self.window
+	}
+}
+impl Drop for ShellHookWindow {
+	fn drop(&mut self) {
+		unsafe {
+			DeregisterShellHookWindow(self.window.into_inner());
+			DestroyWindow(self.window.into_inner());
+		}
+	}
+}
+
+/// Receives shell events without a DLL by registering a hidden window for shell hook messages.
+///
+/// The callback is invoked from the window procedure, the creating thread must pump messages.
+///
+/// See [RegisterShellHookWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644989.aspx) for more information.
+pub fn shell_hook_window<F>(mut f: F) -> Result<ShellHookWindow> where F: FnMut(ShellEvent) + 'static {
+	let shellhook = wide_str!('S' 'H' 'E' 'L' 'L' 'H' 'O' 'O' 'K' 0);
+	let shell_message = unsafe { RegisterWindowMessageW(shellhook.as_ptr()) };
+	if shell_message == 0 {
+		return Err(ErrorCode::last());
+	}
+	// Shell hook messages are not delivered to message-only windows, use a hidden tool window instead
+	let window = WindowBuilder::new()
+		.class_name("ShellHookWindow")
+		.style(WindowStyle::POPUP)
+		.exstyle(WindowExStyle::TOOLWINDOW)
+		.wndproc(move |_, msg, wparam, lparam| {
+			if msg == shell_message {
+				f(ShellEvent::from_raw(wparam as i32, lparam as usize, 0));
+				Some(0)
+			}
+			else {
+				None
+			}
+		})
+		.create()?;
+	if unsafe { RegisterShellHookWindow(window.into_inner()) } == FALSE {
+		let err = ErrorCode::last();
+		let _ = window.destroy();
+		return Err(err);
+	}
+	Ok(ShellHookWindow { window })
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_shell_event() {
+	let window = unsafe { Window::from_inner(0x1234 as HWND) };
+	assert_eq!(ShellEvent::from_raw(HSHELL_WINDOWCREATED, 0x1234, 0), ShellEvent::WindowCreated(window));
+	assert_eq!(ShellEvent::from_raw(HSHELL_RUDEAPPACTIVATED, 0x1234, 1), ShellEvent::RudeAppActivated { window, fullscreen: true });
+	assert_eq!(ShellEvent::from_raw(HSHELL_REDRAW, 1, 2), ShellEvent::Other { code: HSHELL_REDRAW, wparam: 1, lparam: 2 });
+}
+
+#[test]
+fn test_shell_hook_window() {
+	let hook = shell_hook_window(|event| println!("{:?}", event)).unwrap();
+	assert!(hook.window().valid());
+	drop(hook);
+}
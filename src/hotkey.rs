@@ -0,0 +1,200 @@
+/*!
+System wide hotkeys.
+
+Registers hotkeys with `RegisterHotKey` on a message-only window and dispatches `WM_HOTKEY` to closures.
+!*/
+
+use std::{fmt, panic};
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::winapi::*;
+use crate::error::ErrorCode;
+use crate::vk::{Modifiers, VirtualKey};
+use crate::wndclass::{message_window, MessageWindow};
+use crate::{Result, IntoInner};
+
+/// Hotkey identifier.
+///
+/// Allocated by [`HotkeyManager::register`], identifies the hotkey in the `wParam` of `WM_HOTKEY`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[repr(transparent)]
+pub struct HotkeyId(i32);
+impl_inner!(HotkeyId: safe i32);
+
+type Callback = Box<dyn FnMut()>;
+type Callbacks = Rc<RefCell<Vec<(i32, Option<Callback>)>>>;
+
+/// Applications must use ids in the range `0x0000` through `0xBFFF`.
+const MAX_ID: i32 = 0xBFFF;
+
+/// Hotkey registrations on a message-only window.
+///
+/// The hotkey callbacks are called while the creating thread pumps messages, eg. with [`run`](crate::wndclass::run).
+/// A panic in a callback is caught and can be retrieved with [`take_panic`](crate::hook::take_panic).
+///
+/// All hotkeys are unregistered on drop, which must happen on the thread which created the manager.
+///
+/// ```no_run
+/// use external::hotkey::HotkeyManager;
+/// use external::vk::{Modifiers, VirtualKey};
+///
+/// let mut hotkeys = HotkeyManager::new().unwrap();
+/// hotkeys.register(Modifiers::CTRL | Modifiers::ALT, VirtualKey::F12, || {
+///     external::wndclass::post_quit_message(0);
+/// }).unwrap();
+/// external::wndclass::run();
+/// ```
+pub struct HotkeyManager {
+	window: MessageWindow,
+	callbacks: Callbacks,
+	next_id: i32,
+}
+impl HotkeyManager {
+	/// Creates the message-only window receiving the hotkeys.
+	pub fn new() -> Result<HotkeyManager> {
+		let callbacks = Callbacks::default();
+		let window = message_window({
+			let callbacks = callbacks.clone();
+			move |_, msg, wparam, _| {
+				if msg != WM_HOTKEY {
+					return None;
+				}
+				dispatch(&callbacks, wparam as i32);
+				Some(0)
+			}
+		})?;
+		Ok(HotkeyManager { window, callbacks, next_id: 0 })
+	}
+	/// Returns the message-only window receiving the hotkeys.
+	pub fn window(&self) -> crate::window::Window {
+		self.window.window()
+	}
+	/// Registers a hotkey calling the closure when pressed.
+	///
+	/// Only the modifier groups matter, the system does not distinguish between the left and right modifier keys.
+	/// Holding the hotkey down does not repeat, see [`register_repeat`](HotkeyManager::register_repeat).
+	///
+	/// Fails with `ERROR_HOTKEY_ALREADY_REGISTERED` when the hotkey is taken, eg. by another application.
+	///
+	/// See [RegisterHotKey function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646309.aspx) for more information.
+	pub fn register<F>(&mut self, mods: Modifiers, vk: VirtualKey, f: F) -> Result<HotkeyId> where F: FnMut() + 'static {
+		self.register_raw(mod_flags(mods) | MOD_NOREPEAT as UINT, vk, Box::new(f))
+	}
+	/// Registers a hotkey calling the closure when pressed and again for every auto-repeat while held down.
+	pub fn register_repeat<F>(&mut self, mods: Modifiers, vk: VirtualKey, f: F) -> Result<HotkeyId> where F: FnMut() + 'static {
+		self.register_raw(mod_flags(mods), vk, Box::new(f))
+	}
+	fn register_raw(&mut self, flags: UINT, vk: VirtualKey, f: Callback) -> Result<HotkeyId> {
+		let id = self.allocate_id()?;
+		let success = unsafe { RegisterHotKey(self.window.window().into_inner(), id, flags, vk.into_inner() as UINT) };
+		if success == FALSE {
+			return Err(ErrorCode::last());
+		}
+		self.callbacks.borrow_mut().push((id, Some(f)));
+		Ok(HotkeyId(id))
+	}
+	fn allocate_id(&mut self) -> Result<i32> {
+		let callbacks = self.callbacks.borrow();
+		for _ in 0..=MAX_ID {
+			let id = self.next_id;
+			self.next_id = if id >= MAX_ID { 0 } else { id + 1 };
+			if !callbacks.iter().any(|&(other, _)| other == id) {
+				return Ok(id);
+			}
+		}
+		Err(ErrorCode::from(ERROR_NOT_ENOUGH_MEMORY))
+	}
+	/// Unregisters the hotkey and drops its closure.
+	///
+	/// See [UnregisterHotKey function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646327.aspx) for more information.
+	pub fn unregister(&mut self, id: HotkeyId) -> Result<()> {
+		let f = {
+			let mut callbacks = self.callbacks.borrow_mut();
+			let position = match callbacks.iter().position(|&(other, _)| other == id.0) {
+				Some(position) => position,
+				None => return Err(ErrorCode::from(ERROR_HOTKEY_NOT_REGISTERED)),
+			};
+			callbacks.remove(position)
+		};
+		// Drop the closure outside of the borrow
+		drop(f);
+		let success = unsafe { UnregisterHotKey(self.window.window().into_inner(), id.0) };
+		if success == FALSE {
+			return Err(ErrorCode::last());
+		}
+		Ok(())
+	}
+	/// Returns the ids of the registered hotkeys.
+	pub fn ids(&self) -> Vec<HotkeyId> {
+		self.callbacks.borrow().iter().map(|&(id, _)| HotkeyId(id)).collect()
+	}
+}
+impl Drop for HotkeyManager {
+	fn drop(&mut self) {
+		let callbacks = self.callbacks.replace(Vec::new());
+		for &(id, _) in &callbacks {
+			unsafe {
+				UnregisterHotKey(self.window.window().into_inner(), id);
+			}
+		}
+	}
+}
+impl fmt::Debug for HotkeyManager {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("HotkeyManager")
+			.field("window", &self.window)
+			.field("ids", &self.ids())
+			.finish()
+	}
+}
+
+fn mod_flags(mods: Modifiers) -> UINT {
+	let mut flags = 0;
+	if mods.shift() { flags |= MOD_SHIFT; }
+	if mods.ctrl() { flags |= MOD_CONTROL; }
+	if mods.alt() { flags |= MOD_ALT; }
+	if mods.win() { flags |= MOD_WIN; }
+	flags as UINT
+}
+
+fn dispatch(callbacks: &Callbacks, id: i32) {
+	// Take the closure out while calling it, the window procedure may be reentered
+	let mut f = match callbacks.borrow_mut().iter_mut().find(|(other, _)| *other == id).and_then(|(_, f)| f.take()) {
+		Some(f) => f,
+		None => return,
+	};
+	if let Err(payload) = panic::catch_unwind(panic::AssertUnwindSafe(&mut f)) {
+		crate::hook::PANIC.with(|slot| *slot.borrow_mut() = Some(payload));
+	}
+	let f = match callbacks.borrow_mut().iter_mut().find(|(other, _)| *other == id) {
+		Some((_, slot)) => { *slot = Some(f); None },
+		None => Some(f),
+	};
+	drop(f);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_hotkey_manager() {
+	use std::cell::Cell;
+
+	let mut hotkeys = HotkeyManager::new().unwrap();
+	let calls = Rc::new(Cell::new(0));
+	let mods = Modifiers::CTRL | Modifiers::ALT | Modifiers::SHIFT;
+	let id = hotkeys.register(mods, VirtualKey::F24, { let calls = calls.clone(); move || calls.set(calls.get() + 1) }).unwrap();
+
+	// The same hotkey cannot be registered twice
+	let err = hotkeys.register(mods, VirtualKey::F24, || ()).unwrap_err();
+	assert_eq!(err, ErrorCode::from(ERROR_HOTKEY_ALREADY_REGISTERED));
+	assert_eq!(hotkeys.ids(), [id]);
+
+	unsafe {
+		SendMessageW(hotkeys.window().into_inner(), WM_HOTKEY, id.into_inner() as WPARAM, 0);
+	}
+	assert_eq!(calls.get(), 1);
+
+	hotkeys.unregister(id).unwrap();
+	assert_eq!(hotkeys.unregister(id), Err(ErrorCode::from(ERROR_HOTKEY_NOT_REGISTERED)));
+	assert!(hotkeys.ids().is_empty());
+}
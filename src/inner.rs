@@ -64,10 +64,9 @@ macro_rules! impl_inner {
 		impl $crate::IntoInner<$inner> for $ty {
 			#[inline]
 			fn into_inner(self) -> $inner {
-				let inner = self.0;
-				// Forget the container to prevent it from cleaning up the resource
-				std::mem::forget(self);
-				inner
+				// Wrap the container to prevent it from cleaning up the resource
+				let this = std::mem::ManuallyDrop::new(self);
+				unsafe { std::ptr::read(&this.0) }
 			}
 		}
 		impl $crate::FromInner<$inner> for $ty {
@@ -0,0 +1,657 @@
+/*!
+Synthesized and raw input.
+
+Sends keyboard and mouse input in batches with `SendInput`.
+The events of a batch are inserted serially in the input stream, they are not interspersed with other input.
+
+Receives the unprocessed device input with [`RawInput`].
+!*/
+
+use std::{mem, ops, ptr, thread};
+use std::time::Duration;
+use crate::winapi::*;
+use crate::error::ErrorCode;
+use crate::mouse::MouseInput;
+use crate::vk::{ScanCode, VirtualKey};
+use crate::window::Window;
+use crate::IntoInner;
+
+//----------------------------------------------------------------
+
+/// Keyboard input event.
+///
+/// See [KEYBDINPUT structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646271.aspx) for more information.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct KeyboardInput {
+	vk: u16,
+	scan: u16,
+	flags: u32,
+	extra_info: usize,
+}
+impl KeyboardInput {
+	/// Presses a virtual key.
+	#[must_use]
+	pub fn key_down(vk: VirtualKey) -> KeyboardInput {
+		KeyboardInput { vk: vk.into_inner() as u16, scan: vk.scan_code().code() as u16, flags: 0, extra_info: 0 }
+	}
+	/// Releases a virtual key.
+	#[must_use]
+	pub fn key_up(vk: VirtualKey) -> KeyboardInput {
+		KeyboardInput { vk: vk.into_inner() as u16, scan: vk.scan_code().code() as u16, flags: KEYEVENTF_KEYUP, extra_info: 0 }
+	}
+	/// Presses a key by its hardware scan code, the virtual key is ignored.
+	#[must_use]
+	pub const fn scan_down(scan: u16) -> KeyboardInput {
+		KeyboardInput { vk: 0, scan, flags: KEYEVENTF_SCANCODE, extra_info: 0 }
+	}
+	/// Releases a key by its hardware scan code.
+	#[must_use]
+	pub const fn scan_up(scan: u16) -> KeyboardInput {
+		KeyboardInput { vk: 0, scan, flags: KEYEVENTF_SCANCODE | KEYEVENTF_KEYUP, extra_info: 0 }
+	}
+	/// Presses a key by its scan code, marked as extended if the scan code has the `0xE0` prefix.
+	///
+	/// Games reading the keyboard by scan code may ignore input sent by virtual key.
+	///
+	/// # Panics
+	///
+	/// The pause key cannot be injected by scan code, panics if the scan code has its `0xE1` prefix.
+	#[must_use]
+	pub const fn scan_code_down(scan: ScanCode) -> KeyboardInput {
+		assert!(!scan.is_pause(), "the pause key cannot be sent by scan code");
+		KeyboardInput { vk: 0, scan: scan.code() as u16, flags: scan.input_flags(), extra_info: 0 }
+	}
+	/// Releases a key by its scan code.
+	///
+	/// # Panics
+	///
+	/// Panics if the scan code has the `0xE1` prefix of the pause key.
+	#[must_use]
+	pub const fn scan_code_up(scan: ScanCode) -> KeyboardInput {
+		assert!(!scan.is_pause(), "the pause key cannot be sent by scan code");
+		KeyboardInput { vk: 0, scan: scan.code() as u16, flags: scan.input_flags() | KEYEVENTF_KEYUP, extra_info: 0 }
+	}
+	/// Presses a UTF-16 code unit, the receiving window gets a `VK_PACKET` keystroke.
+	#[must_use]
+	pub const fn unicode_down(unit: u16) -> KeyboardInput {
+		KeyboardInput { vk: 0, scan: unit, flags: KEYEVENTF_UNICODE, extra_info: 0 }
+	}
+	/// Releases a UTF-16 code unit.
+	#[must_use]
+	pub const fn unicode_up(unit: u16) -> KeyboardInput {
+		KeyboardInput { vk: 0, scan: unit, flags: KEYEVENTF_UNICODE | KEYEVENTF_KEYUP, extra_info: 0 }
+	}
+	/// Marks the key as extended, eg. the right control key or the arrow keys.
+	#[must_use]
+	pub const fn extended(self) -> KeyboardInput {
+		KeyboardInput { flags: self.flags | KEYEVENTF_EXTENDEDKEY, ..self }
+	}
+	/// Tags the event with extra info.
+	///
+	/// Low level hooks can recognize the tagged event with `KeyboardLL::is_self_injected_by`.
+	#[must_use]
+	pub const fn tagged(self, tag: usize) -> KeyboardInput {
+		KeyboardInput { extra_info: tag, ..self }
+	}
+	/// Returns the flags, eg. `KEYEVENTF_KEYUP`.
+	pub const fn flags(self) -> u32 {
+		self.flags
+	}
+	fn to_raw(self) -> INPUT {
+		unsafe {
+			let mut input: INPUT = mem::zeroed();
+			input.type_ = INPUT_KEYBOARD;
+			*input.u.ki_mut() = KEYBDINPUT {
+				wVk: self.vk,
+				wScan: self.scan,
+				dwFlags: self.flags,
+				time: 0,
+				dwExtraInfo: self.extra_info,
+			};
+			input
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Input event.
+#[derive(Copy, Clone, Debug)]
+pub enum Input {
+	Keyboard(KeyboardInput),
+	Mouse(MouseInput),
+}
+impl From<KeyboardInput> for Input {
+	fn from(input: KeyboardInput) -> Input {
+		Input::Keyboard(input)
+	}
+}
+impl From<MouseInput> for Input {
+	fn from(input: MouseInput) -> Input {
+		Input::Mouse(input)
+	}
+}
+impl Input {
+	fn to_raw(self) -> INPUT {
+		match self {
+			Input::Keyboard(input) => input.to_raw(),
+			Input::Mouse(input) => input.to_raw(),
+		}
+	}
+}
+
+/// Sends the input events in order.
+///
+/// Returns the number of events injected.
+/// When fewer events went through the number of events injected is returned with the error.
+/// The system does not report an error when blocked by UIPI, in that case the error is `ERROR_ACCESS_DENIED`.
+///
+/// See [SendInput function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646310.aspx) for more information.
+pub fn send(inputs: &[Input]) -> Result<u32, (u32, ErrorCode)> {
+	let mut raw: Vec<INPUT> = inputs.iter().map(|input| input.to_raw()).collect();
+	send_raw(&mut raw)
+}
+
+/// Types the text into the foreground window.
+///
+/// See [`StringInput`] for more information and options.
+pub fn send_string(text: &str) -> crate::Result<()> {
+	StringInput::new(text).send()
+}
+
+/// Types text with `KEYEVENTF_UNICODE` keystrokes.
+///
+/// Every character is sent as UTF-16 code unit key presses, this works for characters without a key on the current keyboard layout.
+/// The code units of a surrogate pair are always sent together. Line breaks (`"\n"`, `"\r\n"` or `"\r"`) are sent as presses of the enter key.
+///
+/// The modifier keys held by the user are left alone by default, note that eg. a held control key turns the text into shortcuts.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use external::input::StringInput;
+///
+/// StringInput::new("Hello, world!\n")
+///     .delay(Duration::from_millis(10))
+///     .release_modifiers(true)
+///     .send().unwrap();
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct StringInput<'a> {
+	text: &'a str,
+	delay: Option<Duration>,
+	release_modifiers: bool,
+}
+impl<'a> StringInput<'a> {
+	/// Creates the input for the text.
+	pub fn new(text: &'a str) -> StringInput<'a> {
+		StringInput { text, delay: None, release_modifiers: false }
+	}
+	/// Sleeps between every character for applications which drop input arriving too fast.
+	///
+	/// Without a delay the whole text is sent as a single batch.
+	pub fn delay(self, delay: Duration) -> StringInput<'a> {
+		StringInput { delay: Some(delay), ..self }
+	}
+	/// Releases the held shift, control, alt and windows keys before typing the text.
+	///
+	/// An unassigned key is tapped before releasing a windows key, otherwise the release would open the start menu.
+	pub fn release_modifiers(self, release_modifiers: bool) -> StringInput<'a> {
+		StringInput { release_modifiers, ..self }
+	}
+	/// Sends the text.
+	pub fn send(self) -> crate::Result<()> {
+		if self.release_modifiers {
+			const MODIFIERS: [VirtualKey; 8] = [
+				VirtualKey::LSHIFT, VirtualKey::RSHIFT, VirtualKey::LCTRL, VirtualKey::RCTRL,
+				VirtualKey::LALT, VirtualKey::RALT, VirtualKey::LWIN, VirtualKey::RWIN,
+			];
+			let held: Vec<VirtualKey> = MODIFIERS.iter().cloned().filter(|vk| vk.async_state()).collect();
+			let inputs: Vec<Input> = release_inputs(&held).into_iter().map(Input::from).collect();
+			send(&inputs).map_err(|(_, err)| err)?;
+		}
+		let chars = string_inputs(self.text);
+		match self.delay {
+			None => {
+				let inputs: Vec<Input> = chars.into_iter().flatten().map(Input::from).collect();
+				send(&inputs).map_err(|(_, err)| err)?;
+			},
+			Some(delay) => {
+				for (i, chr) in chars.into_iter().enumerate() {
+					if i != 0 {
+						thread::sleep(delay);
+					}
+					let inputs: Vec<Input> = chr.into_iter().map(Input::from).collect();
+					send(&inputs).map_err(|(_, err)| err)?;
+				}
+			},
+		}
+		Ok(())
+	}
+}
+
+// Keystrokes releasing the held modifier keys.
+fn release_inputs(held: &[VirtualKey]) -> Vec<KeyboardInput> {
+	// VK_NONAME, releasing a lone windows key would open the start menu
+	const DUMMY: VirtualKey = VirtualKey::new(0xfc);
+	let mut inputs = Vec::new();
+	if held.iter().any(|&vk| vk == VirtualKey::LWIN || vk == VirtualKey::RWIN) {
+		inputs.push(KeyboardInput::key_down(DUMMY));
+		inputs.push(KeyboardInput::key_up(DUMMY));
+	}
+	for &vk in held {
+		let input = KeyboardInput::key_up(vk);
+		inputs.push(if vk.scan_code().is_extended() { input.extended() } else { input });
+	}
+	inputs
+}
+
+// Keystrokes for every character of the text.
+fn string_inputs(text: &str) -> Vec<Vec<KeyboardInput>> {
+	let mut result = Vec::new();
+	let mut chars = text.chars().peekable();
+	while let Some(chr) = chars.next() {
+		let inputs = match chr {
+			'\r' | '\n' => {
+				if chr == '\r' && chars.peek() == Some(&'\n') {
+					chars.next();
+				}
+				vec![KeyboardInput::key_down(VirtualKey::RETURN), KeyboardInput::key_up(VirtualKey::RETURN)]
+			},
+			_ => {
+				let mut buf = [0u16; 2];
+				let mut inputs = Vec::new();
+				for &unit in chr.encode_utf16(&mut buf).iter() {
+					inputs.push(KeyboardInput::unicode_down(unit));
+					inputs.push(KeyboardInput::unicode_up(unit));
+				}
+				inputs
+			},
+		};
+		result.push(inputs);
+	}
+	result
+}
+
+pub(crate) fn send_raw(inputs: &mut [INPUT]) -> Result<u32, (u32, ErrorCode)> {
+	if inputs.is_empty() {
+		return Ok(0);
+	}
+	let sent = unsafe {
+		SetLastError(0);
+		SendInput(inputs.len() as UINT, inputs.as_mut_ptr(), mem::size_of::<INPUT>() as c_int)
+	};
+	if sent as usize == inputs.len() {
+		Ok(sent)
+	}
+	else {
+		let err = ErrorCode::last();
+		Err((sent, if err.is_success() { ErrorCode::from(ERROR_ACCESS_DENIED) } else { err }))
+	}
+}
+
+/// Sends a single input event.
+pub(crate) fn send_one(input: INPUT) -> Result<(), ErrorCode> {
+	let mut inputs = [input];
+	send_raw(&mut inputs).map(|_| ()).map_err(|(_, err)| err)
+}
+
+//----------------------------------------------------------------
+
+/// Raw input device kind.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RawKind {
+	Mouse,
+	Keyboard,
+}
+impl RawKind {
+	// Generic desktop controls usage page and usage
+	fn usage(self) -> (u16, u16) {
+		match self {
+			RawKind::Mouse => (0x01, 0x02),
+			RawKind::Keyboard => (0x01, 0x06),
+		}
+	}
+}
+
+/// Raw input registration flags.
+///
+/// See [RAWINPUTDEVICE structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms645565.aspx) for more information.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct RawInputFlags(u32);
+impl_inner!(RawInputFlags: safe u32);
+impl RawInputFlags {
+	pub const NONE: RawInputFlags = RawInputFlags(0);
+	/// Receive input even when the window is not in the foreground.
+	pub const INPUTSINK: RawInputFlags = RawInputFlags(RIDEV_INPUTSINK);
+	/// Receive input in the background only if the foreground application does not process it.
+	pub const EXINPUTSINK: RawInputFlags = RawInputFlags(RIDEV_EXINPUTSINK);
+	/// Suppress the legacy messages such as `WM_MOUSEMOVE` and `WM_KEYDOWN`.
+	pub const NOLEGACY: RawInputFlags = RawInputFlags(RIDEV_NOLEGACY);
+	/// Mouse clicks do not activate other windows, requires `NOLEGACY`.
+	pub const CAPTUREMOUSE: RawInputFlags = RawInputFlags(RIDEV_CAPTUREMOUSE);
+	/// Receive `WM_INPUT_DEVICE_CHANGE` when a device is added or removed.
+	pub const DEVNOTIFY: RawInputFlags = RawInputFlags(RIDEV_DEVNOTIFY);
+}
+impl ops::BitOr for RawInputFlags {
+	type Output = RawInputFlags;
+	fn bitor(self, rhs: RawInputFlags) -> RawInputFlags {
+		RawInputFlags(self.0 | rhs.0)
+	}
+}
+
+/// Raw input.
+///
+/// Register a window to receive `WM_INPUT` messages and decode them with [`parse`](RawInput::parse).
+/// Raw input reports the device data before any processing, eg. the mouse deltas without pointer acceleration.
+///
+/// Pair it with [`message_window`](crate::wndclass::message_window) and the `INPUTSINK` flag to receive the input in the background.
+///
+/// See [Raw Input](https://msdn.microsoft.com/en-us/library/windows/desktop/ms645543.aspx) for more information.
+#[derive(Copy, Clone, Debug)]
+pub struct RawInput;
+impl RawInput {
+	/// Registers the window to receive raw input from the devices.
+	///
+	/// A process registers a single window per device kind, registering again replaces the previous registration.
+	///
+	/// See [RegisterRawInputDevices function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms645600.aspx) for more information.
+	pub fn register(kinds: &[RawKind], window: Window, flags: RawInputFlags) -> crate::Result<()> {
+		register_devices(kinds, window.into_inner(), flags.0)
+	}
+	/// Stops receiving raw input from the devices.
+	pub fn unregister(kinds: &[RawKind]) -> crate::Result<()> {
+		register_devices(kinds, ptr::null_mut(), RIDEV_REMOVE)
+	}
+	/// Reads and decodes the raw input from the `lParam` of a `WM_INPUT` message.
+	///
+	/// See [GetRawInputData function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms645596.aspx) for more information.
+	pub fn parse(lparam: LPARAM) -> crate::Result<RawEvent> {
+		unsafe {
+			let handle = lparam as HRAWINPUT;
+			let header_size = mem::size_of::<RAWINPUTHEADER>() as UINT;
+			let mut header: RAWINPUTHEADER = mem::zeroed();
+			let mut size = header_size;
+			if GetRawInputData(handle, RID_HEADER, &mut header as *mut _ as LPVOID, &mut size, header_size) == !0 {
+				return Err(ErrorCode::last());
+			}
+			if header.dwType != RIM_TYPEMOUSE && header.dwType != RIM_TYPEKEYBOARD {
+				return Ok(RawEvent::Other(header.dwType));
+			}
+			let mut raw: RAWINPUT = mem::zeroed();
+			let mut size = mem::size_of::<RAWINPUT>() as UINT;
+			if GetRawInputData(handle, RID_INPUT, &mut raw as *mut _ as LPVOID, &mut size, header_size) == !0 {
+				return Err(ErrorCode::last());
+			}
+			Ok(RawEvent::decode(&raw))
+		}
+	}
+}
+
+fn register_devices(kinds: &[RawKind], target: HWND, flags: DWORD) -> crate::Result<()> {
+	let devices: Vec<RAWINPUTDEVICE> = kinds.iter().map(|kind| {
+		let (usage_page, usage) = kind.usage();
+		RAWINPUTDEVICE { usUsagePage: usage_page, usUsage: usage, dwFlags: flags, hwndTarget: target }
+	}).collect();
+	unsafe {
+		if RegisterRawInputDevices(devices.as_ptr(), devices.len() as UINT, mem::size_of::<RAWINPUTDEVICE>() as UINT) == FALSE {
+			Err(ErrorCode::last())
+		}
+		else {
+			Ok(())
+		}
+	}
+}
+
+/// Decoded raw input.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RawEvent {
+	Mouse(RawMouse),
+	Keyboard(RawKeyboard),
+	/// Input from another kind of device, eg. `RIM_TYPEHID`.
+	Other(u32),
+}
+impl RawEvent {
+	fn decode(raw: &RAWINPUT) -> RawEvent {
+		let device = raw.header.hDevice as usize;
+		unsafe {
+			match raw.header.dwType {
+				RIM_TYPEMOUSE => RawEvent::Mouse(RawMouse::decode(raw.data.mouse(), device)),
+				RIM_TYPEKEYBOARD => RawEvent::Keyboard(RawKeyboard::decode(raw.data.keyboard(), device)),
+				ty => RawEvent::Other(ty),
+			}
+		}
+	}
+}
+
+/// Raw mouse motion.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RawMotion {
+	/// Motion relative to the last event in device units, typically mickeys.
+	Relative { dx: i32, dy: i32 },
+	/// Absolute position normalized to `0..=65535`, eg. from a tablet or remote desktop.
+	///
+	/// Spans the virtual screen if `virtual_desk` is set, the primary monitor otherwise.
+	Absolute { x: i32, y: i32, virtual_desk: bool },
+}
+
+// Not defined by winapi
+const RI_MOUSE_HWHEEL: USHORT = 0x0800;
+
+/// Raw mouse input.
+///
+/// See [RAWMOUSE structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms645578.aspx) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RawMouse {
+	device: usize,
+	motion: RawMotion,
+	button_flags: u16,
+	button_data: u16,
+}
+impl RawMouse {
+	fn decode(mouse: &RAWMOUSE, device: usize) -> RawMouse {
+		let motion = if mouse.usFlags & MOUSE_MOVE_ABSOLUTE != 0 {
+			RawMotion::Absolute { x: mouse.lLastX, y: mouse.lLastY, virtual_desk: mouse.usFlags & MOUSE_VIRTUAL_DESKTOP != 0 }
+		}
+		else {
+			RawMotion::Relative { dx: mouse.lLastX, dy: mouse.lLastY }
+		};
+		RawMouse { device, motion, button_flags: mouse.usButtonFlags, button_data: mouse.usButtonData }
+	}
+	/// Returns the device handle, distinguishes between multiple mice.
+	pub fn device(&self) -> usize {
+		self.device
+	}
+	/// Returns the mouse motion.
+	pub fn motion(&self) -> RawMotion {
+		self.motion
+	}
+	/// Returns the relative motion, zero for absolute motion.
+	pub fn delta(&self) -> (i32, i32) {
+		match self.motion {
+			RawMotion::Relative { dx, dy } => (dx, dy),
+			RawMotion::Absolute { .. } => (0, 0),
+		}
+	}
+	/// Returns if the mouse button transitioned down (`Some(true)`) or up (`Some(false)`) in this event.
+	///
+	/// Panics if the virtual key is not a mouse button.
+	pub fn button(&self, button: VirtualKey) -> Option<bool> {
+		let (down, up) = match button {
+			VirtualKey::LBUTTON => (RI_MOUSE_LEFT_BUTTON_DOWN, RI_MOUSE_LEFT_BUTTON_UP),
+			VirtualKey::RBUTTON => (RI_MOUSE_RIGHT_BUTTON_DOWN, RI_MOUSE_RIGHT_BUTTON_UP),
+			VirtualKey::MBUTTON => (RI_MOUSE_MIDDLE_BUTTON_DOWN, RI_MOUSE_MIDDLE_BUTTON_UP),
+			VirtualKey::XBUTTON1 => (RI_MOUSE_BUTTON_4_DOWN, RI_MOUSE_BUTTON_4_UP),
+			VirtualKey::XBUTTON2 => (RI_MOUSE_BUTTON_5_DOWN, RI_MOUSE_BUTTON_5_UP),
+			_ => panic!("not a mouse button: {:?}", button),
+		};
+		if self.button_flags & down != 0 { Some(true) }
+		else if self.button_flags & up != 0 { Some(false) }
+		else { None }
+	}
+	/// Returns the button transition flags, eg. `RI_MOUSE_LEFT_BUTTON_DOWN`.
+	pub fn button_flags(&self) -> u16 {
+		self.button_flags
+	}
+	/// Returns the vertical wheel delta, zero if the wheel did not move.
+	pub fn wheel(&self) -> i16 {
+		if self.button_flags & RI_MOUSE_WHEEL != 0 { self.button_data as i16 } else { 0 }
+	}
+	/// Returns the horizontal wheel delta, zero if the wheel did not move.
+	pub fn hwheel(&self) -> i16 {
+		if self.button_flags & RI_MOUSE_HWHEEL != 0 { self.button_data as i16 } else { 0 }
+	}
+}
+
+/// Raw keyboard input.
+///
+/// See [RAWKEYBOARD structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms645575.aspx) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RawKeyboard {
+	device: usize,
+	make_code: u16,
+	flags: u16,
+	vk: VirtualKey,
+	message: u32,
+}
+impl RawKeyboard {
+	fn decode(keyboard: &RAWKEYBOARD, device: usize) -> RawKeyboard {
+		RawKeyboard {
+			device,
+			make_code: keyboard.MakeCode,
+			flags: keyboard.Flags,
+			vk: VirtualKey::from(keyboard.VKey as DWORD),
+			message: keyboard.Message,
+		}
+	}
+	/// Returns the device handle, distinguishes between multiple keyboards.
+	pub fn device(&self) -> usize {
+		self.device
+	}
+	/// Returns the hardware scan code, see [`is_extended`](RawKeyboard::is_extended) for the `E0` prefix.
+	pub fn make_code(&self) -> u16 {
+		self.make_code
+	}
+	/// Returns the virtual key.
+	pub fn vk(&self) -> VirtualKey {
+		self.vk
+	}
+	/// Returns if the key was pressed.
+	pub fn is_down(&self) -> bool {
+		self.flags as DWORD & RI_KEY_BREAK == 0
+	}
+	/// Returns if the key was released.
+	pub fn is_up(&self) -> bool {
+		self.flags as DWORD & RI_KEY_BREAK != 0
+	}
+	/// Returns if the scan code has the `E0` prefix, eg. the right control key or the arrow keys.
+	pub fn is_extended(&self) -> bool {
+		self.flags as DWORD & RI_KEY_E0 != 0
+	}
+	/// Returns the corresponding window message, eg. `WM_KEYDOWN`.
+	pub fn message(&self) -> u32 {
+		self.message
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_send_empty() {
+	assert_eq!(send(&[]), Ok(0));
+}
+
+#[test]
+fn test_keyboard_input() {
+	let input = KeyboardInput::key_up(VirtualKey::SPACE).extended().tagged(42);
+	assert_eq!(input.flags(), KEYEVENTF_KEYUP | KEYEVENTF_EXTENDEDKEY);
+	let raw = input.to_raw();
+	assert_eq!(raw.type_, INPUT_KEYBOARD);
+	let ki = unsafe { raw.u.ki() };
+	assert_eq!(ki.wVk, 0x20);
+	assert_eq!(ki.dwExtraInfo, 42);
+}
+
+#[test]
+fn test_raw_decode() {
+	let mut raw: RAWINPUT = unsafe { mem::zeroed() };
+	raw.header.dwType = RIM_TYPEMOUSE;
+	unsafe {
+		let mouse = raw.data.mouse_mut();
+		mouse.lLastX = -3;
+		mouse.lLastY = 5;
+		mouse.usButtonFlags = RI_MOUSE_LEFT_BUTTON_DOWN | RI_MOUSE_BUTTON_5_UP | RI_MOUSE_WHEEL;
+		mouse.usButtonData = -120i16 as u16;
+	}
+	let mouse = match RawEvent::decode(&raw) {
+		RawEvent::Mouse(mouse) => mouse,
+		event => panic!("unexpected {:?}", event),
+	};
+	assert_eq!(mouse.delta(), (-3, 5));
+	assert_eq!(mouse.button(VirtualKey::LBUTTON), Some(true));
+	assert_eq!(mouse.button(VirtualKey::XBUTTON2), Some(false));
+	assert_eq!(mouse.button(VirtualKey::RBUTTON), None);
+	assert_eq!((mouse.wheel(), mouse.hwheel()), (-120, 0));
+
+	let mut raw: RAWINPUT = unsafe { mem::zeroed() };
+	raw.header.dwType = RIM_TYPEKEYBOARD;
+	unsafe {
+		let keyboard = raw.data.keyboard_mut();
+		keyboard.MakeCode = 0x1d;
+		keyboard.Flags = (RI_KEY_BREAK | RI_KEY_E0) as u16;
+		keyboard.VKey = VK_CONTROL as u16;
+	}
+	let keyboard = match RawEvent::decode(&raw) {
+		RawEvent::Keyboard(keyboard) => keyboard,
+		event => panic!("unexpected {:?}", event),
+	};
+	assert_eq!(keyboard.vk(), VirtualKey::CTRL);
+	assert!(keyboard.is_up() && keyboard.is_extended());
+}
+
+#[test]
+fn test_raw_register() {
+	let window = crate::wndclass::message_window(|_, _, _, _| None).unwrap();
+	RawInput::register(&[RawKind::Mouse, RawKind::Keyboard], window.window(), RawInputFlags::INPUTSINK).unwrap();
+	RawInput::unregister(&[RawKind::Mouse, RawKind::Keyboard]).unwrap();
+}
+
+#[test]
+fn test_string_inputs() {
+	let inputs = string_inputs("a\r\n\u{1F600}\r");
+	assert_eq!(inputs.len(), 4);
+	assert_eq!(inputs[0], [KeyboardInput::unicode_down(0x61), KeyboardInput::unicode_up(0x61)]);
+	assert_eq!(inputs[1], [KeyboardInput::key_down(VirtualKey::RETURN), KeyboardInput::key_up(VirtualKey::RETURN)]);
+	assert_eq!(inputs[2], [
+		KeyboardInput::unicode_down(0xD83D), KeyboardInput::unicode_up(0xD83D),
+		KeyboardInput::unicode_down(0xDE00), KeyboardInput::unicode_up(0xDE00),
+	]);
+	assert_eq!(inputs[3], inputs[1]);
+	assert!(string_inputs("").is_empty());
+}
+
+#[test]
+fn test_release_inputs() {
+	let inputs = release_inputs(&[VirtualKey::LSHIFT, VirtualKey::RCTRL, VirtualKey::RWIN]);
+	assert_eq!(inputs.len(), 5);
+	assert_eq!(inputs[0], KeyboardInput::key_down(VirtualKey::new(0xfc)));
+	assert_eq!(inputs[1], KeyboardInput::key_up(VirtualKey::new(0xfc)));
+	assert_eq!(inputs[2], KeyboardInput::key_up(VirtualKey::LSHIFT));
+	assert_eq!(inputs[3], KeyboardInput::key_up(VirtualKey::RCTRL).extended());
+	assert_eq!(inputs[4], KeyboardInput::key_up(VirtualKey::RWIN).extended());
+	assert_eq!(release_inputs(&[VirtualKey::LALT]), [KeyboardInput::key_up(VirtualKey::LALT)]);
+}
+
+#[test]
+fn test_scan_code_input() {
+	let input = KeyboardInput::scan_code_up(ScanCode::new(0x1d, true));
+	assert_eq!(input.flags(), KEYEVENTF_SCANCODE | KEYEVENTF_EXTENDEDKEY | KEYEVENTF_KEYUP);
+	let ki = unsafe { *input.to_raw().u.ki() };
+	assert_eq!((ki.wVk, ki.wScan), (0, 0x1d));
+}
+
+#[test]
+#[should_panic]
+fn test_scan_code_input_pause() {
+	let _ = KeyboardInput::scan_code_down(VirtualKey::PAUSE.scan_code());
+}
@@ -6,6 +6,12 @@ Externals.
 
 #![cfg_attr(feature = "nightly", feature(asm))]
 
+macro_rules! wide_str {
+    ($($c:tt)+) => {
+        [$($c as u16,)+]
+    }
+}
+
 mod util;
 pub use self::util::*;
 
@@ -13,12 +19,6 @@ pub use self::util::*;
 mod inner;
 pub use self::inner::*;
 
-macro_rules! wide_str {
-    ($($c:tt)+) => {
-        [$($c as u16,)+]
-    }
-}
-
 mod winapi;
 
 pub type Result<T> = std::result::Result<T, error::ErrorCode>;
@@ -30,13 +30,18 @@ pub mod thread;
 pub mod window;
 pub mod wndclass;
 pub mod hook;
+pub mod winevent;
+pub mod hotkey;
 pub mod vk;
 pub mod memory;
+pub mod pattern;
 // #[cfg(target_arch = "x86_64")]
 // mod memory_x86_64;
 pub mod mouse;
+pub mod input;
 pub mod control;
 pub mod snap;
+pub mod draw;
 pub mod system;
 
 pub mod prelude;
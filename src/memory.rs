@@ -1,4 +1,6 @@
 use std::{fmt, ops, ptr, mem};
+use std::ffi::OsString;
+use intptr::IntPtr;
 use crate::winapi::*;
 use crate::error::ErrorCode;
 use crate::Result;
@@ -86,8 +88,117 @@ impl MemoryType {
 	pub const PRIVATE: MemoryType = MemoryType(0x20000);
 }
 
+/// State of the pages in a region.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RegionState {
+	/// Pages for which physical storage has been allocated.
+	Commit,
+	/// Pages reserved without allocating any physical storage.
+	Reserve,
+	/// Free pages not accessible to the process and available to be allocated.
+	Free,
+}
+impl RegionState {
+	/// Converts from the `MEM_COMMIT`, `MEM_RESERVE` or `MEM_FREE` constants.
+	pub fn from_raw(state: u32) -> Option<RegionState> {
+		match state {
+			MEM_COMMIT => Some(RegionState::Commit),
+			MEM_RESERVE => Some(RegionState::Reserve),
+			MEM_FREE => Some(RegionState::Free),
+			_ => None,
+		}
+	}
+}
+
+/// Contiguous range of pages with the same state, protection and type.
+#[derive(Clone, Debug)]
+pub struct Region {
+	/// The address range of the region.
+	pub range: ops::Range<IntPtr>,
+	/// The state of the pages in the region.
+	pub state: RegionState,
+	/// The access protection of the pages in the region, zero for reserved and free regions.
+	pub protect: Protect,
+	/// The type of the pages in the region, zero for free regions.
+	pub ty: MemoryType,
+}
+impl From<&MemoryInformation> for Region {
+	fn from(mi: &MemoryInformation) -> Region {
+		Region {
+			range: mi.range(),
+			state: mi.state(),
+			protect: mi.protect(),
+			ty: mi.ty(),
+		}
+	}
+}
+
+/// Virtual memory allocation aggregated over all its regions.
+#[derive(Clone, Debug)]
+pub struct MemoryAllocation {
+	/// The base address of the allocation.
+	pub base: IntPtr,
+	/// The combined size of the regions in the allocation.
+	pub size: usize,
+	/// The memory protection when the allocation was initially made.
+	pub protect: Protect,
+	/// The memory type of the allocation.
+	pub ty: MemoryType,
+	/// The states of all regions OR'd together.
+	pub region_states: u32,
+	/// The protections of all regions OR'd together.
+	pub region_protects: Protect,
+	/// The memory types of all regions OR'd together.
+	pub region_types: MemoryType,
+	/// The name of the mapped file for image and mapped allocations.
+	pub file_name: Option<OsString>,
+}
+
 pub struct MemoryInformation(MEMORY_BASIC_INFORMATION);
 impl_inner!(MemoryInformation: MEMORY_BASIC_INFORMATION);
+impl MemoryInformation {
+	/// The base address of the region.
+	#[inline]
+	pub fn base_address(&self) -> IntPtr {
+		IntPtr::from_usize(self.0.BaseAddress as usize)
+	}
+	/// The base address of the allocation containing the region.
+	#[inline]
+	pub fn allocation_base(&self) -> IntPtr {
+		IntPtr::from_usize(self.0.AllocationBase as usize)
+	}
+	/// The memory protection when the allocation was initially made.
+	#[inline]
+	pub fn allocation_protect(&self) -> Protect {
+		Protect(self.0.AllocationProtect)
+	}
+	/// The size of the region in bytes.
+	#[inline]
+	pub fn region_size(&self) -> usize {
+		self.0.RegionSize
+	}
+	/// The address range of the region.
+	#[inline]
+	pub fn range(&self) -> ops::Range<IntPtr> {
+		let start = self.0.BaseAddress as usize;
+		IntPtr::from_usize(start)..IntPtr::from_usize(start.wrapping_add(self.0.RegionSize))
+	}
+	/// The access protection of the pages in the region.
+	#[inline]
+	pub fn protect(&self) -> Protect {
+		Protect(self.0.Protect)
+	}
+	/// The state of the pages in the region.
+	#[inline]
+	pub fn state(&self) -> RegionState {
+		RegionState::from_raw(self.0.State).unwrap_or(RegionState::Free)
+	}
+	/// The type of the pages in the region.
+	#[inline]
+	pub fn ty(&self) -> MemoryType {
+		MemoryType(self.0.Type)
+	}
+}
 impl ops::Deref for MemoryInformation {
 	type Target = MEMORY_BASIC_INFORMATION;
 	fn deref(&self) -> &MEMORY_BASIC_INFORMATION {
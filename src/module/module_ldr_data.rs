@@ -1,5 +1,5 @@
 use std::{fmt, slice};
-use std::ffi::OsString;
+use std::ffi::{CStr, OsString};
 use std::os::windows::ffi::OsStringExt;
 use crate::winapi::*;
 
@@ -182,6 +182,42 @@ impl ModuleDataEntry {
 	pub fn time_date_stamp(self) -> u32 {
 		unsafe { (*self.ptr).TimeDateStamp }
 	}
+	/// Resolves an exported function by name from the module's in-memory export table.
+	///
+	/// Equivalent to `GetProcAddress` without calling it.
+	/// Forwarded exports are not followed and return `None`.
+	pub fn proc_address(self, name: &str) -> Option<*const u8> {
+		unsafe {
+			let base = self.base_address() as *const u8;
+			let dos = &*(base as *const IMAGE_DOS_HEADER);
+			if dos.e_magic != IMAGE_DOS_SIGNATURE {
+				return None;
+			}
+			let nt = &*(base.offset(dos.e_lfanew as isize) as *const IMAGE_NT_HEADERS);
+			if nt.Signature != IMAGE_NT_SIGNATURE {
+				return None;
+			}
+			let dir = nt.OptionalHeader.DataDirectory[IMAGE_DIRECTORY_ENTRY_EXPORT as usize];
+			if dir.VirtualAddress == 0 {
+				return None;
+			}
+			let exports = &*(base.offset(dir.VirtualAddress as isize) as *const IMAGE_EXPORT_DIRECTORY);
+			let names = slice::from_raw_parts(base.offset(exports.AddressOfNames as isize) as *const u32, exports.NumberOfNames as usize);
+			let ordinals = slice::from_raw_parts(base.offset(exports.AddressOfNameOrdinals as isize) as *const u16, exports.NumberOfNames as usize);
+			let functions = slice::from_raw_parts(base.offset(exports.AddressOfFunctions as isize) as *const u32, exports.NumberOfFunctions as usize);
+			// The export names are sorted to allow binary searching
+			let index = names.binary_search_by(|&rva| {
+				let export_name = CStr::from_ptr(base.offset(rva as isize) as *const c_char);
+				export_name.to_bytes().cmp(name.as_bytes())
+			}).ok()?;
+			let rva = *functions.get(ordinals[index] as usize)?;
+			// Forwarded exports point to a string inside the export directory
+			if rva >= dir.VirtualAddress && rva < dir.VirtualAddress + dir.Size {
+				return None;
+			}
+			Some(base.offset(rva as isize))
+		}
+	}
 }
 impl fmt::Debug for ModuleDataEntry {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -227,3 +263,14 @@ fn initialization_order() {
 		println!("{:#?}", entry);
 	}
 }
+
+#[test]
+fn proc_address() {
+	let peb = crate::process::ProcessEnvironmentBlock::current();
+	let ntdll = peb.loader_data().load_order()
+		.find(|entry| entry.base_dll_name().to_string_lossy().eq_ignore_ascii_case("ntdll.dll"))
+		.unwrap();
+	let expected = unsafe { GetProcAddress(ntdll.base_address() as HMODULE, b"NtClose\0".as_ptr() as *const c_char) };
+	assert_eq!(ntdll.proc_address("NtClose"), Some(expected as *const u8));
+	assert_eq!(ntdll.proc_address("DoesNotExist"), None);
+}
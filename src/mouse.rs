@@ -2,7 +2,12 @@
 Mouse input.
 !*/
 
+use std::{mem, ptr};
 use crate::winapi::*;
+use crate::error::ErrorCode;
+use crate::vk::VirtualKey;
+use crate::window::Window;
+use crate::snap::Rect;
 
 #[derive(Copy, Clone, Debug)]
 pub struct Mouse;
@@ -10,17 +15,17 @@ impl Mouse {
 	/// Move the mouse relatively.
 	#[inline]
 	pub fn mouse_move(self, dx: i32, dy: i32) {
-		unsafe { mouse_event(MOUSEEVENTF_MOVE, dx as DWORD, dy as DWORD, 0, 0); }
+		send_event(MOUSEEVENTF_MOVE, dx as DWORD, dy as DWORD, 0);
 	}
 	/// Set the mouse position in absolute pixel coordinates.
 	#[inline]
 	pub fn mouse_set(self, dx: u32, dy: u32) {
-		unsafe { mouse_event(MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE, dx as DWORD, dy as DWORD, 0, 0); }
+		send_event(MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE, dx as DWORD, dy as DWORD, 0);
 	}
 	/// Scroll the mouse wheel.
 	#[inline]
 	pub fn mouse_wheel(self, delta: i32) {
-		unsafe { mouse_event(MOUSEEVENTF_WHEEL, 0, 0, delta as DWORD, 0); }
+		send_event(MOUSEEVENTF_WHEEL, 0, 0, delta as DWORD);
 	}
 
 	/// Interact with the left mouse button.
@@ -32,12 +37,12 @@ impl Mouse {
 	/// Press the left mouse button.
 	#[inline]
 	pub fn left_down(self) {
-		unsafe { mouse_event(MOUSEEVENTF_LEFTDOWN, 0, 0, 0, 0); }
+		send_event(MOUSEEVENTF_LEFTDOWN, 0, 0, 0);
 	}
 	/// Release the left mouse button.
 	#[inline]
 	pub fn left_up(self) {
-		unsafe { mouse_event(MOUSEEVENTF_LEFTUP, 0, 0, 0, 0); }
+		send_event(MOUSEEVENTF_LEFTUP, 0, 0, 0);
 	}
 
 	/// Interact with the right mouse button.
@@ -49,12 +54,12 @@ impl Mouse {
 	/// Press the right mouse button.
 	#[inline]
 	pub fn right_down(self) {
-		unsafe { mouse_event(MOUSEEVENTF_RIGHTDOWN, 0, 0, 0, 0); }
+		send_event(MOUSEEVENTF_RIGHTDOWN, 0, 0, 0);
 	}
 	/// Release the right mouse button.
 	#[inline]
 	pub fn right_up(self) {
-		unsafe { mouse_event(MOUSEEVENTF_RIGHTUP, 0, 0, 0, 0); }
+		send_event(MOUSEEVENTF_RIGHTUP, 0, 0, 0);
 	}
 
 	/// Interact with the middle mouse button.
@@ -66,12 +71,48 @@ impl Mouse {
 	/// Press the middle mouse button.
 	#[inline]
 	pub fn middle_down(self) {
-		unsafe { mouse_event(MOUSEEVENTF_MIDDLEDOWN, 0, 0, 0, 0); }
+		send_event(MOUSEEVENTF_MIDDLEDOWN, 0, 0, 0);
 	}
 	/// Release the middle mouse button.
 	#[inline]
 	pub fn middle_up(self) {
-		unsafe { mouse_event(MOUSEEVENTF_MIDDLEUP, 0, 0, 0, 0); }
+		send_event(MOUSEEVENTF_MIDDLEUP, 0, 0, 0);
+	}
+
+	/// Interact with the primary mouse button, this is the right button when the buttons are swapped.
+	///
+	/// See [`vk::primary_button`](crate::vk::primary_button) for more information.
+	#[inline]
+	pub fn primary(self, down: bool) {
+		if down { self.primary_down() }
+		else { self.primary_up() }
+	}
+	/// Press the primary mouse button.
+	#[inline]
+	pub fn primary_down(self) {
+		if crate::vk::swap_buttons() { self.right_down() } else { self.left_down() }
+	}
+	/// Release the primary mouse button.
+	#[inline]
+	pub fn primary_up(self) {
+		if crate::vk::swap_buttons() { self.right_up() } else { self.left_up() }
+	}
+
+	/// Interact with the secondary mouse button, this is the left button when the buttons are swapped.
+	#[inline]
+	pub fn secondary(self, down: bool) {
+		if down { self.secondary_down() }
+		else { self.secondary_up() }
+	}
+	/// Press the secondary mouse button.
+	#[inline]
+	pub fn secondary_down(self) {
+		if crate::vk::swap_buttons() { self.left_down() } else { self.right_down() }
+	}
+	/// Release the secondary mouse button.
+	#[inline]
+	pub fn secondary_up(self) {
+		if crate::vk::swap_buttons() { self.left_up() } else { self.right_up() }
 	}
 
 	/// Interact with the xbutton1 mouse button.
@@ -83,12 +124,12 @@ impl Mouse {
 	/// Press the xbutton1 mouse button.
 	#[inline]
 	pub fn xbutton1_down(self) {
-		unsafe { mouse_event(MOUSEEVENTF_XDOWN, 0, 0, XBUTTON1 as DWORD, 0); }
+		send_event(MOUSEEVENTF_XDOWN, 0, 0, XBUTTON1 as DWORD);
 	}
 	/// Release the xbutton1 mouse button.
 	#[inline]
 	pub fn xbutton1_up(self) {
-		unsafe { mouse_event(MOUSEEVENTF_XUP, 0, 0, XBUTTON1 as DWORD, 0); }
+		send_event(MOUSEEVENTF_XUP, 0, 0, XBUTTON1 as DWORD);
 	}
 
 	/// Interact with the xbutton2 mouse button.
@@ -100,12 +141,71 @@ impl Mouse {
 	/// Press the xbutton2 mouse button.
 	#[inline]
 	pub fn xbutton2_down(self) {
-		unsafe { mouse_event(MOUSEEVENTF_XDOWN, 0, 0, XBUTTON2 as DWORD, 0); }
+		send_event(MOUSEEVENTF_XDOWN, 0, 0, XBUTTON2 as DWORD);
 	}
 	/// Release the xbutton2 mouse button.
 	#[inline]
 	pub fn xbutton2_up(self) {
-		unsafe { mouse_event(MOUSEEVENTF_XUP, 0, 0, XBUTTON2 as DWORD, 0); }
+		send_event(MOUSEEVENTF_XUP, 0, 0, XBUTTON2 as DWORD);
+	}
+
+	/// Gets the cursor position in screen coordinates.
+	///
+	/// Fails when the input desktop is not the current desktop, eg. while the secure desktop is shown.
+	///
+	/// See [GetCursorPos function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms648390.aspx) for more information.
+	pub fn position(self) -> crate::Result<(i32, i32)> {
+		unsafe {
+			let mut pt: POINT = mem::zeroed();
+			if GetCursorPos(&mut pt) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok((pt.x, pt.y))
+			}
+		}
+	}
+	/// Moves the cursor to the screen coordinates.
+	///
+	/// Unlike [`mouse_set`](Mouse::mouse_set) this sets the cursor position directly instead of injecting a mouse event.
+	///
+	/// See [SetCursorPos function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms648394.aspx) for more information.
+	pub fn set_position(self, x: i32, y: i32) -> crate::Result<()> {
+		unsafe {
+			if SetCursorPos(x, y) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Gets the cursor position in client coordinates of the window.
+	pub fn position_in(self, window: Window) -> crate::Result<(i32, i32)> {
+		window.screen_to_client(self.position()?)
+	}
+
+	/// Confines the cursor to the rectangle in screen coordinates until the returned guard is dropped.
+	///
+	/// The guard restores the previous clip rectangle, nested guards must be dropped in reverse order.
+	///
+	/// See [ClipCursor function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms648383.aspx) for more information.
+	pub fn clip_to(self, rect: Rect) -> crate::Result<ClipGuard> {
+		unsafe {
+			let mut previous: RECT = mem::zeroed();
+			if GetClipCursor(&mut previous) == FALSE {
+				return Err(ErrorCode::last());
+			}
+			let rc = RECT { left: rect.left, top: rect.top, right: rect.left + rect.width, bottom: rect.top + rect.height };
+			if ClipCursor(&rc) == FALSE {
+				return Err(ErrorCode::last());
+			}
+			Ok(ClipGuard { previous: Rect::from(previous) })
+		}
+	}
+	/// Confines the cursor to the window rectangle until the returned guard is dropped.
+	pub fn clip_to_window(self, window: Window) -> crate::Result<ClipGuard> {
+		self.clip_to(window.rect()?)
 	}
 
 	/// Gets the primary screen size for use with mouse movement.
@@ -119,6 +219,53 @@ impl Mouse {
 	}
 }
 
+/// Cursor clip rectangle guard.
+///
+/// Created by [`Mouse::clip_to`], restores the previous clip rectangle on drop.
+#[derive(Debug)]
+#[must_use]
+pub struct ClipGuard {
+	previous: Rect,
+}
+impl ClipGuard {
+	/// Restores the previous clip rectangle now.
+	pub fn release(self) -> crate::Result<()> {
+		let result = self.restore();
+		mem::forget(self);
+		result
+	}
+	fn restore(&self) -> crate::Result<()> {
+		unsafe {
+			// Without a clip rectangle GetClipCursor returns the virtual screen, restore this as no clipping at all
+			let previous = self.previous;
+			let unclipped = GetSystemMetrics(SM_XVIRTUALSCREEN) == previous.left &&
+				GetSystemMetrics(SM_YVIRTUALSCREEN) == previous.top &&
+				GetSystemMetrics(SM_CXVIRTUALSCREEN) == previous.width &&
+				GetSystemMetrics(SM_CYVIRTUALSCREEN) == previous.height;
+			let rc = RECT { left: previous.left, top: previous.top, right: previous.left + previous.width, bottom: previous.top + previous.height };
+			if ClipCursor(if unclipped { ptr::null() } else { &rc }) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+}
+impl Drop for ClipGuard {
+	fn drop(&mut self) {
+		let _ = self.restore();
+	}
+}
+
+// Sends a single untagged mouse event, the methods of `Mouse` have nowhere to report errors.
+fn send_event(flags: u32, dx: DWORD, dy: DWORD, mouse_data: DWORD) {
+	let _ = MouseInput { dx: dx as i32, dy: dy as i32, mouse_data, flags, extra_info: 0 }.send();
+}
+
+/// Mouse input event.
+///
+/// See [MOUSEINPUT structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646273.aspx) for more information.
 #[derive(Copy, Clone, Debug, Default)]
 #[repr(C)]
 pub struct MouseInput {
@@ -126,19 +273,235 @@ pub struct MouseInput {
 	dy: i32,
 	mouse_data: u32,
 	flags: u32,
+	extra_info: usize,
 }
 impl MouseInput {
+	/// Moves the mouse relatively, subject to the mouse speed and acceleration settings.
 	#[must_use]
 	pub const fn mouse_move(dx: i32, dy: i32) -> MouseInput {
-		MouseInput { dx, dy, mouse_data: 0, flags: MOUSEEVENTF_MOVE }
+		MouseInput { dx, dy, mouse_data: 0, flags: MOUSEEVENTF_MOVE, extra_info: 0 }
 	}
+	/// Moves the mouse to normalized absolute coordinates, `0..=65535` spans the primary monitor.
+	///
+	/// See [`mouse_absmove_pixels`](MouseInput::mouse_absmove_pixels) to move to pixel coordinates.
 	#[must_use]
 	pub const fn mouse_absmove(x: u32, y: u32) -> MouseInput {
-		MouseInput { dx: x as i32, dy: y as i32, mouse_data: 0, flags: MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE }
+		MouseInput { dx: x as i32, dy: y as i32, mouse_data: 0, flags: MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE, extra_info: 0 }
+	}
+	/// Moves the mouse to the absolute pixel coordinates on the virtual screen.
+	///
+	/// Absolute mouse coordinates are normalized to `0..=65535` over the virtual screen spanning all monitors,
+	/// the virtual screen metrics are read when the event is created.
+	#[must_use]
+	pub fn mouse_absmove_pixels(x: i32, y: i32) -> MouseInput {
+		let (left, top, width, height) = unsafe {(
+			GetSystemMetrics(SM_XVIRTUALSCREEN),
+			GetSystemMetrics(SM_YVIRTUALSCREEN),
+			GetSystemMetrics(SM_CXVIRTUALSCREEN),
+			GetSystemMetrics(SM_CYVIRTUALSCREEN),
+		)};
+		let dx = normalize(x, left, width);
+		let dy = normalize(y, top, height);
+		MouseInput { dx, dy, mouse_data: 0, flags: MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK, extra_info: 0 }
+	}
+	/// Presses the left mouse button.
+	#[must_use]
+	pub const fn left_down() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: 0, flags: MOUSEEVENTF_LEFTDOWN, extra_info: 0 }
+	}
+	/// Releases the left mouse button.
+	#[must_use]
+	pub const fn left_up() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: 0, flags: MOUSEEVENTF_LEFTUP, extra_info: 0 }
+	}
+	/// Presses the right mouse button.
+	#[must_use]
+	pub const fn right_down() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: 0, flags: MOUSEEVENTF_RIGHTDOWN, extra_info: 0 }
+	}
+	/// Releases the right mouse button.
+	#[must_use]
+	pub const fn right_up() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: 0, flags: MOUSEEVENTF_RIGHTUP, extra_info: 0 }
+	}
+	/// Presses the middle mouse button.
+	#[must_use]
+	pub const fn middle_down() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: 0, flags: MOUSEEVENTF_MIDDLEDOWN, extra_info: 0 }
+	}
+	/// Releases the middle mouse button.
+	#[must_use]
+	pub const fn middle_up() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: 0, flags: MOUSEEVENTF_MIDDLEUP, extra_info: 0 }
+	}
+	/// Presses the xbutton1 mouse button.
+	#[must_use]
+	pub const fn xbutton1_down() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: XBUTTON1 as u32, flags: MOUSEEVENTF_XDOWN, extra_info: 0 }
 	}
-	pub fn send(self) {
-		unsafe { mouse_event(self.flags, self.dx as u32, self.dy as u32, self.mouse_data, 0); }
+	/// Releases the xbutton1 mouse button.
+	#[must_use]
+	pub const fn xbutton1_up() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: XBUTTON1 as u32, flags: MOUSEEVENTF_XUP, extra_info: 0 }
+	}
+	/// Presses the xbutton2 mouse button.
+	#[must_use]
+	pub const fn xbutton2_down() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: XBUTTON2 as u32, flags: MOUSEEVENTF_XDOWN, extra_info: 0 }
 	}
+	/// Releases the xbutton2 mouse button.
+	#[must_use]
+	pub const fn xbutton2_up() -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: XBUTTON2 as u32, flags: MOUSEEVENTF_XUP, extra_info: 0 }
+	}
+	/// Presses a mouse button.
+	///
+	/// Panics if the virtual key is not a mouse button.
+	#[must_use]
+	pub fn button_down(button: VirtualKey) -> MouseInput {
+		let (flags, mouse_data) = match button {
+			VirtualKey::LBUTTON => (MOUSEEVENTF_LEFTDOWN, 0),
+			VirtualKey::RBUTTON => (MOUSEEVENTF_RIGHTDOWN, 0),
+			VirtualKey::MBUTTON => (MOUSEEVENTF_MIDDLEDOWN, 0),
+			VirtualKey::XBUTTON1 => (MOUSEEVENTF_XDOWN, XBUTTON1 as u32),
+			VirtualKey::XBUTTON2 => (MOUSEEVENTF_XDOWN, XBUTTON2 as u32),
+			_ => panic!("not a mouse button: {:?}", button),
+		};
+		MouseInput { dx: 0, dy: 0, mouse_data, flags, extra_info: 0 }
+	}
+	/// Releases a mouse button.
+	///
+	/// Panics if the virtual key is not a mouse button.
+	#[must_use]
+	pub fn button_up(button: VirtualKey) -> MouseInput {
+		let (flags, mouse_data) = match button {
+			VirtualKey::LBUTTON => (MOUSEEVENTF_LEFTUP, 0),
+			VirtualKey::RBUTTON => (MOUSEEVENTF_RIGHTUP, 0),
+			VirtualKey::MBUTTON => (MOUSEEVENTF_MIDDLEUP, 0),
+			VirtualKey::XBUTTON1 => (MOUSEEVENTF_XUP, XBUTTON1 as u32),
+			VirtualKey::XBUTTON2 => (MOUSEEVENTF_XUP, XBUTTON2 as u32),
+			_ => panic!("not a mouse button: {:?}", button),
+		};
+		MouseInput { dx: 0, dy: 0, mouse_data, flags, extra_info: 0 }
+	}
+	/// Scrolls the mouse wheel, one notch is `WHEEL_DELTA` (120).
+	#[must_use]
+	pub const fn wheel(delta: i32) -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: delta as u32, flags: MOUSEEVENTF_WHEEL, extra_info: 0 }
+	}
+	/// Scrolls the horizontal mouse wheel, positive values scroll to the right.
+	#[must_use]
+	pub const fn hwheel(delta: i32) -> MouseInput {
+		MouseInput { dx: 0, dy: 0, mouse_data: delta as u32, flags: MOUSEEVENTF_HWHEEL, extra_info: 0 }
+	}
+	/// Tags the event with extra info.
+	///
+	/// Low level hooks can recognize the tagged event with `MouseLL::is_self_injected_by`.
+	#[must_use]
+	pub const fn tagged(self, tag: usize) -> MouseInput {
+		MouseInput { extra_info: tag, ..self }
+	}
+	/// Returns the flags, eg. `MOUSEEVENTF_MOVE`.
+	pub const fn flags(self) -> u32 {
+		self.flags
+	}
+	pub(crate) fn to_raw(self) -> INPUT {
+		unsafe {
+			let mut input: INPUT = mem::zeroed();
+			input.type_ = INPUT_MOUSE;
+			*input.u.mi_mut() = MOUSEINPUT {
+				dx: self.dx,
+				dy: self.dy,
+				mouseData: self.mouse_data,
+				dwFlags: self.flags,
+				time: 0,
+				dwExtraInfo: self.extra_info,
+			};
+			input
+		}
+	}
+	/// Sends the input.
+	///
+	/// See [`input::send`](crate::input::send) to send multiple events at once.
+	pub fn send(self) -> Result<(), ErrorCode> {
+		crate::input::send_one(self.to_raw())
+	}
+	/// Sends the input tagging the event with extra info.
+	///
+	/// Low level hooks can recognize the tagged event with `MouseLL::is_self_injected_by`.
+	pub fn send_tagged(self, tag: usize) -> Result<(), ErrorCode> {
+		self.tagged(tag).send()
+	}
+	/// Sends the inputs in order, they are not interspersed with other input.
+	///
+	/// Use this to inject a click as a button down and up pair.
+	/// See [`input::send`](crate::input::send) for the result.
+	pub fn send_batch(inputs: &[MouseInput]) -> Result<u32, (u32, ErrorCode)> {
+		let mut raw: Vec<INPUT> = inputs.iter().map(|input| input.to_raw()).collect();
+		crate::input::send_raw(&mut raw)
+	}
+}
+
+// Normalizes a pixel coordinate to 0..=65535 over the extent of the virtual screen.
+fn normalize(pixel: i32, origin: i32, extent: i32) -> i32 {
+	if extent <= 1 {
+		return 0;
+	}
+	let pixel = (pixel - origin).max(0).min(extent - 1) as i64;
+	((pixel * 65535 + (extent as i64 - 1) / 2) / (extent as i64 - 1)) as i32
 }
 
-// MouseInput::mouse_move(1, 1).send();
+#[test]
+fn test_mouse_input() {
+	let input = MouseInput::button_down(VirtualKey::XBUTTON2).tagged(7);
+	assert_eq!(input.flags(), MOUSEEVENTF_XDOWN);
+	let raw = input.to_raw();
+	assert_eq!(raw.type_, INPUT_MOUSE);
+	let mi = unsafe { raw.u.mi() };
+	assert_eq!(mi.mouseData, XBUTTON2 as u32);
+	assert_eq!(mi.dwExtraInfo, 7);
+	assert_eq!(MouseInput::wheel(-120).mouse_data as i32, -120);
+}
+
+#[test]
+fn test_normalize() {
+	assert_eq!(normalize(0, 0, 1920), 0);
+	assert_eq!(normalize(1919, 0, 1920), 65535);
+	assert_eq!(normalize(-1920, -1920, 3840), 0);
+	assert_eq!(normalize(0, -1920, 3840), 32776);
+	assert_eq!(normalize(5000, -1920, 3840), 65535);
+	assert_eq!(MouseInput::mouse_absmove_pixels(0, 0).flags(), MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK);
+	assert_eq!(MouseInput::send_batch(&[]), Ok(0));
+}
+
+#[test]
+fn test_position() {
+	// The cursor position is not available without an interactive desktop
+	if let Ok(pos) = Mouse.position() {
+		assert_eq!(Mouse.position_in(Window::desktop()), Ok(pos));
+	}
+}
+
+#[test]
+fn test_clip_to() {
+	fn clip() -> Rect {
+		unsafe {
+			let mut rc: RECT = mem::zeroed();
+			GetClipCursor(&mut rc);
+			Rect::from(rc)
+		}
+	}
+	let original = clip();
+	// Clipping the cursor requires an interactive desktop
+	let outer = match Mouse.clip_to(Rect { left: 10, top: 10, width: 100, height: 100 }) {
+		Ok(guard) => guard,
+		Err(_) => return,
+	};
+	assert_eq!(clip(), Rect { left: 10, top: 10, width: 100, height: 100 });
+	let inner = Mouse.clip_to(Rect { left: 20, top: 20, width: 10, height: 10 }).unwrap();
+	assert_eq!(clip(), Rect { left: 20, top: 20, width: 10, height: 10 });
+	drop(inner);
+	assert_eq!(clip(), Rect { left: 10, top: 10, width: 100, height: 100 });
+	outer.release().unwrap();
+	assert_eq!(clip(), original);
+}
@@ -0,0 +1,188 @@
+/*!
+Byte patterns with wildcards.
+!*/
+
+use std::{fmt, mem};
+
+/// Byte pattern with wildcards for signature scanning.
+///
+/// Scanning searches for the first non-wildcard byte a machine word at a time and only compares the full pattern at those candidates.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Pattern {
+	bytes: Vec<u8>,
+	mask: Vec<bool>,
+	// Offset and value of the first non-wildcard byte
+	anchor: Option<(usize, u8)>,
+}
+impl Pattern {
+	/// Creates a pattern from bytes and a mask where `x` matches the byte and `?` is a wildcard.
+	///
+	/// ```
+	/// use external::pattern::Pattern;
+	/// let pat = Pattern::from_code_style(b"\x48\x8B\x00\x89", "xx?x").unwrap();
+	/// assert_eq!(pat.find(&[0x90, 0x48, 0x8B, 0x05, 0x89]), Some(1));
+	/// ```
+	///
+	/// Returns `None` if the lengths differ or the mask contains other characters.
+	pub fn from_code_style(bytes: &[u8], mask: &str) -> Option<Pattern> {
+		if bytes.len() != mask.len() {
+			return None;
+		}
+		let mask = mask.bytes().map(|chr| match chr {
+			b'x' => Some(true),
+			b'?' => Some(false),
+			_ => None,
+		}).collect::<Option<Vec<bool>>>()?;
+		Some(Pattern::new(bytes.to_vec(), mask))
+	}
+	/// Creates a pattern from space separated hex bytes where `?` or `??` is a wildcard.
+	///
+	/// ```
+	/// use external::pattern::Pattern;
+	/// let pat = Pattern::from_ida("48 8B ? ? 89").unwrap();
+	/// assert_eq!(pat.find(&[0x48, 0x8B, 0x05, 0x00, 0x89]), Some(0));
+	/// ```
+	///
+	/// Returns `None` if any of the tokens is not a wildcard or exactly two hex digits.
+	pub fn from_ida(pattern: &str) -> Option<Pattern> {
+		let mut bytes = Vec::new();
+		let mut mask = Vec::new();
+		for token in pattern.split_whitespace() {
+			if token == "?" || token == "??" {
+				bytes.push(0);
+				mask.push(false);
+			}
+			else if token.len() == 2 && token.bytes().all(|chr| chr.is_ascii_hexdigit()) {
+				bytes.push(u8::from_str_radix(token, 16).ok()?);
+				mask.push(true);
+			}
+			else {
+				return None;
+			}
+		}
+		Some(Pattern::new(bytes, mask))
+	}
+	fn new(bytes: Vec<u8>, mask: Vec<bool>) -> Pattern {
+		let anchor = mask.iter().position(|&m| m).map(|offset| (offset, bytes[offset]));
+		Pattern { bytes, mask, anchor }
+	}
+	/// Returns the length of the pattern in bytes.
+	pub fn len(&self) -> usize {
+		self.bytes.len()
+	}
+	/// Returns if the pattern is empty.
+	pub fn is_empty(&self) -> bool {
+		self.bytes.is_empty()
+	}
+	/// Returns if the pattern matches at the start of the bytes.
+	pub fn matches(&self, bytes: &[u8]) -> bool {
+		bytes.len() >= self.bytes.len() && self.bytes.iter().zip(&self.mask).zip(bytes).all(|((&pat, &mask), &byte)| !mask || pat == byte)
+	}
+	/// Finds the offset of the first match.
+	pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+		self.find_from(haystack, 0)
+	}
+	/// Finds the offsets of all the matches, including overlapping ones.
+	pub fn find_all(&self, haystack: &[u8]) -> Vec<usize> {
+		let mut result = Vec::new();
+		let mut start = 0;
+		while let Some(offset) = self.find_from(haystack, start) {
+			result.push(offset);
+			start = offset + 1;
+		}
+		result
+	}
+	fn find_from(&self, haystack: &[u8], start: usize) -> Option<usize> {
+		if haystack.len() < self.bytes.len() {
+			return None;
+		}
+		let last = haystack.len() - self.bytes.len();
+		let (anchor_offset, anchor_byte) = match self.anchor {
+			Some(anchor) => anchor,
+			// All wildcards matches everywhere
+			None => return if start <= last { Some(start) } else { None },
+		};
+		let mut offset = start;
+		while offset <= last {
+			// Skip to the next candidate for the anchor byte
+			let candidates = &haystack[offset + anchor_offset..last + anchor_offset + 1];
+			offset += find_byte(candidates, anchor_byte)?;
+			if self.matches(&haystack[offset..]) {
+				return Some(offset);
+			}
+			offset += 1;
+		}
+		None
+	}
+}
+// Finds the first occurrence of the byte, testing a whole machine word per step.
+fn find_byte(haystack: &[u8], byte: u8) -> Option<usize> {
+	const WORD: usize = mem::size_of::<usize>();
+	const LO: usize = usize::MAX / 0xff;
+	const HI: usize = LO << 7;
+	let repeated = LO * byte as usize;
+	let mut chunks = haystack.chunks_exact(WORD);
+	let mut offset = 0;
+	for chunk in &mut chunks {
+		let mut word = [0u8; WORD];
+		word.copy_from_slice(chunk);
+		// Any byte of the word equal to the needle becomes zero
+		let x = usize::from_ne_bytes(word) ^ repeated;
+		if x.wrapping_sub(LO) & !x & HI != 0 {
+			break;
+		}
+		offset += WORD;
+	}
+	haystack[offset..].iter().position(|&other| other == byte).map(|i| offset + i)
+}
+
+impl fmt::Display for Pattern {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, (&byte, &mask)) in self.bytes.iter().zip(&self.mask).enumerate() {
+			if i != 0 {
+				f.write_str(" ")?;
+			}
+			if mask {
+				write!(f, "{:02X}", byte)?;
+			}
+			else {
+				f.write_str("?")?;
+			}
+		}
+		Ok(())
+	}
+}
+impl fmt::Debug for Pattern {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Pattern({})", self)
+	}
+}
+
+#[test]
+fn test_pattern() {
+	let pat = Pattern::from_ida("? 8B ?? 89").unwrap();
+	assert_eq!(pat.to_string(), "? 8B ? 89");
+	assert_eq!(pat, Pattern::from_code_style(b"\x00\x8B\x00\x89", "?x?x").unwrap());
+	let haystack = [0x8B, 0x48, 0x8B, 0x05, 0x89, 0x8B, 0x00, 0x89, 0x8B];
+	assert_eq!(pat.find(&haystack), Some(1));
+	assert_eq!(pat.find_all(&haystack), vec![1, 4]);
+	assert_eq!(pat.find(&haystack[..4]), None);
+	assert!(Pattern::from_ida("48 8G").is_none());
+	assert!(Pattern::from_ida("+4").is_none());
+	assert!(Pattern::from_ida("48 -1").is_none());
+	assert!(Pattern::from_code_style(b"\x48", "xx").is_none());
+	assert_eq!(Pattern::from_ida("? ?").unwrap().find_all(&[1, 2, 3]), vec![0, 1]);
+}
+
+#[test]
+fn test_find_byte() {
+	let mut haystack = [0u8; 67];
+	assert_eq!(find_byte(&haystack, 0x89), None);
+	for i in 0..haystack.len() {
+		haystack[i] = 0x89;
+		assert_eq!(find_byte(&haystack, 0x89), Some(i));
+		assert_eq!(find_byte(&haystack[..i], 0x89), None);
+		haystack[i] = 0x88;
+	}
+	assert_eq!(find_byte(&[], 0), None);
+}
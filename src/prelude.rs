@@ -10,8 +10,11 @@ pub use super::thread::*;
 pub use super::window::*;
 pub use super::wndclass::*;
 pub use super::hook::*;
+pub use super::winevent::*;
+pub use super::hotkey::*;
 pub use super::vk::*;
 pub use super::memory::*;
+pub use super::pattern::*;
 pub use super::mouse::*;
 pub use super::control::*;
 pub use super::system::*;
@@ -5,6 +5,7 @@ Process handles.
 mod process_enum;
 mod process_id;
 mod process_list;
+mod process_modules;
 mod process_peb;
 mod process_rights;
 mod process_vm;
@@ -13,6 +14,7 @@ mod process;
 pub use self::process_enum::*;
 pub use self::process_id::*;
 pub use self::process_list::*;
+pub use self::process_modules::*;
 pub use self::process_peb::*;
 pub use self::process_rights::*;
 pub use self::process_vm::*;
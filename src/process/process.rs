@@ -1,12 +1,14 @@
 use std::{mem, ptr};
 use std::ffi::OsString;
+use std::time::{Duration, SystemTime};
 use std::os::windows::ffi::OsStringExt;
 use intptr::IntPtr;
 use crate::winapi::*;
 use crate::process::{ProcessId, ProcessRights};
-use crate::thread::Thread;
+use crate::module::EnumModules;
+use crate::thread::{EnumThreads, Thread, ThreadId, ThreadRights, WaitResult, wait_handle};
 use crate::error::ErrorCode;
-use crate::{Result, IntoInner, FromInner};
+use crate::{util, Result, IntoInner, FromInner};
 
 /// Process handle.
 #[derive(Debug)]
@@ -28,6 +30,13 @@ impl Process {
 			Ok(Process(handle))
 		}
 	}
+	/// Attach to a process by id with only the `QUERY_LIMITED_INFORMATION` right.
+	///
+	/// This succeeds for far more processes, including protected and most system processes.
+	/// The handle is enough for `full_image_name`, `times`, `exit_code` and `is_wow64`.
+	pub fn attach_limited(pid: ProcessId) -> Result<Process> {
+		Process::attach(pid, ProcessRights::new().query_limited_information())
+	}
 	pub fn try_clone(&self) -> Result<Process> {
 		unsafe {
 			let current = GetCurrentProcess();
@@ -51,6 +60,20 @@ impl Process {
 			Err(ErrorCode::last())
 		}
 	}
+	/// Returns if the process is a 32-bit process running under WOW64 on 64-bit Windows.
+	///
+	/// See [IsWow64Process function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms684139.aspx) for more information.
+	pub fn is_wow64(&self) -> Result<bool> {
+		unsafe {
+			let mut wow64 = FALSE;
+			if IsWow64Process(self.0, &mut wow64) != FALSE {
+				Ok(wow64 != FALSE)
+			}
+			else {
+				Err(ErrorCode::last())
+			}
+		}
+	}
 	/// Get the exit code for the process, `None` if the process is still running.
 	pub fn exit_code(&self) -> Result<Option<DWORD>> {
 		unsafe {
@@ -64,10 +87,44 @@ impl Process {
 			}
 		}
 	}
-	/// Wait for the process to finish.
+	/// Finds the base address of a module loaded in this process by its name.
+	///
+	/// The name is compared case insensitive, returns `ERROR_MOD_NOT_FOUND` if no module with this name is loaded.
+	/// Every call takes a toolhelp snapshot of all the modules in the process, use a [`ModuleCache`](crate::process::ModuleCache) for repeated lookups.
+	pub fn module_base(&self, name: &str) -> Result<IntPtr> {
+		let pid = self.pid()?;
+		for module in EnumModules::create(pid)? {
+			if module.name().to_string_lossy().eq_ignore_ascii_case(name) {
+				return Ok(IntPtr::from_usize(module.base()));
+			}
+		}
+		Err(ErrorCode::from(ERROR_MOD_NOT_FOUND))
+	}
+	/// Terminates the process and all of its threads.
+	///
+	/// See [TerminateProcess function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms686714.aspx) for more information.
+	pub fn terminate(&self, exit_code: u32) -> Result<()> {
+		unsafe {
+			if TerminateProcess(self.0, exit_code) != FALSE {
+				Ok(())
+			}
+			else {
+				Err(ErrorCode::last())
+			}
+		}
+	}
+	/// Returns if the process is still running.
+	///
+	/// Errors, eg. a handle without the `SYNCHRONIZE` right, are reported as not alive.
+	pub fn is_alive(&self) -> bool {
+		unsafe { WaitForSingleObject(self.0, 0) == WAIT_TIMEOUT }
+	}
+	/// Wait for the process to finish with a raw timeout in milliseconds.
+	///
+	/// Returns the raw wait result, eg. `WAIT_OBJECT_0` or `WAIT_TIMEOUT`.
 	///
 	/// See [WaitForSingleObject](https://msdn.microsoft.com/en-us/library/windows/desktop/ms687032.aspx) for more information.
-	pub fn wait(&self, milis: DWORD) -> Result<DWORD> {
+	pub fn wait_millis(&self, milis: DWORD) -> Result<DWORD> {
 		unsafe {
 			let result = WaitForSingleObject(self.0, milis);
 			if result == WAIT_FAILED {
@@ -78,6 +135,87 @@ impl Process {
 			}
 		}
 	}
+	/// Wait for the process to finish.
+	///
+	/// A timeout of `None` waits indefinitely, longer timeouts saturate at `INFINITE`.
+	///
+	/// See [WaitForSingleObject](https://msdn.microsoft.com/en-us/library/windows/desktop/ms687032.aspx) for more information.
+	pub fn wait(&self, timeout: Option<Duration>) -> Result<WaitResult> {
+		wait_handle(self.0, timeout, false)
+	}
+	/// Wait for the process to finish with an optionally alertable wait.
+	///
+	/// An alertable wait returns `WaitResult::IoCompletion` when interrupted to run queued APCs.
+	///
+	/// See [WaitForSingleObjectEx](https://msdn.microsoft.com/en-us/library/windows/desktop/ms687036.aspx) for more information.
+	pub fn wait_ex(&self, timeout: Option<Duration>, alertable: bool) -> Result<WaitResult> {
+		wait_handle(self.0, timeout, alertable)
+	}
+	/// Get the timing information for this process.
+	///
+	/// See [GetProcessTimes function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms683223.aspx) for more information.
+	pub fn times(&self) -> Result<ProcessTimes> {
+		unsafe {
+			let mut creation = mem::zeroed();
+			let mut exit = mem::zeroed();
+			let mut kernel = mem::zeroed();
+			let mut user = mem::zeroed();
+			if GetProcessTimes(self.0, &mut creation, &mut exit, &mut kernel, &mut user) == FALSE {
+				return Err(ErrorCode::last());
+			}
+			let intervals = |ft: FILETIME| (ft.dwHighDateTime as u64) << 32 | ft.dwLowDateTime as u64;
+			Ok(ProcessTimes {
+				creation: util::filetime_to_system_time(&creation).unwrap_or(SystemTime::UNIX_EPOCH),
+				exit: util::filetime_to_system_time(&exit),
+				kernel: util::filetime_to_duration(intervals(kernel)),
+				user: util::filetime_to_duration(intervals(user)),
+			})
+		}
+	}
+	/// Suspends all the threads of this process.
+	///
+	/// The threads are resumed when the returned guard is dropped.
+	/// When called on the current process the calling thread is not suspended.
+	///
+	/// Threads which exit between enumerating and suspending them are skipped, any other error resumes the already suspended threads and is returned.
+	pub fn suspend_all_threads(&self) -> Result<ThreadSuspendGuard> {
+		let pid = self.pid()?;
+		let current_tid = ThreadId::current();
+		// Open all the threads before suspending any of them,
+		// a suspended thread of the current process may hold the heap lock and allocating would deadlock
+		let mut threads = Vec::new();
+		for entry in EnumThreads::create()? {
+			if entry.process_id() != pid || entry.thread_id() == current_tid {
+				continue;
+			}
+			match Thread::attach(entry.thread_id(), false, ThreadRights::new().suspend_resume().query_limited_information()) {
+				Ok(thread) => threads.push(thread),
+				// The thread has exited since the snapshot was taken
+				Err(err) if err == ErrorCode::from(ERROR_INVALID_PARAMETER) => continue,
+				Err(err) => return Err(err),
+			}
+		}
+		// Suspend the threads without allocating, the suspended threads are moved to the front
+		let mut suspended = 0;
+		let mut result = Ok(());
+		for i in 0..threads.len() {
+			match threads[i].suspend() {
+				Ok(_) => {
+					threads.swap(suspended, i);
+					suspended += 1;
+				},
+				Err(err) => match threads[i].exit_code() {
+					// The thread has exited since it was opened
+					Ok(Some(_)) => (),
+					Ok(None) => { result = Err(err); break; },
+					Err(err) => { result = Err(err); break; },
+				},
+			}
+		}
+		threads.truncate(suspended);
+		let guard = ThreadSuspendGuard { threads };
+		result.map(|()| guard)
+	}
 	pub fn create_thread(&self, start_address: IntPtr, parameter: IntPtr) -> Result<Thread> {
 		unsafe {
 			let handle = CreateRemoteThread(self.0, ptr::null_mut(), 0, mem::transmute(start_address), parameter.into_usize() as LPVOID, 0, ptr::null_mut());
@@ -118,6 +256,47 @@ impl Process {
 		}
 	}
 }
+/// Timing information for a process.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ProcessTimes {
+	/// The creation time of the process.
+	pub creation: SystemTime,
+	/// The exit time of the process, `None` if the process is still running.
+	pub exit: Option<SystemTime>,
+	/// The amount of time the process has executed in kernel mode.
+	pub kernel: Duration,
+	/// The amount of time the process has executed in user mode.
+	pub user: Duration,
+}
+impl ProcessTimes {
+	/// Returns how long the process has been running, or ran for if it has exited.
+	pub fn run_duration(&self) -> Duration {
+		let end = self.exit.unwrap_or_else(SystemTime::now);
+		end.duration_since(self.creation).unwrap_or_default()
+	}
+}
+
+/// Resumes the suspended threads when dropped.
+///
+/// Returned by [`Process::suspend_all_threads`].
+#[derive(Debug)]
+pub struct ThreadSuspendGuard {
+	threads: Vec<Thread>,
+}
+impl ThreadSuspendGuard {
+	/// Returns the suspended threads.
+	pub fn threads(&self) -> &[Thread] {
+		&self.threads
+	}
+}
+impl Drop for ThreadSuspendGuard {
+	fn drop(&mut self) {
+		for thread in &self.threads {
+			let _ = thread.resume();
+		}
+	}
+}
+
 impl Clone for Process {
 	fn clone(&self) -> Process {
 		self.try_clone().expect("duplicate handle error")
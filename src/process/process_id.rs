@@ -1,10 +1,18 @@
-use std::fmt;
+use std::{fmt, num, str};
 use crate::winapi::*;
 
 /// Wraps a process identifier.
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct ProcessId(pub(super) DWORD);
 impl_inner!(ProcessId: DWORD);
+impl ProcessId {
+	/// Get the current process' id.
+	///
+	/// See [GetCurrentProcessId function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms683180.aspx) for more information.
+	pub fn current() -> ProcessId {
+		ProcessId(unsafe { GetCurrentProcessId() })
+	}
+}
 
 // Custom Debug and Display implementation to disable pretty formatting
 impl fmt::Debug for ProcessId {
@@ -17,3 +25,9 @@ impl fmt::Display for ProcessId {
 		self.0.fmt(f)
 	}
 }
+impl str::FromStr for ProcessId {
+	type Err = num::ParseIntError;
+	fn from_str(s: &str) -> Result<ProcessId, num::ParseIntError> {
+		s.parse().map(ProcessId)
+	}
+}
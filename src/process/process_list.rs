@@ -2,7 +2,8 @@ use std::{cmp, fmt, mem, slice};
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
 use crate::winapi::*;
-use crate::FromInner;
+use crate::error::ErrorCode;
+use crate::{Result, FromInner};
 use crate::thread::ThreadId;
 use crate::process::ProcessId;
 
@@ -11,21 +12,29 @@ use crate::process::ProcessId;
 #[derive(Clone)]
 pub struct ProcessList(Box<[u8]>);
 impl ProcessList {
+	/// Only retries with a bigger buffer when the list has grown, any other failure is returned as an error.
 	#[inline(never)]
-	pub fn query() -> ProcessList {
+	pub fn query() -> Result<ProcessList> {
 		let mut data = Vec::new().into_boxed_slice();
 		let mut return_length = 0;
 		unsafe {
-			while NtQuerySystemInformation(
-				SystemProcessInformation,
-				data.as_mut_ptr() as PVOID,
-				data.len() as ULONG,
-				&mut return_length,
-			) < 0 {
+			loop {
+				let ntstatus = NtQuerySystemInformation(
+					SystemProcessInformation,
+					data.as_mut_ptr() as PVOID,
+					data.len() as ULONG,
+					&mut return_length,
+				);
+				if ntstatus >= 0 {
+					break;
+				}
+				if ntstatus != STATUS_INFO_LENGTH_MISMATCH {
+					return Err(ErrorCode::from_nt(ntstatus));
+				}
 				data = vec![0; return_length as usize].into_boxed_slice();
 			}
 		}
-		ProcessList(data)
+		Ok(ProcessList(data))
 	}
 	pub fn iter<'a>(&'a self) -> ProcessListIter<'a> {
 		ProcessListIter(&self.0)
@@ -227,7 +236,7 @@ mod tests {
 
 	#[test]
 	fn units() {
-		let processes = ProcessList::query();
+		let processes = ProcessList::query().unwrap();
 		println!("{:#?}", processes);
 	}
 }
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use dataview::Pod;
+use intptr::IntPtr;
+use crate::process::Process;
+use crate::Result;
+
+/// Caches the base addresses of the modules loaded in a process.
+///
+/// Resolving a module base with [`Process::module_base`] takes a toolhelp snapshot of all the modules in the process.
+/// The cache resolves every module only once, keyed by its lowercase name.
+///
+/// The cache is not aware of modules being unloaded or reloaded at a different address,
+/// call [`invalidate`](ModuleCache::invalidate) or [`clear`](ModuleCache::clear) to resolve them again.
+///
+/// ```no_run
+/// use external::process::{ModuleCache, Process};
+///
+/// let process = Process::current();
+/// let mut cache = ModuleCache::new();
+/// for _ in 0..1000 {
+///     let magic: [u8; 2] = cache.read_at_module(&process, "ntdll.dll", 0).unwrap();
+///     assert_eq!(&magic, b"MZ");
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ModuleCache {
+	bases: HashMap<String, IntPtr>,
+}
+impl ModuleCache {
+	/// Creates an empty cache.
+	pub fn new() -> ModuleCache {
+		ModuleCache::default()
+	}
+	/// Finds the base address of a module loaded in the process by its name.
+	///
+	/// The module is resolved with [`Process::module_base`] the first time its name is seen, errors are not cached.
+	///
+	/// The cache does not remember which process the base addresses came from, use a separate cache for every process.
+	pub fn module_base(&mut self, process: &Process, name: &str) -> Result<IntPtr> {
+		let key = name.to_ascii_lowercase();
+		if let Some(&base) = self.bases.get(&key) {
+			return Ok(base);
+		}
+		let base = process.module_base(name)?;
+		self.bases.insert(key, base);
+		Ok(base)
+	}
+	/// Reads a Pod `T` at an offset from the cached base of a module.
+	///
+	/// Returns `ERROR_MOD_NOT_FOUND` if the module is not loaded, otherwise any error comes from reading the memory.
+	pub fn read_at_module<T: Pod>(&mut self, process: &Process, module: &str, offset: usize) -> Result<T> {
+		let base = self.module_base(process, module)?;
+		process.vm_read(IntPtr::from_usize(base.into_usize().wrapping_add(offset)))
+	}
+	/// Forgets the base address of a module, it is resolved again on the next lookup.
+	pub fn invalidate(&mut self, name: &str) {
+		self.bases.remove(&name.to_ascii_lowercase());
+	}
+	/// Forgets the base addresses of all the modules.
+	pub fn clear(&mut self) {
+		self.bases.clear();
+	}
+}
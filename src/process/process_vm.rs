@@ -1,4 +1,6 @@
 use std::{iter, mem, ops, ptr};
+use std::ffi::OsString;
+use std::os::windows::ffi::OsStringExt;
 use dataview::Pod;
 use intptr::IntPtr;
 use crate::winapi::*;
@@ -54,6 +56,15 @@ impl Process {
 			}
 		}
 	}
+	/// Reads as many bytes of a Pod `T` as are available.
+	///
+	/// Returns the value together with the number of bytes read, the unread tail of the value is zeroed.
+	#[inline]
+	pub fn vm_read_partial_as<T: Pod>(&self, ptr: IntPtr<T>) -> Result<(T, usize)> {
+		let mut dest = dataview::zeroed::<T>();
+		let bytes_read = self.vm_read_partial(ptr.cast(), dataview::bytes_mut(&mut dest))?.len();
+		Ok((dest, bytes_read))
+	}
 	/// Reads a Pod `T` from the process.
 	#[inline]
 	pub fn vm_read<T: Pod>(&self, ptr: IntPtr<T>) -> Result<T> {
@@ -63,6 +74,83 @@ impl Process {
 			Ok(dest.assume_init())
 		}
 	}
+	/// Reads a value or an array of values from the process.
+	///
+	/// The result type is inferred from the source, see [`ReadFrom`].
+	///
+	/// ```no_run
+	/// use external::prelude::*;
+	/// # fn example(process: &Process, health: IntPtr<f32>, name: IntPtr<u8>) -> external::Result<()> {
+	/// let health = process.read(health)?;
+	/// let name = process.read(Array(name, 32))?;
+	/// # let _: (f32, Vec<u8>) = (health, name); Ok(()) }
+	/// ```
+	#[inline]
+	pub fn read<R: ReadFrom>(&self, src: R) -> Result<R::Output> {
+		src.read_from(self)
+	}
+	/// Reads a Pod `T` from the process calling `NtReadVirtualMemory` directly.
+	///
+	/// This skips the extra validation done by `ReadProcessMemory` for hot read loops.
+	/// On failure the raw `NTSTATUS` is returned for inspection.
+	#[inline]
+	pub fn vm_read_nt<T: Pod>(&self, ptr: IntPtr<T>) -> std::result::Result<T, NTSTATUS> {
+		unsafe {
+			let mut dest = mem::MaybeUninit::<T>::uninit();
+			let status = NtReadVirtualMemory(
+				*self.as_inner(),
+				ptr.into_usize() as PVOID,
+				dest.as_mut_ptr() as PVOID,
+				mem::size_of::<T>() as SIZE_T,
+				ptr::null_mut(),
+			);
+			if status >= 0 {
+				Ok(dest.assume_init())
+			}
+			else {
+				Err(status)
+			}
+		}
+	}
+	/// Reads a pointer sized for the process.
+	///
+	/// Reads 4 bytes for WOW64 processes and the native pointer width otherwise.
+	pub fn read_ptr(&self, address: IntPtr) -> Result<IntPtr> {
+		self.read_ptr_wow64(address, self.is_wow64()?)
+	}
+	/// Reads the address of a virtual method from an object's virtual method table.
+	///
+	/// Reads the vtable pointer at the start of the object then reads entry `index` of the vtable, both with the pointer width of the process.
+	pub fn read_vfunc(&self, object: IntPtr, index: usize) -> Result<IntPtr> {
+		let wow64 = self.is_wow64()?;
+		let ptr_size = if wow64 { 4 } else { mem::size_of::<usize>() };
+		let vtable = self.read_ptr_wow64(object, wow64)?;
+		self.read_ptr_wow64(IntPtr::from_usize(vtable.into_usize().wrapping_add(index * ptr_size)), wow64)
+	}
+	/// Resolves the target of a relative call or jump instruction.
+	///
+	/// Reads the signed 32-bit displacement at `addr + disp_offset` and returns `addr + instr_len + disp`.
+	/// Eg. `E8 xx xx xx xx` (call) and `E9 xx xx xx xx` (jmp) have a `disp_offset` of 1 and an `instr_len` of 5.
+	pub fn resolve_rel(&self, addr: IntPtr, instr_len: usize, disp_offset: usize) -> Result<IntPtr> {
+		let disp = self.vm_read(IntPtr::<i32>::from_usize(addr.into_usize().wrapping_add(disp_offset)))?;
+		Ok(IntPtr::from_usize(addr.into_usize().wrapping_add(instr_len).wrapping_add(disp as isize as usize)))
+	}
+	/// Resolves the address referenced by a `[rip+disp]` operand.
+	///
+	/// The displacement is relative to the end of the instruction, which is not necessarily right after the displacement.
+	/// Eg. `48 8D 05 xx xx xx xx` (lea rax, [rip+disp]) has a `disp_offset` of 3 and an `instr_len` of 7,
+	/// while `C7 05 xx xx xx xx yy yy yy yy` (mov dword ptr [rip+disp], imm32) has a `disp_offset` of 2 and an `instr_len` of 10.
+	pub fn resolve_rip(&self, addr: IntPtr, disp_offset: usize, instr_len: usize) -> Result<IntPtr> {
+		self.resolve_rel(addr, instr_len, disp_offset)
+	}
+	fn read_ptr_wow64(&self, address: IntPtr, wow64: bool) -> Result<IntPtr> {
+		if wow64 {
+			self.vm_read(address.cast::<u32>()).map(|ptr| IntPtr::from_usize(ptr as usize))
+		}
+		else {
+			self.vm_read(address.cast::<usize>()).map(IntPtr::from_usize)
+		}
+	}
 	/// Reads a slice of Pod `T` from the process.
 	#[inline]
 	pub fn vm_read_into<'a, T: Pod + ?Sized>(&self, ptr: IntPtr<T>, dest: &'a mut T) -> Result<&'a mut T> {
@@ -114,6 +202,15 @@ impl Process {
 			Err(ErrorCode::last())
 		}
 	}
+	/// Writes each of the byte slices to their address in order.
+	///
+	/// Stops at the first failure and returns the index of the failed write with its error, the writes before it remain applied.
+	pub fn vm_scatter_write(&self, writes: &[(IntPtr, &[u8])]) -> std::result::Result<(), (usize, ErrorCode)> {
+		for (index, &(address, bytes)) in writes.iter().enumerate() {
+			self.vm_write_bytes(address, bytes).map_err(|err| (index, err))?;
+		}
+		Ok(())
+	}
 	/// Writes as many bytes as it can.
 	#[inline]
 	pub fn vm_write_partial<'a>(&self, address: usize, bytes: &'a [u8]) -> Result<&'a [u8]> {
@@ -146,6 +243,28 @@ impl Process {
 	pub fn vm_write<T: ?Sized + Pod>(&self, ptr: IntPtr<T>, val: &T) -> Result<()> {
 		self.vm_write_bytes(ptr.cast(), dataview::bytes(val))
 	}
+	/// Writes a Pod `T` to the process after checking the destination is writable.
+	///
+	/// Every region covered by the write is queried first.
+	/// Returns `ERROR_INVALID_ADDRESS` if any of the memory is not committed and `ERROR_WRITE_PROTECT` if any of it is not writable,
+	/// instead of letting `WriteProcessMemory` fail with an error which might mean either.
+	pub fn vm_write_checked<T: ?Sized + Pod>(&self, ptr: IntPtr<T>, val: &T) -> Result<()> {
+		let start = ptr.into_usize();
+		let end = start.wrapping_add(mem::size_of_val(val));
+		let mut address = start;
+		while address < end {
+			let mi = self.vm_query(IntPtr::from_usize(address))?;
+			if mi.state() != RegionState::Commit {
+				return Err(ErrorCode::from(ERROR_INVALID_ADDRESS));
+			}
+			let protect = mi.protect();
+			if !protect.is_writable() || protect.has_guard() {
+				return Err(ErrorCode::from(ERROR_WRITE_PROTECT));
+			}
+			address = mi.range().end.into_usize();
+		}
+		self.vm_write(ptr, val)
+	}
 	/// Writes a sub range of the Pod `T` to the process.
 	/// Panics if the range falls outside the bytes of the given value.
 	#[inline]
@@ -270,23 +389,88 @@ impl Process {
 			Some(mi)
 		})
 	}
-	/// Iterator returning all unique allocation bases from vm_query.
+	/// Iterator over the entire address space of the process classifying every region.
 	#[inline]
-	pub fn vm_allocations(&self) -> impl '_ + Clone + Iterator<Item = (IntPtr, Protect, MemoryType)> {
-		let mut allocation_base = ptr::null_mut();
+	pub fn memory_map(&self) -> impl '_ + Clone + Iterator<Item = Region> {
+		self.vm_regions(IntPtr::NULL).map(|mi| Region::from(&mi))
+	}
+	/// Iterator over all allocations aggregating the information of their regions.
+	#[inline]
+	pub fn vm_allocations(&self) -> impl '_ + Clone + Iterator<Item = MemoryAllocation> {
 		let mut address = IntPtr::NULL;
 		iter::from_fn(move || {
-			loop {
+			// Skip over the free regions
+			let mut mi = loop {
 				let mi = self.vm_query(address).ok()?;
-				if mi.AllocationBase != ptr::null_mut() && mi.AllocationBase != allocation_base {
-					allocation_base = mi.AllocationBase;
-					let allocation_base = IntPtr::from_usize(allocation_base as usize);
-					let allocation_protect = unsafe { Protect::from_inner(mi.AllocationProtect) };
-					let memory_type = unsafe { MemoryType::from_inner(mi.Type) };
-					return Some((allocation_base, allocation_protect, memory_type));
-				}
 				address = IntPtr::from_usize((mi.BaseAddress as usize).wrapping_add(mi.RegionSize));
+				if !mi.AllocationBase.is_null() {
+					break mi;
+				}
+			};
+			let allocation_base = mi.AllocationBase;
+			let mut allocation = MemoryAllocation {
+				base: IntPtr::from_usize(allocation_base as usize),
+				size: 0,
+				protect: unsafe { Protect::from_inner(mi.AllocationProtect) },
+				ty: unsafe { MemoryType::from_inner(mi.Type) },
+				region_states: 0,
+				region_protects: unsafe { Protect::from_inner(0) },
+				region_types: unsafe { MemoryType::from_inner(0) },
+				file_name: None,
+			};
+			// Aggregate all the regions belonging to this allocation
+			loop {
+				allocation.size += mi.RegionSize;
+				allocation.region_states |= mi.State;
+				allocation.region_protects = unsafe { Protect::from_inner(allocation.region_protects.into_inner() | mi.Protect) };
+				allocation.region_types = unsafe { MemoryType::from_inner(allocation.region_types.into_inner() | mi.Type) };
+				match self.vm_query(address) {
+					Ok(next) if next.AllocationBase == allocation_base => {
+						address = IntPtr::from_usize((next.BaseAddress as usize).wrapping_add(next.RegionSize));
+						mi = next;
+					},
+					_ => break,
+				}
+			}
+			if allocation.ty == MemoryType::IMAGE || allocation.ty == MemoryType::MAPPED {
+				let mut buffer = [0u16; 0x400];
+				allocation.file_name = self.get_mapped_file_name_wide(allocation.base, &mut buffer).ok().map(|name| OsString::from_wide(name));
 			}
+			Some(allocation)
 		})
 	}
 }
+
+//----------------------------------------------------------------
+
+/// Sources which can be read with [`Process::read`].
+pub trait ReadFrom {
+	/// The type of the value read.
+	type Output;
+	/// Reads the value from the process.
+	fn read_from(self, process: &Process) -> Result<Self::Output>;
+}
+
+/// Reads a Pod `T`.
+impl<T: Pod> ReadFrom for IntPtr<T> {
+	type Output = T;
+	#[inline]
+	fn read_from(self, process: &Process) -> Result<T> {
+		process.vm_read(self)
+	}
+}
+
+/// Array of `len` consecutive Pod `T` starting at the pointer, read into a `Vec<T>`.
+#[derive(Copy, Clone, Debug)]
+pub struct Array<T>(pub IntPtr<T>, pub usize);
+
+impl<T: Pod> ReadFrom for Array<T> {
+	type Output = Vec<T>;
+	#[inline]
+	fn read_from(self, process: &Process) -> Result<Vec<T>> {
+		let Array(ptr, len) = self;
+		let mut dest = Vec::new();
+		process.vm_read_append(ptr.cast(), &mut dest, len)?;
+		Ok(dest)
+	}
+}
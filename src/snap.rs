@@ -43,6 +43,16 @@ pub struct Rect {
 	pub width: i32,
 	pub height: i32,
 }
+impl From<RECT> for Rect {
+	fn from(rc: RECT) -> Rect {
+		Rect {
+			left: rc.left,
+			top: rc.top,
+			width: rc.right - rc.left,
+			height: rc.bottom - rc.top,
+		}
+	}
+}
 
 //----------------------------------------------------------------
 
@@ -132,7 +142,38 @@ impl Capture {
 			}
 		}
 	}
+	/// Capture the window contents by asking the window to render itself.
+	///
+	/// Unlike [`blit`](Capture::blit) this works for windows which are covered by other windows or moved off-screen.
+	/// Rendering with `PW_RENDERFULLCONTENT` is attempted first, falling back to the default rendering on systems before Windows 8.1.
+	/// Returns which of the methods succeeded.
+	///
+	/// See [PrintWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd162869.aspx) for more information.
+	pub fn print_window(&self) -> Result<PrintMethod> {
+		unsafe {
+			// PrintWindow always renders the client area at the origin, shift it so the subrectangle lands at the origin instead
+			let mut old_origin = POINT { x: 0, y: 0 };
+			if SetViewportOrgEx(self.hdc, -self.rect.left, -self.rect.top, &mut old_origin) == FALSE {
+				return Err(ErrorCode::last());
+			}
+			let hwnd = self.source.wnd.into_inner();
+			let result = if PrintWindow(hwnd, self.hdc, PW_CLIENTONLY | PW_RENDERFULLCONTENT) != FALSE {
+				Ok(PrintMethod::RenderFullContent)
+			}
+			else if PrintWindow(hwnd, self.hdc, PW_CLIENTONLY) != FALSE {
+				Ok(PrintMethod::Default)
+			}
+			else {
+				Err(ErrorCode::last())
+			};
+			SetViewportOrgEx(self.hdc, old_origin.x, old_origin.y, ptr::null_mut());
+			result
+		}
+	}
 	/// Get the captured pixels.
+	///
+	/// The rows are stored top-down, see [`Image`] for the orientation.
+	/// The pixel buffer of the image is reused, it is only reallocated if its capacity is too small.
 	pub fn pixels(&self, image: &mut Image) -> Result<()> {
 		unsafe {
 			// FIXME! `GetDIBits` writes a color table of 3 values: RED, GREEN, BLUE. Why?
@@ -145,12 +186,12 @@ impl Capture {
 			if GetDIBits(self.hdc, self.hbmp, 0, self.rect.height as u32, ptr::null_mut(), bmi, DIB_RGB_COLORS) == 0 {
 				return Err(ErrorCode::last());
 			}
-			// Reserve space for the dibits
+			// Reserve space for the dibits, reuses the existing allocation if it is large enough
 			let len = bmi.bmiHeader.biWidth as usize * bmi.bmiHeader.biHeight as usize;
-			if image.pixels.capacity() < len {
-				let additional = len - image.pixels.capacity();
-				image.pixels.reserve_exact(additional);
-			}
+			image.pixels.clear();
+			image.pixels.reserve_exact(len);
+			// A negative height requests a top-down DIB instead of the default bottom-up
+			bmi.bmiHeader.biHeight = -bmi.bmiHeader.biHeight.abs();
 			// Copy the dibits
 			let bits = image.pixels.as_mut_ptr() as *mut c_void;
 			if GetDIBits(self.hdc, self.hbmp, 0, self.rect.height as u32, bits, bmi, DIB_RGB_COLORS) == 0 {
@@ -163,10 +204,31 @@ impl Capture {
 			Ok(())
 		}
 	}
+	/// Capture the screen pixels into the image.
+	///
+	/// Combines [`blit`](Capture::blit) and [`pixels`](Capture::pixels), reusing the same image across frames does not allocate after the first frame.
+	pub fn capture_into(&self, image: &mut Image) -> Result<()> {
+		self.blit()?;
+		self.pixels(image)
+	}
+}
+
+/// The rendering used by [`Capture::print_window`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PrintMethod {
+	/// Rendered with `PW_RENDERFULLCONTENT`, includes content drawn by DirectX.
+	RenderFullContent,
+	/// Rendered without flags, DirectX content may be black.
+	Default,
 }
 
 //----------------------------------------------------------------
 
+/// Image of captured pixels.
+///
+/// The pixels are stored row by row top-down, the first pixel is the top left corner of the image.
+/// This matches the PPM format used by [`save`](Image::save) and [`load`](Image::load).
+/// GDI bitmaps are bottom-up by default, use [`flip_vertical`](Image::flip_vertical) to convert between the two.
 #[derive(PartialEq)]
 pub struct Image {
 	pixels: Vec<Color>,
@@ -184,6 +246,20 @@ impl Image {
 	pub fn height(&self) -> i32 {
 		self.height
 	}
+	/// Flips the image upside down in place.
+	///
+	/// Converts between top-down and bottom-up row order.
+	pub fn flip_vertical(&mut self) {
+		let width = self.width as usize;
+		let height = self.height as usize;
+		if width == 0 {
+			return;
+		}
+		for y in 0..height / 2 {
+			let (top, bottom) = self.pixels.split_at_mut((height - 1 - y) * width);
+			top[y * width..(y + 1) * width].swap_with_slice(&mut bottom[..width]);
+		}
+	}
 	pub fn save(&self, file: &mut dyn io::Write) -> io::Result<()> {
 		writeln!(file, "P6 {} {} 255", self.width, self.height)?;
 		for i in 0..self.pixels.len() {
@@ -259,3 +335,17 @@ impl AsMut<[Color]> for Image {
 		&mut self.pixels
 	}
 }
+
+#[test]
+fn test_flip_vertical() {
+	let color = |red| Color { red, ..Color::default() };
+	let mut image = Image {
+		pixels: vec![color(0), color(1), color(2), color(3), color(4), color(5)],
+		width: 2,
+		height: 3,
+	};
+	image.flip_vertical();
+	assert_eq!(image.pixels(), &[color(4), color(5), color(2), color(3), color(0), color(1)][..]);
+	image.flip_vertical();
+	assert_eq!(image.pixels(), &[color(0), color(1), color(2), color(3), color(4), color(5)][..]);
+}
@@ -2,8 +2,12 @@
 System APIs.
 !*/
 
+mod privilege;
+mod processors;
 mod system_modules;
 mod time;
 
+pub use self::privilege::*;
+pub use self::processors::*;
 pub use self::system_modules::*;
 pub use self::time::*;
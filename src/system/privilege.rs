@@ -0,0 +1,38 @@
+use std::ptr;
+use crate::winapi::*;
+use crate::thread::{Privilege, ThreadToken, ThreadTokenRights};
+use crate::error::ErrorCode;
+use crate::{Result, IntoInner, FromInner};
+
+/// Enables a privilege in the access token of the current process.
+///
+/// Fails with `ERROR_NOT_ALL_ASSIGNED` if the token does not hold the privilege.
+///
+/// See [OpenProcessToken function](https://msdn.microsoft.com/en-us/library/windows/desktop/aa379295.aspx) for more information.
+pub fn enable_privilege(privilege: Privilege) -> Result<()> {
+	unsafe {
+		let mut handle = ptr::null_mut();
+		let rights = ThreadTokenRights::new().adjust_privileges().query();
+		if OpenProcessToken(GetCurrentProcess(), rights.into_inner(), &mut handle) == FALSE {
+			return Err(ErrorCode::last());
+		}
+		let token = ThreadToken::from_inner(handle);
+		token.set_privilege(privilege, true)
+	}
+}
+
+/// Enables `SeDebugPrivilege` for the current process.
+///
+/// Required before attaching to processes owned by other users or services with [`Process::attach`](crate::process::Process::attach).
+/// Only succeeds when running elevated, fails with `ERROR_NOT_ALL_ASSIGNED` otherwise.
+pub fn enable_debug_privilege() -> Result<()> {
+	enable_privilege(Privilege::SE_DEBUG_NAME)
+}
+
+#[test]
+fn test_enable_debug_privilege() {
+	match enable_debug_privilege() {
+		Ok(()) => (),
+		Err(err) => assert_eq!(err, ErrorCode::from(ERROR_NOT_ALL_ASSIGNED)),
+	}
+}
@@ -0,0 +1,18 @@
+use std::mem;
+use crate::util::kernel32_proc;
+
+type GetActiveProcessorGroupCountFn = unsafe extern "system" fn() -> u16;
+
+/// Returns the number of active processor groups in the system.
+///
+/// On systems without processor groups this returns 1.
+///
+/// See [GetActiveProcessorGroupCount function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd405486.aspx) for more information.
+pub fn processor_group_count() -> u16 {
+	unsafe {
+		match kernel32_proc(b"GetActiveProcessorGroupCount\0") {
+			Some(address) => mem::transmute::<usize, GetActiveProcessorGroupCountFn>(address)(),
+			None => 1,
+		}
+	}
+}
@@ -1,7 +1,8 @@
 use std::{cmp, fmt, mem, ops, slice};
 use std::path::Path;
 use crate::winapi::*;
-use crate::{AsInner, util};
+use crate::error::ErrorCode;
+use crate::{Result, AsInner, util};
 
 //----------------------------------------------------------------
 
@@ -10,8 +11,10 @@ use crate::{AsInner, util};
 pub struct SystemModules(Vec<u8>);
 impl SystemModules {
 	/// Constructor.
+	///
+	/// Only retries with a bigger buffer when the list has grown, any other failure is returned as an error.
 	#[inline(never)]
-	pub fn query() -> SystemModules {
+	pub fn query() -> Result<SystemModules> {
 		let mut data = Vec::new();
 		let mut return_length = 0;
 		unsafe {
@@ -26,13 +29,26 @@ impl SystemModules {
 					data.set_len(return_length as usize);
 					break;
 				}
+				if ntstatus != STATUS_INFO_LENGTH_MISMATCH {
+					return Err(ErrorCode::from_nt(ntstatus));
+				}
 				if data.capacity() < return_length as usize {
 					let additional = return_length as usize - data.capacity();
 					data.reserve_exact(additional);
 				}
 			}
 		}
-		SystemModules(data)
+		Ok(SystemModules(data))
+	}
+}
+impl SystemModules {
+	/// Finds a loaded system module by its file name, case insensitive.
+	pub fn find(&self, name: &str) -> Option<&SystemModule> {
+		self.iter().find(|module| module.file_name_bytes().eq_ignore_ascii_case(name.as_bytes()))
+	}
+	/// Returns the image base of a loaded system module by its file name, case insensitive.
+	pub fn base_of(&self, name: &str) -> Option<usize> {
+		self.find(name).map(SystemModule::image_base)
 	}
 }
 impl AsInner<[RTL_PROCESS_MODULE_INFORMATION]> for SystemModules {
@@ -93,11 +109,14 @@ impl SystemModule {
 		}
 	}
 	pub fn file_name(&self) -> &Path {
-		let offset = cmp::min(self.0.OffsetToFileName as usize, self.0.FullPathName.len());
 		unsafe {
-			mem::transmute(util::from_char_buf(&self.0.FullPathName[offset..]))
+			mem::transmute(self.file_name_bytes())
 		}
 	}
+	fn file_name_bytes(&self) -> &[u8] {
+		let offset = cmp::min(self.0.OffsetToFileName as usize, self.0.FullPathName.len());
+		util::from_char_buf(&self.0.FullPathName[offset..])
+	}
 }
 impl fmt::Debug for SystemModule {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -119,7 +138,8 @@ mod tests {
 
 	#[test]
 	fn units() {
-		let modules = SystemModules::query();
+		let modules = SystemModules::query().unwrap();
 		println!("{:#?}", modules);
+		assert!(modules.base_of("NTOSKRNL.EXE").is_some());
 	}
 }
@@ -2,15 +2,23 @@
 Threads.
 !*/
 
+#[cfg(target_arch = "x86_64")]
+mod thread_context;
 mod thread_id;
 mod thread_rights;
+mod thread_token;
 mod thread_enum;
+mod thread_wait;
 // mod thread_tib;
 mod thread;
 
+#[cfg(target_arch = "x86_64")]
+pub use self::thread_context::*;
 pub use self::thread_id::*;
 pub use self::thread_rights::*;
+pub use self::thread_token::*;
 pub use self::thread_enum::*;
+pub use self::thread_wait::*;
 // pub use self::thread_tib::*;
 pub use self::thread::*;
 
@@ -29,3 +37,33 @@ pub unsafe fn start(f: fn()) {
 		}
 		CreateThread(ptr::null_mut(), 0, Some(thunk), f as LPVOID, 0, ptr::null_mut());
 }
+
+/// CreateThread from DllMain with a closure and calls FreeLibraryAndExitThread when the closure returns.
+///
+/// Like [`start`] except the closure can carry its own configuration instead of relying on global state.
+/// A panic inside the closure is caught so the library is still freed and no unwinding crosses into the system.
+///
+/// # Safety
+///
+/// Must be called from a DLL, the library is freed with `FreeLibraryAndExitThread` when the closure returns.
+/// Nothing of the library may be in use by other threads at that point.
+pub unsafe fn start_boxed<F: FnOnce() + Send + 'static>(f: F) {
+		use std::{panic, ptr};
+		use crate::winapi::*;
+		extern "system" fn thunk<F: FnOnce()>(param: LPVOID) -> DWORD {
+			unsafe {
+				let f = Box::from_raw(param as *mut F);
+				let _ = panic::catch_unwind(panic::AssertUnwindSafe(f));
+				FreeLibraryAndExitThread(crate::module::image_base(), 0);
+			}
+			0
+		}
+		let param = Box::into_raw(Box::new(f));
+		let handle = CreateThread(ptr::null_mut(), 0, Some(thunk::<F>), param as LPVOID, 0, ptr::null_mut());
+		if handle.is_null() {
+			drop(Box::from_raw(param));
+		}
+		else {
+			CloseHandle(handle);
+		}
+}
@@ -1,8 +1,12 @@
-use std::mem;
+use std::{mem, ptr};
+use std::time::Duration;
 use crate::winapi::*;
 use crate::process::ProcessId;
-use crate::thread::{ThreadId, ThreadRights};
+use crate::window::Window;
+use crate::snap::Rect;
+use crate::thread::{ThreadId, ThreadRights, WaitResult, wait_handle};
 use crate::error::ErrorCode;
+use crate::util::kernel32_proc;
 use crate::{Result, IntoInner, FromInner};
 
 //----------------------------------------------------------------
@@ -26,6 +30,14 @@ impl Thread {
 			Ok(Thread(handle))
 		}
 	}
+	/// Attach to a thread by id and given rights and query its owning process' id.
+	///
+	/// The rights must include `query_limited_information`, eg. the `ThreadRights::query()` preset.
+	pub fn attach_with_process(tid: ThreadId, access: ThreadRights) -> Result<(Thread, ProcessId)> {
+		let thread = Thread::attach(tid, false, access)?;
+		let pid = thread.process_id()?;
+		Ok((thread, pid))
+	}
 	/// Get the id for this thread.
 	pub fn tid(&self) -> Result<ThreadId> {
 		let tid = unsafe { GetThreadId(self.0) };
@@ -59,10 +71,12 @@ impl Thread {
 			}
 		}
 	}
-	/// Wait for the thread to finish.
+	/// Wait for the thread to finish with a raw timeout in milliseconds.
+	///
+	/// Returns the raw wait result, eg. `WAIT_OBJECT_0` or `WAIT_TIMEOUT`.
 	///
 	/// See [WaitForSingleObject](https://msdn.microsoft.com/en-us/library/windows/desktop/ms687032.aspx) for more information.
-	pub fn wait(&self, milis: DWORD) -> Result<DWORD> {
+	pub fn wait_millis(&self, milis: DWORD) -> Result<DWORD> {
 		unsafe {
 			let result = WaitForSingleObject(self.0, milis);
 			if result == WAIT_FAILED {
@@ -73,6 +87,22 @@ impl Thread {
 			}
 		}
 	}
+	/// Wait for the thread to finish.
+	///
+	/// A timeout of `None` waits indefinitely, longer timeouts saturate at `INFINITE`.
+	///
+	/// See [WaitForSingleObject](https://msdn.microsoft.com/en-us/library/windows/desktop/ms687032.aspx) for more information.
+	pub fn wait(&self, timeout: Option<Duration>) -> Result<WaitResult> {
+		wait_handle(self.0, timeout, false)
+	}
+	/// Wait for the thread to finish with an optionally alertable wait.
+	///
+	/// An alertable wait returns `WaitResult::IoCompletion` when interrupted to run queued APCs.
+	///
+	/// See [WaitForSingleObjectEx](https://msdn.microsoft.com/en-us/library/windows/desktop/ms687036.aspx) for more information.
+	pub fn wait_ex(&self, timeout: Option<Duration>, alertable: bool) -> Result<WaitResult> {
+		wait_handle(self.0, timeout, alertable)
+	}
     /// Suspends the thread by increasing its suspend count by one.
 	pub fn suspend(&self) -> Result<DWORD> {
 		unsafe {
@@ -97,6 +127,130 @@ impl Thread {
 		}
 	}
 }
+/// Processor group and affinity mask within that group.
+///
+/// See [GROUP_AFFINITY structure](https://msdn.microsoft.com/en-us/library/windows/desktop/dd405500.aspx) for more information.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct GroupAffinity {
+	pub group: u16,
+	pub mask: usize,
+}
+
+type GetThreadGroupAffinityFn = unsafe extern "system" fn(HANDLE, *mut GROUP_AFFINITY) -> BOOL;
+type SetThreadGroupAffinityFn = unsafe extern "system" fn(HANDLE, *const GROUP_AFFINITY, *mut GROUP_AFFINITY) -> BOOL;
+
+/// Processor group affinity.
+///
+/// On systems without processor groups everything is treated as group 0.
+impl Thread {
+	/// Get the processor group affinity of the thread.
+	///
+	/// See [GetThreadGroupAffinity function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd405498.aspx) for more information.
+	pub fn group_affinity(&self) -> Result<GroupAffinity> {
+		unsafe {
+			if let Some(address) = kernel32_proc(b"GetThreadGroupAffinity\0") {
+				let get_thread_group_affinity: GetThreadGroupAffinityFn = mem::transmute(address);
+				let mut affinity: GROUP_AFFINITY = mem::zeroed();
+				if get_thread_group_affinity(self.0, &mut affinity) == FALSE {
+					return Err(ErrorCode::last());
+				}
+				return Ok(GroupAffinity { group: affinity.Group, mask: affinity.Mask });
+			}
+			// Without processor groups query the affinity mask from the basic thread information
+			let mut info: THREAD_BASIC_INFORMATION = mem::zeroed();
+			let status = NtQueryInformationThread(
+				self.0,
+				ThreadBasicInformation,
+				&mut info as *mut _ as PVOID,
+				mem::size_of::<THREAD_BASIC_INFORMATION>() as ULONG,
+				ptr::null_mut(),
+			);
+			if status < 0 {
+				return Err(ErrorCode::from_nt(status));
+			}
+			let mask = info.AffinityMask;
+			Ok(GroupAffinity { group: 0, mask })
+		}
+	}
+	/// Set the processor group affinity of the thread, returns the previous group affinity.
+	///
+	/// See [SetThreadGroupAffinity function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd405516.aspx) for more information.
+	pub fn set_group_affinity(&self, affinity: GroupAffinity) -> Result<GroupAffinity> {
+		unsafe {
+			if let Some(address) = kernel32_proc(b"SetThreadGroupAffinity\0") {
+				let set_thread_group_affinity: SetThreadGroupAffinityFn = mem::transmute(address);
+				let mut new: GROUP_AFFINITY = mem::zeroed();
+				new.Group = affinity.group;
+				new.Mask = affinity.mask;
+				let mut previous: GROUP_AFFINITY = mem::zeroed();
+				if set_thread_group_affinity(self.0, &new, &mut previous) == FALSE {
+					return Err(ErrorCode::last());
+				}
+				return Ok(GroupAffinity { group: previous.Group, mask: previous.Mask });
+			}
+			if affinity.group != 0 {
+				return Err(ErrorCode::from(ERROR_INVALID_PARAMETER));
+			}
+			let mask = SetThreadAffinityMask(self.0, affinity.mask);
+			if mask == 0 {
+				return Err(ErrorCode::last());
+			}
+			Ok(GroupAffinity { group: 0, mask })
+		}
+	}
+}
+/// GUI state of a thread.
+///
+/// See [GUITHREADINFO structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms632604.aspx) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct GuiThreadInfo {
+	/// The thread state flags, eg. `GUI_CARETBLINKING` or `GUI_INMENUMODE`.
+	pub flags: u32,
+	/// The active window.
+	pub active: Option<Window>,
+	/// The window which has the keyboard focus.
+	pub focus: Option<Window>,
+	/// The window which has captured the mouse.
+	pub capture: Option<Window>,
+	/// The window which owns any active menus.
+	pub menu_owner: Option<Window>,
+	/// The window in a move or size loop.
+	pub move_size: Option<Window>,
+	/// The window which displays the caret.
+	pub caret: Option<Window>,
+	/// The caret rectangle in client coordinates of the caret window.
+	pub caret_rect: Rect,
+}
+
+impl Thread {
+	/// Get the GUI state of the thread, eg. which window has the keyboard focus.
+	///
+	/// Requires the `query_limited_information` right to look up the thread id.
+	/// Fails if the thread has no message queue.
+	///
+	/// See [GetGUIThreadInfo function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633506.aspx) for more information.
+	pub fn gui_info(&self) -> Result<GuiThreadInfo> {
+		let tid = self.tid()?;
+		unsafe {
+			let mut info: GUITHREADINFO = mem::zeroed();
+			info.cbSize = mem::size_of::<GUITHREADINFO>() as DWORD;
+			if GetGUIThreadInfo(tid.into_inner(), &mut info) == FALSE {
+				return Err(ErrorCode::last());
+			}
+			let window = |hwnd: HWND| if hwnd.is_null() { None } else { Some(Window::from_inner(hwnd)) };
+			Ok(GuiThreadInfo {
+				flags: info.flags,
+				active: window(info.hwndActive),
+				focus: window(info.hwndFocus),
+				capture: window(info.hwndCapture),
+				menu_owner: window(info.hwndMenuOwner),
+				move_size: window(info.hwndMoveSize),
+				caret: window(info.hwndCaret),
+				caret_rect: Rect::from(info.rcCaret),
+			})
+		}
+	}
+}
 impl Drop for Thread {
 	fn drop(&mut self) {
 		unsafe {
@@ -104,3 +258,12 @@ impl Drop for Thread {
 		}
 	}
 }
+
+#[test]
+fn test_gui_info() {
+	// Creating a window turns this thread into a GUI thread
+	let window = crate::wndclass::message_window(|_, _, _, _| None).unwrap();
+	let info = Thread::current().gui_info().unwrap();
+	assert_eq!(info.capture, None);
+	drop(window);
+}
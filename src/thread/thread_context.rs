@@ -0,0 +1,220 @@
+use std::{fmt, mem};
+use crate::winapi::*;
+use crate::thread::Thread;
+use crate::error::ErrorCode;
+use crate::{Result, AsInner};
+
+//----------------------------------------------------------------
+
+/// Thread context flags selecting which parts of the context are captured or set.
+///
+/// See [CONTEXT structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms679284.aspx) for more information.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub struct ContextFlags(DWORD);
+impl_inner!(ContextFlags: safe DWORD);
+impl ContextFlags {
+	pub const CONTROL: ContextFlags = ContextFlags(CONTEXT_CONTROL);
+	pub const INTEGER: ContextFlags = ContextFlags(CONTEXT_INTEGER);
+	pub const SEGMENTS: ContextFlags = ContextFlags(CONTEXT_SEGMENTS);
+	pub const FLOATING_POINT: ContextFlags = ContextFlags(CONTEXT_FLOATING_POINT);
+	pub const DEBUG_REGISTERS: ContextFlags = ContextFlags(CONTEXT_DEBUG_REGISTERS);
+	pub const FULL: ContextFlags = ContextFlags(CONTEXT_FULL);
+	pub const ALL: ContextFlags = ContextFlags(CONTEXT_ALL);
+}
+impl ContextFlags {
+	pub const fn contains(self, flags: ContextFlags) -> bool {
+		self.0 & flags.0 == flags.0
+	}
+}
+impl std::ops::BitOr for ContextFlags {
+	type Output = ContextFlags;
+	fn bitor(self, rhs: ContextFlags) -> ContextFlags {
+		ContextFlags(self.0 | rhs.0)
+	}
+}
+impl fmt::Debug for ContextFlags {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ContextFlags({:#x})", self.0)
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Thread context of a 64-bit thread.
+///
+/// The `CONTEXT` structure must be 16 byte aligned which the winapi definition does not guarantee.
+/// Only the AVX upper halves of the YMM registers are not available, they live in the extended `XSTATE` area.
+#[derive(Copy, Clone)]
+#[repr(C, align(16))]
+pub struct Context(CONTEXT);
+impl Context {
+	/// Returns the flags which were used to capture this context.
+	pub fn flags(&self) -> ContextFlags {
+		ContextFlags(self.0.ContextFlags)
+	}
+	/// Gives access to the raw context, eg. for the general purpose registers.
+	pub fn raw(&self) -> &CONTEXT {
+		&self.0
+	}
+	/// Gives mutable access to the raw context.
+	pub fn raw_mut(&mut self) -> &mut CONTEXT {
+		&mut self.0
+	}
+	/// Returns the instruction pointer.
+	pub fn ip(&self) -> u64 {
+		self.0.Rip
+	}
+	/// Returns the stack pointer.
+	pub fn sp(&self) -> u64 {
+		self.0.Rsp
+	}
+	/// Returns the `MXCSR` control and status register.
+	pub fn mxcsr(&self) -> u32 {
+		self.float_save().MxCsr
+	}
+	/// Returns the `n`th XMM register.
+	///
+	/// # Panics
+	///
+	/// Panics if `n` is not in range `0..16` or the context was not captured with `ContextFlags::FLOATING_POINT`.
+	pub fn xmm(&self, n: usize) -> [u8; 16] {
+		m128a_to_bytes(&self.float_save().XmmRegisters[n])
+	}
+	/// Returns the `n`th XMM register as four packed floats.
+	pub fn xmm_f32(&self, n: usize) -> [f32; 4] {
+		unsafe { mem::transmute(self.xmm(n)) }
+	}
+	/// Returns the `n`th XMM register as two packed doubles.
+	pub fn xmm_f64(&self, n: usize) -> [f64; 2] {
+		unsafe { mem::transmute(self.xmm(n)) }
+	}
+	/// Sets the `n`th XMM register.
+	///
+	/// # Panics
+	///
+	/// Panics if `n` is not in range `0..16` or the context was not captured with `ContextFlags::FLOATING_POINT`.
+	pub fn set_xmm(&mut self, n: usize, value: [u8; 16]) {
+		self.float_save_mut().XmmRegisters[n] = bytes_to_m128a(value);
+	}
+	/// Sets the `n`th XMM register to four packed floats.
+	pub fn set_xmm_f32(&mut self, n: usize, value: [f32; 4]) {
+		self.set_xmm(n, unsafe { mem::transmute::<[f32; 4], [u8; 16]>(value) })
+	}
+	/// Sets the `n`th XMM register to two packed doubles.
+	pub fn set_xmm_f64(&mut self, n: usize, value: [f64; 2]) {
+		self.set_xmm(n, unsafe { mem::transmute::<[f64; 2], [u8; 16]>(value) })
+	}
+	/// Returns the 80-bit extended precision x87 register `ST(n)`.
+	///
+	/// # Panics
+	///
+	/// Panics if `n` is not in range `0..8` or the context was not captured with `ContextFlags::FLOATING_POINT`.
+	pub fn st(&self, n: usize) -> [u8; 10] {
+		let bytes = m128a_to_bytes(&self.float_save().FloatRegisters[n]);
+		let mut st = [0u8; 10];
+		st.copy_from_slice(&bytes[..10]);
+		st
+	}
+	/// Sets the 80-bit extended precision x87 register `ST(n)`.
+	pub fn set_st(&mut self, n: usize, value: [u8; 10]) {
+		let mut bytes = [0u8; 16];
+		bytes[..10].copy_from_slice(&value);
+		self.float_save_mut().FloatRegisters[n] = bytes_to_m128a(bytes);
+	}
+	fn float_save(&self) -> &XMM_SAVE_AREA32 {
+		assert!(self.flags().contains(ContextFlags::FLOATING_POINT), "context does not contain the floating point state");
+		unsafe { self.0.u.FltSave() }
+	}
+	fn float_save_mut(&mut self) -> &mut XMM_SAVE_AREA32 {
+		assert!(self.flags().contains(ContextFlags::FLOATING_POINT), "context does not contain the floating point state");
+		unsafe { self.0.u.FltSave_mut() }
+	}
+}
+impl fmt::Debug for Context {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Context")
+			.field("flags", &self.flags())
+			.field("ip", &format_args!("{:#x}", self.0.Rip))
+			.field("sp", &format_args!("{:#x}", self.0.Rsp))
+			.finish()
+	}
+}
+
+fn m128a_to_bytes(value: &M128A) -> [u8; 16] {
+	let mut bytes = [0u8; 16];
+	bytes[..8].copy_from_slice(&value.Low.to_le_bytes());
+	bytes[8..].copy_from_slice(&value.High.to_le_bytes());
+	bytes
+}
+fn bytes_to_m128a(bytes: [u8; 16]) -> M128A {
+	let mut low = [0u8; 8];
+	let mut high = [0u8; 8];
+	low.copy_from_slice(&bytes[..8]);
+	high.copy_from_slice(&bytes[8..]);
+	M128A { Low: u64::from_le_bytes(low), High: i64::from_le_bytes(high) }
+}
+
+//----------------------------------------------------------------
+
+impl Thread {
+	/// Captures the context of this thread.
+	///
+	/// Requires the `get_context` right, the thread should be suspended to get a meaningful result.
+	///
+	/// See [GetThreadContext function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms679362.aspx) for more information.
+	pub fn get_context(&self, flags: ContextFlags) -> Result<Context> {
+		unsafe {
+			let mut context: Context = mem::zeroed();
+			context.0.ContextFlags = flags.0;
+			if GetThreadContext(*self.as_inner(), &mut context.0) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(context)
+			}
+		}
+	}
+	/// Sets the context of this thread.
+	///
+	/// Only the parts selected by the context's flags are written.
+	/// Requires the `set_context` right, the thread should be suspended.
+	///
+	/// See [SetThreadContext function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms680632.aspx) for more information.
+	pub fn set_context(&self, context: &Context) -> Result<()> {
+		unsafe {
+			if SetThreadContext(*self.as_inner(), &context.0) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_context_xmm() {
+	use std::sync::mpsc;
+	use std::os::windows::io::AsRawHandle;
+	use crate::FromInner;
+	let (tx, rx) = mpsc::channel::<()>();
+	let handle = std::thread::spawn(move || { let _ = rx.recv(); });
+	let thread = unsafe { Thread::from_inner(handle.as_raw_handle() as HANDLE) };
+	thread.suspend().unwrap();
+	let original = thread.get_context(ContextFlags::CONTROL | ContextFlags::FLOATING_POINT).unwrap();
+	assert_ne!(original.ip(), 0);
+	let mut context = original;
+	context.set_xmm_f32(7, [1.0, 2.0, 3.0, 4.0]);
+	thread.set_context(&context).unwrap();
+	let context = thread.get_context(ContextFlags::FLOATING_POINT).unwrap();
+	assert_eq!(context.xmm_f32(7), [1.0, 2.0, 3.0, 4.0]);
+	// Restore the nonvolatile register before letting the thread continue
+	thread.set_context(&original).unwrap();
+	thread.resume().unwrap();
+	// Don't close the handle owned by the join handle
+	std::mem::forget(thread);
+	drop(tx);
+	handle.join().unwrap();
+}
@@ -1,14 +1,92 @@
-use std::fmt;
+use std::{fmt, num, str};
 use crate::winapi::*;
 
 /// Wraps a thread identifier.
-#[derive(Copy, Clone, Eq, PartialEq)]
+///
+/// # Examples
+///
+/// Diff two snapshots of the threads in a process to detect newly created threads.
+///
+/// ```
+/// use std::collections::HashSet;
+/// use external::process::ProcessId;
+/// use external::thread::{EnumThreads, ThreadId};
+///
+/// fn snapshot(pid: ProcessId) -> HashSet<ThreadId> {
+///     EnumThreads::create().unwrap()
+///         .filter(|entry| entry.process_id() == pid)
+///         .map(|entry| entry.thread_id())
+///         .collect()
+/// }
+///
+/// let pid = ProcessId::current();
+/// let before = snapshot(pid);
+/// // Let the process do its thing...
+/// let after = snapshot(pid);
+/// for tid in after.difference(&before) {
+///     println!("new thread: {}", tid);
+/// }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct ThreadId(pub(super) DWORD);
 impl_inner!(ThreadId: DWORD);
+impl ThreadId {
+	/// Get the current thread's id.
+	///
+	/// See [GetCurrentThreadId function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms683183.aspx) for more information.
+	pub fn current() -> ThreadId {
+		ThreadId(unsafe { GetCurrentThreadId() })
+	}
+}
 
-// Custom Debug implementation to disable pretty formatting
+// Custom Debug and Display implementation to disable pretty formatting
 impl fmt::Debug for ThreadId {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "ThreadId({})", self.0)
 	}
 }
+impl fmt::Display for ThreadId {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+impl str::FromStr for ThreadId {
+	type Err = num::ParseIntError;
+	fn from_str(s: &str) -> Result<ThreadId, num::ParseIntError> {
+		s.parse().map(ThreadId)
+	}
+}
+
+#[test]
+fn test_new_threads() {
+	use std::collections::HashSet;
+	use std::sync::mpsc;
+	use crate::process::ProcessId;
+	use crate::thread::EnumThreads;
+
+	fn snapshot(pid: ProcessId) -> HashSet<ThreadId> {
+		EnumThreads::create().unwrap()
+			.filter(|entry| entry.process_id() == pid)
+			.map(|entry| entry.thread_id())
+			.collect()
+	}
+
+	let pid = ProcessId::current();
+	let before = snapshot(pid);
+	assert!(before.contains(&ThreadId::current()));
+
+	let (tid_tx, tid_rx) = mpsc::channel();
+	let (done_tx, done_rx) = mpsc::channel::<()>();
+	let handle = std::thread::spawn(move || {
+		tid_tx.send(ThreadId::current()).unwrap();
+		let _ = done_rx.recv();
+	});
+	let tid = tid_rx.recv().unwrap();
+
+	let after = snapshot(pid);
+	assert!(after.difference(&before).any(|&new| new == tid));
+	assert_eq!(tid.to_string().parse(), Ok(tid));
+
+	drop(done_tx);
+	handle.join().unwrap();
+}
@@ -1,68 +1,164 @@
+use std::{fmt, ops};
 use crate::winapi::*;
 
-//use winapi::{THREAD_ALL_ACCESS, THREAD_DIRECT_IMPERSONATION, THREAD_GET_CONTEXT, THREAD_IMPERSONATE, THREAD_QUERY_INFORMATION, THREAD_QUERY_LIMITED_INFORMATION,
-//	THREAD_SET_CONTEXT, THREAD_SET_INFORMATION, THREAD_SET_LIMITED_INFORMATION, THREAD_SET_THREAD_TOKEN, THREAD_SUSPEND_RESUME, THREAD_TERMINATE};
-
 /// Create thread access rights using the builder pattern.
 ///
 /// See [Thread Security and Access Rights](https://msdn.microsoft.com/en-us/library/windows/desktop/ms686769.aspx) for more information.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
 pub struct ThreadRights(DWORD);
 impl_inner!(ThreadRights: DWORD);
 impl ThreadRights {
-	pub fn new() -> ThreadRights {
+	pub const DELETE: ThreadRights = ThreadRights(DELETE);
+	pub const READ_CONTROL: ThreadRights = ThreadRights(READ_CONTROL);
+	pub const SYNCHRONIZE: ThreadRights = ThreadRights(SYNCHRONIZE);
+	pub const WRITE_DAC: ThreadRights = ThreadRights(WRITE_DAC);
+	pub const WRITE_OWNER: ThreadRights = ThreadRights(WRITE_OWNER);
+
+	pub const DIRECT_IMPERSONATION: ThreadRights = ThreadRights(THREAD_DIRECT_IMPERSONATION);
+	pub const GET_CONTEXT: ThreadRights = ThreadRights(THREAD_GET_CONTEXT);
+	pub const IMPERSONATE: ThreadRights = ThreadRights(THREAD_IMPERSONATE);
+	pub const QUERY_INFORMATION: ThreadRights = ThreadRights(THREAD_QUERY_INFORMATION);
+	pub const QUERY_LIMITED_INFORMATION: ThreadRights = ThreadRights(THREAD_QUERY_LIMITED_INFORMATION);
+	pub const SET_CONTEXT: ThreadRights = ThreadRights(THREAD_SET_CONTEXT);
+	pub const SET_INFORMATION: ThreadRights = ThreadRights(THREAD_SET_INFORMATION);
+	pub const SET_LIMITED_INFORMATION: ThreadRights = ThreadRights(THREAD_SET_LIMITED_INFORMATION);
+	pub const SET_THREAD_TOKEN: ThreadRights = ThreadRights(THREAD_SET_THREAD_TOKEN);
+	pub const SUSPEND_RESUME: ThreadRights = ThreadRights(THREAD_SUSPEND_RESUME);
+	pub const TERMINATE: ThreadRights = ThreadRights(THREAD_TERMINATE);
+
+	pub const ALL_ACCESS: ThreadRights = ThreadRights(0x1F0FFB);
+}
+impl ThreadRights {
+	pub const fn new() -> ThreadRights {
 		ThreadRights(0)
 	}
-	pub fn all_access() -> ThreadRights {
-		ThreadRights(0x1F0FFB)
+	pub const fn all_access() -> ThreadRights {
+		ThreadRights::ALL_ACCESS
+	}
+	/// Preset for querying and waiting on a thread.
+	pub const fn query() -> ThreadRights {
+		ThreadRights(THREAD_QUERY_LIMITED_INFORMATION | SYNCHRONIZE)
+	}
+	/// Returns if all the given rights are present.
+	pub const fn contains(self, rights: ThreadRights) -> bool {
+		self.0 & rights.0 == rights.0
 	}
 
-	pub fn delete(self) -> ThreadRights {
+	pub const fn delete(self) -> ThreadRights {
 		ThreadRights(self.0 | DELETE)
 	}
-	pub fn read_control(self) -> ThreadRights {
+	pub const fn read_control(self) -> ThreadRights {
 		ThreadRights(self.0 | READ_CONTROL)
 	}
-	pub fn synchronize(self) -> ThreadRights {
+	pub const fn synchronize(self) -> ThreadRights {
 		ThreadRights(self.0 | SYNCHRONIZE)
 	}
-	pub fn write_dac(self) -> ThreadRights {
+	pub const fn write_dac(self) -> ThreadRights {
 		ThreadRights(self.0 | WRITE_DAC)
 	}
-	pub fn write_owner(self) -> ThreadRights {
+	pub const fn write_owner(self) -> ThreadRights {
 		ThreadRights(self.0 | WRITE_OWNER)
 	}
 
-	pub fn direct_impersonation(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0200)
+	pub const fn direct_impersonation(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_DIRECT_IMPERSONATION)
 	}
-	pub fn get_context(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0008)
+	pub const fn get_context(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_GET_CONTEXT)
 	}
-	pub fn impersonate(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0100)
+	pub const fn impersonate(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_IMPERSONATE)
 	}
-	pub fn query_information(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0040)
+	pub const fn query_information(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_QUERY_INFORMATION)
 	}
-	pub fn query_limited_information(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0800)
+	pub const fn query_limited_information(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_QUERY_LIMITED_INFORMATION)
 	}
-	pub fn set_context(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0010)
+	pub const fn set_context(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_SET_CONTEXT)
 	}
-	pub fn set_information(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0020)
+	pub const fn set_information(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_SET_INFORMATION)
 	}
-	pub fn set_limited_information(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0400)
+	pub const fn set_limited_information(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_SET_LIMITED_INFORMATION)
 	}
-	pub fn set_thread_token(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0080)
+	pub const fn set_thread_token(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_SET_THREAD_TOKEN)
 	}
-	pub fn suspend_resume(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0002)
+	pub const fn suspend_resume(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_SUSPEND_RESUME)
 	}
-	pub fn terminate(self) -> ThreadRights {
-		ThreadRights(self.0 | 0x0001)
+	pub const fn terminate(self) -> ThreadRights {
+		ThreadRights(self.0 | THREAD_TERMINATE)
+	}
+}
+impl ops::BitOr for ThreadRights {
+	type Output = ThreadRights;
+	fn bitor(self, rhs: ThreadRights) -> ThreadRights {
+		ThreadRights(self.0 | rhs.0)
 	}
 }
+impl ops::BitOrAssign for ThreadRights {
+	fn bitor_assign(&mut self, rhs: ThreadRights) {
+		self.0 |= rhs.0;
+	}
+}
+
+static NAMES: [(ThreadRights, &str); 16] = [
+	(ThreadRights::DELETE, "DELETE"),
+	(ThreadRights::READ_CONTROL, "READ_CONTROL"),
+	(ThreadRights::SYNCHRONIZE, "SYNCHRONIZE"),
+	(ThreadRights::WRITE_DAC, "WRITE_DAC"),
+	(ThreadRights::WRITE_OWNER, "WRITE_OWNER"),
+	(ThreadRights::DIRECT_IMPERSONATION, "DIRECT_IMPERSONATION"),
+	(ThreadRights::GET_CONTEXT, "GET_CONTEXT"),
+	(ThreadRights::IMPERSONATE, "IMPERSONATE"),
+	(ThreadRights::QUERY_INFORMATION, "QUERY_INFORMATION"),
+	(ThreadRights::QUERY_LIMITED_INFORMATION, "QUERY_LIMITED_INFORMATION"),
+	(ThreadRights::SET_CONTEXT, "SET_CONTEXT"),
+	(ThreadRights::SET_INFORMATION, "SET_INFORMATION"),
+	(ThreadRights::SET_LIMITED_INFORMATION, "SET_LIMITED_INFORMATION"),
+	(ThreadRights::SET_THREAD_TOKEN, "SET_THREAD_TOKEN"),
+	(ThreadRights::SUSPEND_RESUME, "SUSPEND_RESUME"),
+	(ThreadRights::TERMINATE, "TERMINATE"),
+];
+
+impl fmt::Debug for ThreadRights {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if *self == ThreadRights::ALL_ACCESS {
+			return f.write_str("ThreadRights(ALL_ACCESS)");
+		}
+		f.write_str("ThreadRights(")?;
+		let mut rest = self.0;
+		let mut first = true;
+		for &(rights, name) in NAMES.iter() {
+			if self.contains(rights) {
+				if !first {
+					f.write_str(" | ")?;
+				}
+				f.write_str(name)?;
+				rest &= !rights.0;
+				first = false;
+			}
+		}
+		if rest != 0 || first {
+			if !first {
+				f.write_str(" | ")?;
+			}
+			write!(f, "{:#x}", rest)?;
+		}
+		f.write_str(")")
+	}
+}
+
+#[test]
+fn test_thread_rights() {
+	let rights = ThreadRights::new().suspend_resume() | ThreadRights::QUERY_INFORMATION;
+	assert!(rights.contains(ThreadRights::SUSPEND_RESUME));
+	assert!(!rights.contains(ThreadRights::TERMINATE));
+	assert!(ThreadRights::ALL_ACCESS.contains(rights));
+	assert_eq!(format!("{:?}", rights), "ThreadRights(QUERY_INFORMATION | SUSPEND_RESUME)");
+	assert_eq!(format!("{:?}", ThreadRights::new()), "ThreadRights(0x0)");
+}
@@ -0,0 +1,188 @@
+use std::{fmt, mem, ptr};
+use crate::winapi::*;
+use crate::thread::Thread;
+use crate::error::ErrorCode;
+use crate::{Result, AsInner, IntoInner};
+
+//----------------------------------------------------------------
+
+/// Create token access rights using the builder pattern.
+///
+/// See [Access Rights for Access-Token Objects](https://msdn.microsoft.com/en-us/library/windows/desktop/aa374905.aspx) for more information.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub struct ThreadTokenRights(DWORD);
+impl_inner!(ThreadTokenRights: safe DWORD);
+impl ThreadTokenRights {
+	pub const fn new() -> ThreadTokenRights {
+		ThreadTokenRights(0)
+	}
+	pub const ALL_ACCESS: ThreadTokenRights = ThreadTokenRights(TOKEN_ALL_ACCESS);
+
+	pub const fn adjust_default(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_ADJUST_DEFAULT)
+	}
+	pub const fn adjust_groups(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_ADJUST_GROUPS)
+	}
+	pub const fn adjust_privileges(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_ADJUST_PRIVILEGES)
+	}
+	pub const fn adjust_sessionid(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_ADJUST_SESSIONID)
+	}
+	pub const fn assign_primary(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_ASSIGN_PRIMARY)
+	}
+	pub const fn duplicate(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_DUPLICATE)
+	}
+	pub const fn impersonate(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_IMPERSONATE)
+	}
+	pub const fn query(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_QUERY)
+	}
+	pub const fn query_source(self) -> ThreadTokenRights {
+		ThreadTokenRights(self.0 | TOKEN_QUERY_SOURCE)
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Privilege constants.
+///
+/// See [Privilege Constants](https://msdn.microsoft.com/en-us/library/windows/desktop/bb530716.aspx) for more information.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Privilege(&'static [u16]);
+impl Privilege {
+	pub const SE_BACKUP_NAME: Privilege = Privilege(&wide_str!('S' 'e' 'B' 'a' 'c' 'k' 'u' 'p' 'P' 'r' 'i' 'v' 'i' 'l' 'e' 'g' 'e' 0));
+	pub const SE_DEBUG_NAME: Privilege = Privilege(&wide_str!('S' 'e' 'D' 'e' 'b' 'u' 'g' 'P' 'r' 'i' 'v' 'i' 'l' 'e' 'g' 'e' 0));
+	pub const SE_INC_BASE_PRIORITY_NAME: Privilege = Privilege(&wide_str!('S' 'e' 'I' 'n' 'c' 'r' 'e' 'a' 's' 'e' 'B' 'a' 's' 'e' 'P' 'r' 'i' 'o' 'r' 'i' 't' 'y' 'P' 'r' 'i' 'v' 'i' 'l' 'e' 'g' 'e' 0));
+	pub const SE_LOAD_DRIVER_NAME: Privilege = Privilege(&wide_str!('S' 'e' 'L' 'o' 'a' 'd' 'D' 'r' 'i' 'v' 'e' 'r' 'P' 'r' 'i' 'v' 'i' 'l' 'e' 'g' 'e' 0));
+	pub const SE_RESTORE_NAME: Privilege = Privilege(&wide_str!('S' 'e' 'R' 'e' 's' 't' 'o' 'r' 'e' 'P' 'r' 'i' 'v' 'i' 'l' 'e' 'g' 'e' 0));
+	pub const SE_SHUTDOWN_NAME: Privilege = Privilege(&wide_str!('S' 'e' 'S' 'h' 'u' 't' 'd' 'o' 'w' 'n' 'P' 'r' 'i' 'v' 'i' 'l' 'e' 'g' 'e' 0));
+	pub const SE_TCB_NAME: Privilege = Privilege(&wide_str!('S' 'e' 'T' 'c' 'b' 'P' 'r' 'i' 'v' 'i' 'l' 'e' 'g' 'e' 0));
+}
+impl Privilege {
+	/// The privilege name without nul terminator.
+	pub fn name_wide(self) -> &'static [u16] {
+		&self.0[..self.0.len() - 1]
+	}
+}
+impl fmt::Debug for Privilege {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Privilege({})", String::from_utf16_lossy(self.name_wide()))
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Thread access token.
+#[derive(Debug)]
+pub struct ThreadToken(HANDLE);
+impl_inner!(ThreadToken: HANDLE);
+impl ThreadToken {
+	/// Opens the access token associated with a thread.
+	///
+	/// Fails with `ERROR_NO_TOKEN` if the thread is not impersonating, see [`ThreadToken::impersonate_self`].
+	///
+	/// See [OpenThreadToken function](https://msdn.microsoft.com/en-us/library/windows/desktop/aa379296.aspx) for more information.
+	pub fn open(thread: &Thread, rights: ThreadTokenRights, open_as_self: bool) -> Result<ThreadToken> {
+		unsafe {
+			let mut handle = ptr::null_mut();
+			if OpenThreadToken(*thread.as_inner(), rights.into_inner(), open_as_self as BOOL, &mut handle) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(ThreadToken(handle))
+			}
+		}
+	}
+	/// Enables or disables a privilege in this token.
+	///
+	/// Requires the `adjust_privileges` right.
+	/// Fails with `ERROR_NOT_ALL_ASSIGNED` if the token does not hold the privilege, eg. `SeDebugPrivilege` when not elevated.
+	///
+	/// See [AdjustTokenPrivileges function](https://msdn.microsoft.com/en-us/library/windows/desktop/aa375202.aspx) for more information.
+	pub fn set_privilege(&self, privilege: Privilege, enable: bool) -> Result<()> {
+		unsafe {
+			let mut luid = mem::MaybeUninit::<LUID>::uninit();
+			if LookupPrivilegeValueW(ptr::null(), privilege.0.as_ptr(), luid.as_mut_ptr()) == FALSE {
+				return Err(ErrorCode::last());
+			}
+			let mut privileges = TOKEN_PRIVILEGES {
+				PrivilegeCount: 1,
+				Privileges: [LUID_AND_ATTRIBUTES {
+					Luid: luid.assume_init(),
+					Attributes: if enable { SE_PRIVILEGE_ENABLED } else { 0 },
+				}],
+			};
+			let size = mem::size_of::<TOKEN_PRIVILEGES>() as DWORD;
+			if AdjustTokenPrivileges(self.0, FALSE, &mut privileges, size, ptr::null_mut(), ptr::null_mut()) == FALSE {
+				return Err(ErrorCode::last());
+			}
+			// AdjustTokenPrivileges succeeds even when not all privileges were assigned
+			let err = ErrorCode::last();
+			if err.into_inner() == ERROR_NOT_ALL_ASSIGNED {
+				Err(err)
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Makes the calling thread impersonate the security context of its process.
+	///
+	/// See [ImpersonateSelf function](https://msdn.microsoft.com/en-us/library/windows/desktop/aa378729.aspx) for more information.
+	pub fn impersonate_self() -> Result<()> {
+		unsafe {
+			if ImpersonateSelf(SecurityImpersonation) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Terminates the impersonation of the calling thread.
+	///
+	/// See [RevertToSelf function](https://msdn.microsoft.com/en-us/library/windows/desktop/aa379317.aspx) for more information.
+	pub fn revert_to_self() -> Result<()> {
+		unsafe {
+			if RevertToSelf() == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+}
+impl Drop for ThreadToken {
+	fn drop(&mut self) {
+		unsafe {
+			CloseHandle(self.0);
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn debug_privilege() {
+		ThreadToken::impersonate_self().unwrap();
+		let rights = ThreadTokenRights::new().adjust_privileges().query();
+		let token = ThreadToken::open(&Thread::current(), rights, true).unwrap();
+		// Only succeeds when running elevated
+		match token.set_privilege(Privilege::SE_DEBUG_NAME, true) {
+			Ok(()) => (),
+			Err(err) => assert_eq!(err, ErrorCode::from(ERROR_NOT_ALL_ASSIGNED)),
+		}
+		drop(token);
+		ThreadToken::revert_to_self().unwrap();
+	}
+}
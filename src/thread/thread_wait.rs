@@ -0,0 +1,104 @@
+use std::ptr;
+use std::time::{Duration, Instant};
+use crate::winapi::*;
+use crate::thread::Thread;
+use crate::error::ErrorCode;
+use crate::util::timeout_millis;
+use crate::{Result, AsInner};
+
+/// Outcome of waiting on a single object.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WaitResult {
+	/// The object was signaled, eg. the thread or process has finished.
+	Signaled,
+	/// The object is a mutex whose owning thread terminated without releasing it.
+	Abandoned,
+	/// The timeout elapsed before the object was signaled.
+	Timeout,
+	/// An alertable wait was interrupted by a queued APC or I/O completion routine.
+	IoCompletion,
+}
+
+/// Waits on a single handle with `WaitForSingleObjectEx`.
+pub(crate) fn wait_handle(handle: HANDLE, timeout: Option<Duration>, alertable: bool) -> Result<WaitResult> {
+	let result = unsafe { WaitForSingleObjectEx(handle, timeout_millis(timeout), alertable as BOOL) };
+	match result {
+		WAIT_OBJECT_0 => Ok(WaitResult::Signaled),
+		WAIT_ABANDONED => Ok(WaitResult::Abandoned),
+		WAIT_TIMEOUT => Ok(WaitResult::Timeout),
+		WAIT_IO_COMPLETION => Ok(WaitResult::IoCompletion),
+		_ => Err(ErrorCode::last()),
+	}
+}
+
+/// Wait for all the threads to finish.
+///
+/// Returns `Ok(true)` when all the threads have finished or `Ok(false)` if the timeout elapsed first.
+///
+/// More than `MAXIMUM_WAIT_OBJECTS` threads are waited on in chunks which share the same timeout.
+///
+/// See [WaitForMultipleObjects function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms687025.aspx) for more information.
+pub fn wait_all(threads: &[&Thread], timeout: Duration) -> Result<bool> {
+	let deadline = Instant::now().checked_add(timeout);
+	let mut handles = [ptr::null_mut(); MAXIMUM_WAIT_OBJECTS as usize];
+	for chunk in threads.chunks(MAXIMUM_WAIT_OBJECTS as usize) {
+		for (handle, thread) in handles.iter_mut().zip(chunk) {
+			*handle = *thread.as_inner();
+		}
+		let timeout = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+		let result = unsafe { WaitForMultipleObjects(chunk.len() as DWORD, handles.as_ptr(), TRUE, timeout_millis(timeout)) };
+		match result {
+			WAIT_FAILED => return Err(ErrorCode::last()),
+			WAIT_TIMEOUT => return Ok(false),
+			_ => (),
+		}
+	}
+	Ok(true)
+}
+
+/// Wait for any of the threads to finish.
+///
+/// Returns the index of a finished thread or `Ok(None)` if the timeout elapsed first.
+///
+/// Waiting on more than `MAXIMUM_WAIT_OBJECTS` threads (or none at all) is an `ERROR_INVALID_PARAMETER` error.
+///
+/// See [WaitForMultipleObjects function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms687025.aspx) for more information.
+pub fn wait_any(threads: &[&Thread], timeout: Duration) -> Result<Option<usize>> {
+	if threads.is_empty() || threads.len() > MAXIMUM_WAIT_OBJECTS as usize {
+		return Err(ErrorCode::from(ERROR_INVALID_PARAMETER));
+	}
+	let mut handles = [ptr::null_mut(); MAXIMUM_WAIT_OBJECTS as usize];
+	for (handle, thread) in handles.iter_mut().zip(threads) {
+		*handle = *thread.as_inner();
+	}
+	let result = unsafe { WaitForMultipleObjects(threads.len() as DWORD, handles.as_ptr(), FALSE, timeout_millis(Some(timeout))) };
+	match result {
+		WAIT_FAILED => Err(ErrorCode::last()),
+		WAIT_TIMEOUT => Ok(None),
+		_ if result >= WAIT_ABANDONED_0 => Ok(Some((result - WAIT_ABANDONED_0) as usize)),
+		_ => Ok(Some((result - WAIT_OBJECT_0) as usize)),
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_wait_threads() {
+	use std::sync::mpsc;
+	use std::os::windows::io::AsRawHandle;
+	use crate::FromInner;
+	let (tx, rx) = mpsc::channel::<()>();
+	let waiting = std::thread::spawn(move || { let _ = rx.recv(); });
+	let finished = std::thread::spawn(|| ());
+	let threads = unsafe { [Thread::from_inner(waiting.as_raw_handle() as HANDLE), Thread::from_inner(finished.as_raw_handle() as HANDLE)] };
+	assert_eq!(wait_any(&[&threads[1], &threads[0]], Duration::from_secs(5)), Ok(Some(0)));
+	assert_eq!(wait_any(&[&threads[0]], Duration::from_millis(10)), Ok(None));
+	assert_eq!(wait_all(&[&threads[0], &threads[1]], Duration::from_millis(10)), Ok(false));
+	assert_eq!(wait_any(&[], Duration::from_millis(10)), Err(ErrorCode::from(ERROR_INVALID_PARAMETER)));
+	drop(tx);
+	assert_eq!(wait_all(&[&threads[0], &threads[1]], Duration::from_secs(5)), Ok(true));
+	// Don't close the handles owned by the join handles
+	std::mem::forget(threads);
+	waiting.join().unwrap();
+	finished.join().unwrap();
+}
@@ -24,3 +24,62 @@ pub fn from_char_buf(buf: &[u8]) -> &[u8] {
 	}
 	&buf[..len]
 }
+
+/// Converts a `FILETIME` timestamp to `SystemTime`, `None` if the timestamp is zero.
+pub(crate) fn filetime_to_system_time(ft: &crate::winapi::FILETIME) -> Option<std::time::SystemTime> {
+	// Number of 100ns intervals between 1601-01-01 and 1970-01-01
+	const UNIX_EPOCH_INTERVALS: u64 = 116_444_736_000_000_000;
+	let intervals = (ft.dwHighDateTime as u64) << 32 | ft.dwLowDateTime as u64;
+	if intervals == 0 {
+		return None;
+	}
+	let epoch = std::time::UNIX_EPOCH;
+	Some(if intervals >= UNIX_EPOCH_INTERVALS {
+		epoch + filetime_to_duration(intervals - UNIX_EPOCH_INTERVALS)
+	}
+	else {
+		epoch - filetime_to_duration(UNIX_EPOCH_INTERVALS - intervals)
+	})
+}
+
+/// Converts a `FILETIME` interval count of 100ns to `Duration`.
+pub(crate) fn filetime_to_duration(intervals: u64) -> std::time::Duration {
+	std::time::Duration::new(intervals / 10_000_000, (intervals % 10_000_000) as u32 * 100)
+}
+
+/// Converts an optional timeout to milliseconds for the wait functions.
+///
+/// `None` means `INFINITE`, durations are rounded up and saturate just below `INFINITE`.
+#[inline]
+pub(crate) fn timeout_millis(timeout: Option<std::time::Duration>) -> u32 {
+	match timeout {
+		Some(timeout) => {
+			// Round up any partial millisecond
+			let millis = timeout.as_millis() + (timeout.subsec_nanos() % 1_000_000 != 0) as u128;
+			if millis >= 0xFFFFFFFF { 0xFFFFFFFE } else { millis as u32 }
+		},
+		None => 0xFFFFFFFF,
+	}
+}
+
+/// Looks up an export from kernel32 at runtime for APIs which may be missing on older systems.
+pub(crate) unsafe fn kernel32_proc(name: &[u8]) -> Option<usize> {
+	static KERNEL32: [u16; 13] = wide_str!('k' 'e' 'r' 'n' 'e' 'l' '3' '2' '.' 'd' 'l' 'l' 0);
+	module_proc(&KERNEL32, name)
+}
+
+/// Looks up an export from user32 at runtime for APIs which may be missing on older systems.
+pub(crate) unsafe fn user32_proc(name: &[u8]) -> Option<usize> {
+	static USER32: [u16; 11] = wide_str!('u' 's' 'e' 'r' '3' '2' '.' 'd' 'l' 'l' 0);
+	module_proc(&USER32, name)
+}
+
+unsafe fn module_proc(module: &[u16], name: &[u8]) -> Option<usize> {
+	use crate::winapi::*;
+	let module = GetModuleHandleW(module.as_ptr());
+	if module.is_null() {
+		return None;
+	}
+	let address = GetProcAddress(module, name.as_ptr() as *const c_char);
+	if address.is_null() { None } else { Some(address as usize) }
+}
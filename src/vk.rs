@@ -2,7 +2,14 @@
 Virtual keys.
 !*/
 
+use std::{fmt, ops};
+use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicU8, Ordering};
+use dataview::Pod;
 use crate::winapi::*;
+use crate::thread::ThreadId;
+use crate::input::KeyboardInput;
+use crate::IntoInner;
 
 /// Windows virtual key code.
 ///
@@ -81,6 +88,12 @@ impl VirtualKey {
 	pub const PRINT_SCREEN: VirtualKey = VirtualKey(0x2c);
 	pub const INSERT: VirtualKey = VirtualKey(0x2d);
 	pub const DELETE: VirtualKey = VirtualKey(0x2e);
+	pub const HELP: VirtualKey = VirtualKey(0x2f);
+
+	pub const LWIN: VirtualKey = VirtualKey(0x5b);
+	pub const RWIN: VirtualKey = VirtualKey(0x5c);
+	pub const APPS: VirtualKey = VirtualKey(0x5d);
+	pub const SLEEP: VirtualKey = VirtualKey(0x5f);
 
 	pub const NUMPAD0: VirtualKey = VirtualKey(0x60);
 	pub const NUMPAD1: VirtualKey = VirtualKey(0x61);
@@ -111,6 +124,18 @@ impl VirtualKey {
 	pub const F10: VirtualKey = VirtualKey(0x79);
 	pub const F11: VirtualKey = VirtualKey(0x7a);
 	pub const F12: VirtualKey = VirtualKey(0x7b);
+	pub const F13: VirtualKey = VirtualKey(0x7c);
+	pub const F14: VirtualKey = VirtualKey(0x7d);
+	pub const F15: VirtualKey = VirtualKey(0x7e);
+	pub const F16: VirtualKey = VirtualKey(0x7f);
+	pub const F17: VirtualKey = VirtualKey(0x80);
+	pub const F18: VirtualKey = VirtualKey(0x81);
+	pub const F19: VirtualKey = VirtualKey(0x82);
+	pub const F20: VirtualKey = VirtualKey(0x83);
+	pub const F21: VirtualKey = VirtualKey(0x84);
+	pub const F22: VirtualKey = VirtualKey(0x85);
+	pub const F23: VirtualKey = VirtualKey(0x86);
+	pub const F24: VirtualKey = VirtualKey(0x87);
 
 	pub const NUM_LOCK: VirtualKey = VirtualKey(0x90);
 	pub const SCROLL_LOCK: VirtualKey = VirtualKey(0x91);
@@ -121,23 +146,91 @@ impl VirtualKey {
 	pub const RCTRL: VirtualKey = VirtualKey(0xa3);
 	pub const LALT: VirtualKey = VirtualKey(0xa4);
 	pub const RALT: VirtualKey = VirtualKey(0xa5);
+
+	pub const BROWSER_BACK: VirtualKey = VirtualKey(0xa6);
+	pub const BROWSER_FORWARD: VirtualKey = VirtualKey(0xa7);
+	pub const BROWSER_REFRESH: VirtualKey = VirtualKey(0xa8);
+	pub const BROWSER_STOP: VirtualKey = VirtualKey(0xa9);
+	pub const BROWSER_SEARCH: VirtualKey = VirtualKey(0xaa);
+	pub const BROWSER_FAVORITES: VirtualKey = VirtualKey(0xab);
+	pub const BROWSER_HOME: VirtualKey = VirtualKey(0xac);
+	pub const VOLUME_MUTE: VirtualKey = VirtualKey(0xad);
+	pub const VOLUME_DOWN: VirtualKey = VirtualKey(0xae);
+	pub const VOLUME_UP: VirtualKey = VirtualKey(0xaf);
+	pub const MEDIA_NEXT_TRACK: VirtualKey = VirtualKey(0xb0);
+	pub const MEDIA_PREV_TRACK: VirtualKey = VirtualKey(0xb1);
+	pub const MEDIA_STOP: VirtualKey = VirtualKey(0xb2);
+	pub const MEDIA_PLAY_PAUSE: VirtualKey = VirtualKey(0xb3);
+	pub const LAUNCH_MAIL: VirtualKey = VirtualKey(0xb4);
+	pub const LAUNCH_MEDIA_SELECT: VirtualKey = VirtualKey(0xb5);
+	pub const LAUNCH_APP1: VirtualKey = VirtualKey(0xb6);
+	pub const LAUNCH_APP2: VirtualKey = VirtualKey(0xb7);
+
+	/// `;:` on the US keyboard.
+	pub const OEM_1: VirtualKey = VirtualKey(0xba);
+	/// `=+` on any keyboard.
+	pub const OEM_PLUS: VirtualKey = VirtualKey(0xbb);
+	/// `,<` on any keyboard.
+	pub const OEM_COMMA: VirtualKey = VirtualKey(0xbc);
+	/// `-_` on any keyboard.
+	pub const OEM_MINUS: VirtualKey = VirtualKey(0xbd);
+	/// `.>` on any keyboard.
+	pub const OEM_PERIOD: VirtualKey = VirtualKey(0xbe);
+	/// `/?` on the US keyboard.
+	pub const OEM_2: VirtualKey = VirtualKey(0xbf);
+	/// `` `~ `` on the US keyboard.
+	pub const OEM_3: VirtualKey = VirtualKey(0xc0);
+	/// `[{` on the US keyboard.
+	pub const OEM_4: VirtualKey = VirtualKey(0xdb);
+	/// `\|` on the US keyboard.
+	pub const OEM_5: VirtualKey = VirtualKey(0xdc);
+	/// `]}` on the US keyboard.
+	pub const OEM_6: VirtualKey = VirtualKey(0xdd);
+	/// `'"` on the US keyboard.
+	pub const OEM_7: VirtualKey = VirtualKey(0xde);
+	/// Varies by keyboard.
+	pub const OEM_8: VirtualKey = VirtualKey(0xdf);
+	/// `<>` or `\|` on the RT 102-key keyboard.
+	pub const OEM_102: VirtualKey = VirtualKey(0xe2);
 }
 impl VirtualKey {
 	/// Press a virtual key.
 	#[inline]
 	pub fn down(self) {
-		unsafe { keybd_event(self.0, self.to_scan_code(), 0, 0); }
+		let _ = crate::input::send(&[KeyboardInput::key_down(self).into()]);
 	}
 	/// Release a virtual key.
 	#[inline]
 	pub fn up(self) {
-		unsafe { keybd_event(self.0, self.to_scan_code(), KEYEVENTF_KEYUP, 0); }
+		let _ = crate::input::send(&[KeyboardInput::key_up(self).into()]);
+	}
+	/// Press a virtual key tagging the event with extra info.
+	///
+	/// Low level hooks can recognize the tagged event with `KeyboardLL::is_self_injected_by`.
+	#[inline]
+	pub fn down_tagged(self, tag: usize) {
+		let _ = crate::input::send(&[KeyboardInput::key_down(self).tagged(tag).into()]);
+	}
+	/// Release a virtual key tagging the event with extra info.
+	///
+	/// Low level hooks can recognize the tagged event with `KeyboardLL::is_self_injected_by`.
+	#[inline]
+	pub fn up_tagged(self, tag: usize) {
+		let _ = crate::input::send(&[KeyboardInput::key_up(self).tagged(tag).into()]);
 	}
 	/// Gets the async key state.
 	#[inline]
 	pub fn async_state(self) -> bool {
 		unsafe { GetAsyncKeyState(self.0 as i32) as u16 & 0x8000 != 0 }
 	}
+	/// Gets the key state as seen by the calling thread's message queue, including the toggle state.
+	///
+	/// See [GetKeyState function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646301.aspx) for more information.
+	#[inline]
+	pub fn state(self) -> KeyState {
+		let state = unsafe { GetKeyState(self.0 as i32) as u16 };
+		KeyState((state >> 8) as u8 & 0x80 | state as u8 & 0x01)
+	}
 	/// Translates the virtual key to a character, if possible.
 	#[inline]
 	pub fn to_char(self) -> Option<char> {
@@ -149,22 +242,831 @@ impl VirtualKey {
 			Some(std::char::from_u32_unchecked(value & 0x7ffffff))
 		}
 	}
+	/// Translates the virtual key to characters with the given keyboard state and layout.
+	///
+	/// Unlike [`to_char`](VirtualKey::to_char) this respects the modifiers in the keyboard state and the layout,
+	/// eg. shift and `'1'` translate to `'!'` on the US layout. The current thread's layout is used if none is given.
+	///
+	/// The translation goes through the same dead key buffer as the keyboard input of the thread:
+	/// translating a dead key leaves it pending and combines it with the next translated key, also the one typed by the user.
+	/// Use [`to_unicode_peek`](VirtualKey::to_unicode_peek) to avoid this.
+	///
+	/// See [ToUnicodeEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646322.aspx) for more information.
+	pub fn to_unicode(self, scan: u8, state: &KeyboardState, layout: Option<KeyboardLayout>) -> ToUnicodeResult {
+		let layout = layout.unwrap_or_else(KeyboardLayout::current);
+		let mut buf = [0u16; 8];
+		let len = unsafe {
+			ToUnicodeEx(self.0 as u32, scan as u32, state.0.as_ptr() as *const u8, buf.as_mut_ptr(), buf.len() as c_int, 0, layout.0)
+		};
+		match len {
+			0 => ToUnicodeResult::NoTranslation,
+			len if len < 0 => ToUnicodeResult::DeadKey(std::char::decode_utf16(buf[..1].iter().cloned()).next().and_then(Result::ok).unwrap_or('\u{fffd}')),
+			len => {
+				let mut chars = KeyChars::default();
+				for chr in std::char::decode_utf16(buf[..len as usize].iter().cloned()) {
+					chars.push(chr.unwrap_or('\u{fffd}'));
+				}
+				ToUnicodeResult::Chars(chars)
+			},
+		}
+	}
+	/// Translates the virtual key to characters without leaving a dead key pending.
+	///
+	/// When the key is a dead key it is translated a second time, which combines the dead key with itself and clears it from the dead key buffer.
+	/// A dead key which was already pending before the call is still consumed, this cannot be avoided.
+	pub fn to_unicode_peek(self, scan: u8, state: &KeyboardState, layout: Option<KeyboardLayout>) -> ToUnicodeResult {
+		let result = self.to_unicode(scan, state, layout);
+		if let ToUnicodeResult::DeadKey(_) = result {
+			let _ = self.to_unicode(scan, state, layout);
+		}
+		result
+	}
+	/// Is this an extended key, its scan code has the `E0` prefix.
+	///
+	/// Distinguishes eg. the arrow keys and the insert, delete, home, end, page up and page down cluster from the numpad keys sharing their scan codes.
+	pub const fn is_extended(self) -> bool {
+		match self.0 {
+			// PAGE_UP, PAGE_DOWN, END, HOME, LEFT, UP, RIGHT, DOWN
+			0x21..=0x28 => true,
+			// INSERT, DELETE
+			0x2d | 0x2e => true,
+			// LWIN, RWIN, APPS
+			0x5b..=0x5d => true,
+			// DIVIDE, NUM_LOCK
+			0x6f | 0x90 => true,
+			// RCTRL, RALT
+			0xa3 | 0xa5 => true,
+			// The browser, volume, media and launch keys
+			0xa6..=0xb7 => true,
+			_ => false,
+		}
+	}
+	/// Gets the localized name of the key for display to the user, eg. `"Right Ctrl"` or `"Num +"`.
+	///
+	/// Falls back to [`to_str`](VirtualKey::to_str) for keys without a scan code or a name in the current keyboard layout.
+	///
+	/// See [GetKeyNameText function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646300.aspx) for more information.
+	pub fn display_name(self) -> Option<String> {
+		let scan = unsafe { MapVirtualKeyW(self.0 as u32, MAPVK_VK_TO_VSC) };
+		if scan != 0 {
+			let mut lparam = (scan as LONG & 0xff) << 16;
+			if self.is_extended() {
+				lparam |= 1 << 24;
+			}
+			let mut buf = [0u16; 64];
+			let len = unsafe { GetKeyNameTextW(lparam, buf.as_mut_ptr(), buf.len() as c_int) };
+			if len > 0 {
+				return Some(String::from_utf16_lossy(&buf[..len as usize]));
+			}
+		}
+		self.to_str().map(String::from)
+	}
+	/// Translates the virtual key to its scan code including the extended prefix.
+	///
+	/// Returns the zero scan code if the key has no scan code.
+	///
+	/// See [MapVirtualKey function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646306.aspx) for more information.
+	#[inline]
+	pub fn scan_code(self) -> ScanCode {
+		ScanCode::from_mapped(self, unsafe { MapVirtualKeyW(self.0 as u32, MAPVK_VK_TO_VSC_EX) })
+	}
+	/// Translates the virtual key to its scan code using the given keyboard layout.
+	///
+	/// See [MapVirtualKeyEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646307.aspx) for more information.
+	#[inline]
+	pub fn scan_code_in(self, layout: KeyboardLayout) -> ScanCode {
+		ScanCode::from_mapped(self, unsafe { MapVirtualKeyExW(self.0 as u32, MAPVK_VK_TO_VSC_EX, layout.0) })
+	}
 	/// Translates the virtual key to a virtual scan code, if possible.
+	#[deprecated(note = "the extended prefix is lost, use `scan_code`")]
 	#[inline]
 	pub fn to_scan_code(self) -> u8 {
-		unsafe { MapVirtualKeyW(self.0 as u32, MAPVK_VK_TO_VSC) as u8 }
+		self.scan_code().code()
 	}
 	/// Gets the virtual key for a virtual scan code.
+	#[deprecated(note = "use `ScanCode::to_vk`")]
 	#[inline]
 	pub fn from_scan_code(scan_code: u8) -> VirtualKey {
 		unsafe { VirtualKey(MapVirtualKeyW(scan_code as u32, MAPVK_VSC_TO_VK) as u8) }
 	}
 	/// Gets the virtual key for a virtual scan code which distinguishes between left- and right-hand keys.
+	#[deprecated(note = "the extended prefix is lost, use `ScanCode::to_vk`")]
 	#[inline]
 	pub fn from_scan_code_ex(scan_code: u8) -> VirtualKey {
-		unsafe { VirtualKey(MapVirtualKeyW(scan_code as u32, MAPVK_VSC_TO_VK_EX) as u8) }
+		ScanCode::new(scan_code, false).to_vk()
+	}
+	/// Translates the virtual key to a virtual scan code using the given keyboard layout.
+	#[deprecated(note = "the extended prefix is lost, use `scan_code_in`")]
+	#[inline]
+	pub fn to_scan_code_in(self, layout: KeyboardLayout) -> u8 {
+		self.scan_code_in(layout).code()
+	}
+	/// Gets the virtual key for a virtual scan code using the given keyboard layout.
+	#[deprecated(note = "use `ScanCode::to_vk_in`")]
+	#[inline]
+	pub fn from_scan_code_in(scan_code: u8, layout: KeyboardLayout) -> VirtualKey {
+		unsafe { VirtualKey(MapVirtualKeyExW(scan_code as u32, MAPVK_VSC_TO_VK, layout.0) as u8) }
+	}
+	/// Gets the virtual key for a virtual scan code using the given keyboard layout which distinguishes between left- and right-hand keys.
+	#[deprecated(note = "the extended prefix is lost, use `ScanCode::to_vk_in`")]
+	#[inline]
+	pub fn from_scan_code_ex_in(scan_code: u8, layout: KeyboardLayout) -> VirtualKey {
+		ScanCode::new(scan_code, false).to_vk_in(layout)
+	}
+}
+
+/// Keyboard scan code.
+///
+/// The low byte is the scan code, the high byte is the `0xE0` prefix of the extended keys (or `0xE1` for the pause key).
+/// The prefix distinguishes eg. the right control key from the left control key, and the numpad enter key from the enter key.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct ScanCode(u16);
+impl_inner!(ScanCode: safe u16);
+impl ScanCode {
+	/// Creates a scan code, with the `0xE0` prefix if extended.
+	#[inline]
+	pub const fn new(code: u8, extended: bool) -> ScanCode {
+		ScanCode(code as u16 | if extended { 0xe000 } else { 0 })
+	}
+	fn from_mapped(vk: VirtualKey, mapped: u32) -> ScanCode {
+		let code = mapped as u8;
+		match (mapped >> 8) as u8 {
+			prefix @ 0xe0 | prefix @ 0xe1 => ScanCode((prefix as u16) << 8 | code as u16),
+			// Older systems do not report the prefix for every extended key
+			_ => ScanCode::new(code, code != 0 && vk.is_extended()),
+		}
+	}
+	/// Returns the scan code without its prefix.
+	#[inline]
+	pub const fn code(self) -> u8 {
+		self.0 as u8
+	}
+	/// Returns if the scan code has the `0xE0` prefix.
+	#[inline]
+	pub const fn is_extended(self) -> bool {
+		self.0 >> 8 == 0xe0
+	}
+	/// Returns if the scan code has the `0xE1` prefix of the pause key.
+	///
+	/// The pause key sends the `E1 1D 45` sequence which cannot be injected by scan code, send [`VirtualKey::PAUSE`] by virtual key instead.
+	#[inline]
+	pub const fn is_pause(self) -> bool {
+		self.0 >> 8 == 0xe1
+	}
+	/// Translates the scan code to a virtual key which distinguishes between left- and right-hand keys.
+	///
+	/// See [MapVirtualKey function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646306.aspx) for more information.
+	#[inline]
+	pub fn to_vk(self) -> VirtualKey {
+		unsafe { VirtualKey(MapVirtualKeyW(self.0 as u32, MAPVK_VSC_TO_VK_EX) as u8) }
+	}
+	/// Translates the scan code to a virtual key using the given keyboard layout.
+	#[inline]
+	pub fn to_vk_in(self, layout: KeyboardLayout) -> VirtualKey {
+		unsafe { VirtualKey(MapVirtualKeyExW(self.0 as u32, MAPVK_VSC_TO_VK_EX, layout.0) as u8) }
+	}
+	/// Returns the `SendInput` flags to inject the key by this scan code, `KEYEVENTF_SCANCODE` and `KEYEVENTF_EXTENDEDKEY` if extended.
+	#[inline]
+	pub const fn input_flags(self) -> u32 {
+		KEYEVENTF_SCANCODE | if self.is_extended() { KEYEVENTF_EXTENDEDKEY } else { 0 }
+	}
+}
+
+/// Result of [`VirtualKey::to_unicode`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ToUnicodeResult {
+	/// The key does not translate to a character in the current keyboard state.
+	NoTranslation,
+	/// The key is a dead key, the spacing version of the accent is given.
+	DeadKey(char),
+	/// The key translates to one or more characters.
+	///
+	/// Multiple characters are produced by ligatures or a pending dead key which could not be combined with the key.
+	Chars(KeyChars),
+}
+
+/// Up to four characters produced by a single key.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct KeyChars {
+	len: u8,
+	chars: [char; 4],
+}
+impl KeyChars {
+	fn push(&mut self, chr: char) {
+		if (self.len as usize) < self.chars.len() {
+			self.chars[self.len as usize] = chr;
+			self.len += 1;
+		}
+	}
+	/// Returns the characters as a slice.
+	pub fn as_slice(&self) -> &[char] {
+		&self.chars[..self.len as usize]
+	}
+}
+impl ops::Deref for KeyChars {
+	type Target = [char];
+	fn deref(&self) -> &[char] {
+		self.as_slice()
+	}
+}
+impl fmt::Display for KeyChars {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use fmt::Write;
+		for &chr in self.as_slice() {
+			f.write_char(chr)?;
+		}
+		Ok(())
+	}
+}
+impl fmt::Debug for KeyChars {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(&self.to_string(), f)
+	}
+}
+
+/// Keyboard layout handle.
+///
+/// See [Keyboard Input](https://msdn.microsoft.com/en-us/library/windows/desktop/ms645530.aspx) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct KeyboardLayout(HKL);
+impl_inner!(KeyboardLayout: HKL);
+impl KeyboardLayout {
+	/// Gets the active keyboard layout of the current thread.
+	///
+	/// See [GetKeyboardLayout function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646296.aspx) for more information.
+	#[inline]
+	pub fn current() -> KeyboardLayout {
+		unsafe { KeyboardLayout(GetKeyboardLayout(0)) }
+	}
+	/// Gets the active keyboard layout of the given thread.
+	///
+	/// Use the thread of the foreground window to translate scan codes the same way the foreground application does.
+	#[inline]
+	pub fn of_thread(thread_id: ThreadId) -> KeyboardLayout {
+		unsafe { KeyboardLayout(GetKeyboardLayout(thread_id.into_inner())) }
+	}
+	/// Gets the language identifier of the keyboard layout.
+	#[inline]
+	pub fn language_id(self) -> u16 {
+		self.0 as usize as u16
+	}
+}
+
+/// Modifier key state with the left and right keys tracked separately.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct Modifiers(u8);
+impl_inner!(Modifiers: safe u8);
+unsafe impl Pod for Modifiers {}
+impl Modifiers {
+	pub const NONE: Modifiers = Modifiers(0);
+	pub const LSHIFT: Modifiers = Modifiers(0x01);
+	pub const RSHIFT: Modifiers = Modifiers(0x02);
+	pub const LCTRL: Modifiers = Modifiers(0x04);
+	pub const RCTRL: Modifiers = Modifiers(0x08);
+	pub const LALT: Modifiers = Modifiers(0x10);
+	pub const RALT: Modifiers = Modifiers(0x20);
+	pub const LWIN: Modifiers = Modifiers(0x40);
+	pub const RWIN: Modifiers = Modifiers(0x80);
+	/// Both shift keys, the generic modifier in [`matches`](Modifiers::matches) and [`KeyChord`].
+	pub const SHIFT: Modifiers = Modifiers(0x03);
+	/// Both control keys, the generic modifier in [`matches`](Modifiers::matches) and [`KeyChord`].
+	pub const CTRL: Modifiers = Modifiers(0x0c);
+	/// Both alt keys, the generic modifier in [`matches`](Modifiers::matches) and [`KeyChord`].
+	pub const ALT: Modifiers = Modifiers(0x30);
+	/// Both windows keys, the generic modifier in [`matches`](Modifiers::matches) and [`KeyChord`].
+	pub const WIN: Modifiers = Modifiers(0xc0);
+}
+impl Modifiers {
+	/// Returns the modifier for a virtual key or `NONE` if it is not a modifier key.
+	///
+	/// The generic `SHIFT`, `CONTROL` and `MENU` virtual keys map to their left variants.
+	pub const fn from_vk(vk: VirtualKey) -> Modifiers {
+		match vk.0 {
+			0x10 | 0xa0 => Modifiers::LSHIFT,
+			0xa1 => Modifiers::RSHIFT,
+			0x11 | 0xa2 => Modifiers::LCTRL,
+			0xa3 => Modifiers::RCTRL,
+			0x12 | 0xa4 => Modifiers::LALT,
+			0xa5 => Modifiers::RALT,
+			0x5b => Modifiers::LWIN,
+			0x5c => Modifiers::RWIN,
+			_ => Modifiers::NONE,
+		}
+	}
+	/// Returns if no modifiers are held.
+	pub const fn is_empty(self) -> bool {
+		self.0 == 0
+	}
+	/// Returns if all the given modifiers are held.
+	///
+	/// The generic modifiers require both keys, eg. `contains(Modifiers::SHIFT)` is only true while both shift keys are held.
+	/// Use [`intersects`](Modifiers::intersects) or [`shift`](Modifiers::shift) to test for either key.
+	pub const fn contains(self, modifiers: Modifiers) -> bool {
+		self.0 & modifiers.0 == modifiers.0
+	}
+	/// Returns if any of the given modifiers are held.
+	pub const fn intersects(self, modifiers: Modifiers) -> bool {
+		self.0 & modifiers.0 != 0
+	}
+	/// Returns if either shift key is held.
+	pub const fn shift(self) -> bool {
+		self.intersects(Modifiers::SHIFT)
+	}
+	/// Returns if either control key is held.
+	pub const fn ctrl(self) -> bool {
+		self.intersects(Modifiers::CTRL)
+	}
+	/// Returns if either alt key is held.
+	pub const fn alt(self) -> bool {
+		self.intersects(Modifiers::ALT)
+	}
+	/// Returns if either windows key is held.
+	pub const fn win(self) -> bool {
+		self.intersects(Modifiers::WIN)
+	}
+	/// Returns if the held modifiers match the given modifiers exactly.
+	///
+	/// The generic `SHIFT`, `CTRL`, `ALT` and `WIN` match either key, a left or right variant only matches that key.
+	/// Modifiers which are not given must not be held, eg. `CTRL | SHIFT` does not match while alt is held.
+	pub const fn matches(self, modifiers: Modifiers) -> bool {
+		const GROUPS: [u8; 4] = [Modifiers::SHIFT.0, Modifiers::CTRL.0, Modifiers::ALT.0, Modifiers::WIN.0];
+		let mut i = 0;
+		while i < GROUPS.len() {
+			let held = self.0 & GROUPS[i];
+			let wanted = modifiers.0 & GROUPS[i];
+			let ok = if wanted == 0 { held == 0 } else if wanted == GROUPS[i] { held != 0 } else { held & wanted == wanted };
+			if !ok {
+				return false;
+			}
+			i += 1;
+		}
+		true
+	}
+}
+impl ops::BitOr for Modifiers {
+	type Output = Modifiers;
+	fn bitor(self, rhs: Modifiers) -> Modifiers {
+		Modifiers(self.0 | rhs.0)
+	}
+}
+impl ops::BitAnd for Modifiers {
+	type Output = Modifiers;
+	fn bitand(self, rhs: Modifiers) -> Modifiers {
+		Modifiers(self.0 & rhs.0)
+	}
+}
+impl ops::Not for Modifiers {
+	type Output = Modifiers;
+	fn not(self) -> Modifiers {
+		Modifiers(!self.0)
+	}
+}
+impl fmt::Debug for Modifiers {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		const NAMES: [&str; 8] = ["LSHIFT", "RSHIFT", "LCTRL", "RCTRL", "LALT", "RALT", "LWIN", "RWIN"];
+		f.write_str("Modifiers(")?;
+		let mut first = true;
+		for (i, name) in NAMES.iter().enumerate() {
+			if self.0 & (1 << i) != 0 {
+				if !first {
+					f.write_str(" | ")?;
+				}
+				f.write_str(name)?;
+				first = false;
+			}
+		}
+		f.write_str(")")
+	}
+}
+
+/// Formats the held modifiers like `"Ctrl+Shift"`, left and right keys are not distinguished.
+impl fmt::Display for Modifiers {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let names = [(self.ctrl(), "Ctrl"), (self.shift(), "Shift"), (self.alt(), "Alt"), (self.win(), "Win")];
+		let mut first = true;
+		for &(held, name) in names.iter() {
+			if held {
+				if !first {
+					f.write_str("+")?;
+				}
+				f.write_str(name)?;
+				first = false;
+			}
+		}
+		Ok(())
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Key combination of modifiers and a single key, eg. `"Ctrl+Shift+F1"`.
+///
+/// Parsed case-insensitively from the key names joined by `+`, the modifiers are given by their virtual key names (`"CTRL"`, `"LSHIFT"`, `"RALT"`, ...) or as `"Control"` and `"Win"`.
+/// The generic modifiers accept either key, the left and right variants only that key.
+/// The last key is parsed by the [`FromStr`](std::str::FromStr) implementation of `VirtualKey`.
+///
+/// ```
+/// use external::vk::{KeyChord, Modifiers, VirtualKey};
+///
+/// let chord: KeyChord = "ctrl+shift+f1".parse().unwrap();
+/// assert_eq!(chord, KeyChord::new(Modifiers::CTRL | Modifiers::SHIFT, VirtualKey::F1));
+/// assert_eq!(chord.to_string(), "Ctrl+Shift+F1");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct KeyChord {
+	modifiers: Modifiers,
+	key: VirtualKey,
+	was_pressed: bool,
+}
+impl KeyChord {
+	/// Creates a key chord from its modifiers and key.
+	pub const fn new(modifiers: Modifiers, key: VirtualKey) -> KeyChord {
+		KeyChord { modifiers, key, was_pressed: false }
+	}
+	/// Returns the modifiers.
+	pub const fn modifiers(&self) -> Modifiers {
+		self.modifiers
+	}
+	/// Returns the key.
+	pub const fn key(&self) -> VirtualKey {
+		self.key
+	}
+	/// Returns if the key and all the modifiers are held down.
+	///
+	/// Polls [`async_state`](VirtualKey::async_state), additional modifiers being held do not matter.
+	pub fn is_pressed(&self) -> bool {
+		const GROUPS: [(Modifiers, Modifiers, &[VirtualKey], VirtualKey, VirtualKey); 4] = [
+			(Modifiers::SHIFT, Modifiers::LSHIFT, &[VirtualKey::SHIFT], VirtualKey::LSHIFT, VirtualKey::RSHIFT),
+			(Modifiers::CTRL, Modifiers::LCTRL, &[VirtualKey::CTRL], VirtualKey::LCTRL, VirtualKey::RCTRL),
+			(Modifiers::ALT, Modifiers::LALT, &[VirtualKey::ALT], VirtualKey::LALT, VirtualKey::RALT),
+			(Modifiers::WIN, Modifiers::LWIN, &[VirtualKey::LWIN, VirtualKey::RWIN], VirtualKey::LWIN, VirtualKey::RWIN),
+		];
+		for &(group, left_mod, generic, left, right) in GROUPS.iter() {
+			let wanted = self.modifiers & group;
+			let held = if wanted.is_empty() { true }
+				else if wanted == group { generic.iter().any(|vk| vk.async_state()) }
+				else if wanted.intersects(left_mod) { left.async_state() }
+				else { right.async_state() };
+			if !held {
+				return false;
+			}
+		}
+		self.key.async_state()
+	}
+	/// Returns if the chord is pressed now but was not the previous time this method was called.
+	///
+	/// Call this every tick to act once when the chord is pressed.
+	pub fn just_pressed(&mut self) -> bool {
+		let pressed = self.is_pressed();
+		let just_pressed = pressed && !self.was_pressed;
+		self.was_pressed = pressed;
+		just_pressed
+	}
+}
+impl PartialEq for KeyChord {
+	fn eq(&self, other: &KeyChord) -> bool {
+		self.modifiers == other.modifiers && self.key == other.key
+	}
+}
+impl Eq for KeyChord {}
+/// Formats the chord like `"Ctrl+LShift+F1"`, the output can be parsed back.
+impl fmt::Display for KeyChord {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		const NAMES: [(Modifiers, Modifiers, &str); 4] = [
+			(Modifiers::CTRL, Modifiers::LCTRL, "Ctrl"),
+			(Modifiers::SHIFT, Modifiers::LSHIFT, "Shift"),
+			(Modifiers::ALT, Modifiers::LALT, "Alt"),
+			(Modifiers::WIN, Modifiers::LWIN, "Win"),
+		];
+		for &(group, left, name) in NAMES.iter() {
+			let wanted = self.modifiers & group;
+			if wanted.is_empty() {
+				continue;
+			}
+			let side = if wanted == group { "" } else if wanted.intersects(left) { "L" } else { "R" };
+			write!(f, "{}{}+", side, name)?;
+		}
+		match self.key.to_str() {
+			Some(name) => f.write_str(name),
+			None => write!(f, "{:#04x}", self.key.0),
+		}
+	}
+}
+
+/// Error returned when parsing a [`KeyChord`] fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeyChordFromStrError {
+	/// The chord or one of its keys is empty, eg. `"ctrl++x"`.
+	Empty,
+	/// The key name is not known.
+	UnknownKey(String),
+	/// The chord has more than one key which is not a modifier.
+	MultipleKeys(VirtualKey, VirtualKey),
+	/// The chord only has modifiers.
+	MissingKey,
+}
+impl fmt::Display for KeyChordFromStrError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			KeyChordFromStrError::Empty => f.write_str("empty key name in key chord"),
+			KeyChordFromStrError::UnknownKey(name) => write!(f, "unknown virtual key name {:?} in key chord", name),
+			KeyChordFromStrError::MultipleKeys(first, second) => write!(f, "key chord has more than one non-modifier key: {} and {}", first, second),
+			KeyChordFromStrError::MissingKey => f.write_str("key chord has no key besides its modifiers"),
+		}
+	}
+}
+impl std::error::Error for KeyChordFromStrError {}
+
+impl std::str::FromStr for KeyChord {
+	type Err = KeyChordFromStrError;
+	fn from_str(s: &str) -> Result<KeyChord, KeyChordFromStrError> {
+		let mut modifiers = Modifiers::NONE;
+		let mut key = None;
+		for part in s.split('+') {
+			let part = part.trim();
+			if part.is_empty() {
+				return Err(KeyChordFromStrError::Empty);
+			}
+			let (vk, modifier) = if part.eq_ignore_ascii_case("control") { (VirtualKey::CTRL, Modifiers::CTRL) }
+				else if part.eq_ignore_ascii_case("win") { (VirtualKey::LWIN, Modifiers::WIN) }
+				else {
+					let vk: VirtualKey = part.parse().map_err(|_| KeyChordFromStrError::UnknownKey(String::from(part)))?;
+					let modifier = match vk {
+						VirtualKey::SHIFT => Modifiers::SHIFT,
+						VirtualKey::CTRL => Modifiers::CTRL,
+						VirtualKey::ALT => Modifiers::ALT,
+						vk => Modifiers::from_vk(vk),
+					};
+					(vk, modifier)
+				};
+			if modifier != Modifiers::NONE {
+				modifiers = modifiers | modifier;
+			}
+			else if let Some(first) = key {
+				return Err(KeyChordFromStrError::MultipleKeys(first, vk));
+			}
+			else {
+				key = Some(vk);
+			}
+		}
+		match key {
+			Some(key) => Ok(KeyChord::new(modifiers, key)),
+			None => Err(KeyChordFromStrError::MissingKey),
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+// Cached SM_SWAPBUTTON: 0 unknown, 1 not swapped, 2 swapped
+static SWAP_BUTTONS: AtomicU8 = AtomicU8::new(0);
+
+/// Returns if the user swapped the left and right mouse buttons.
+///
+/// The setting is queried once and cached, see [`refresh_swap_buttons`].
+pub fn swap_buttons() -> bool {
+	match SWAP_BUTTONS.load(Ordering::Relaxed) {
+		0 => refresh_swap_buttons(),
+		value => value == 2,
+	}
+}
+
+/// Queries the mouse button swap setting again, it can be changed by the user at any time.
+///
+/// See [GetSystemMetrics function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms724385.aspx) for more information.
+pub fn refresh_swap_buttons() -> bool {
+	let swapped = unsafe { GetSystemMetrics(SM_SWAPBUTTON) != 0 };
+	SWAP_BUTTONS.store(if swapped { 2 } else { 1 }, Ordering::Relaxed);
+	swapped
+}
+
+/// Returns the virtual key of the primary mouse button.
+///
+/// The mouse button virtual keys refer to the physical buttons, this is `RBUTTON` when the buttons are swapped.
+/// Use this to check if the primary button is down with [`async_state`](VirtualKey::async_state).
+pub fn primary_button() -> VirtualKey {
+	if swap_buttons() { VirtualKey::RBUTTON } else { VirtualKey::LBUTTON }
+}
+
+/// Returns the virtual key of the secondary mouse button.
+///
+/// This is `LBUTTON` when the buttons are swapped, see [`primary_button`].
+pub fn secondary_button() -> VirtualKey {
+	if swap_buttons() { VirtualKey::LBUTTON } else { VirtualKey::RBUTTON }
+}
+
+/// Snapshot of the async key state of all 256 virtual keys, indexed by virtual key code.
+///
+/// Calls `GetAsyncKeyState` for every key, this reflects the physical state of the keys at the time of the call.
+pub fn async_keyboard_state() -> [bool; 256] {
+	let mut state = [false; 256];
+	for (vk, down) in state.iter_mut().enumerate() {
+		*down = unsafe { GetAsyncKeyState(vk as i32) as u16 & 0x8000 != 0 };
+	}
+	state
+}
+
+/// Key state of a virtual key.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct KeyState(u8);
+impl_inner!(KeyState: safe u8);
+unsafe impl Pod for KeyState {}
+impl KeyState {
+	/// Returns if the key is down.
+	#[inline]
+	pub const fn is_down(self) -> bool {
+		self.0 & 0x80 != 0
+	}
+	/// Returns if the key is toggled, eg. caps lock is on.
+	#[inline]
+	pub const fn is_toggled(self) -> bool {
+		self.0 & 0x01 != 0
 	}
 }
+impl fmt::Debug for KeyState {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("KeyState")
+			.field("down", &self.is_down())
+			.field("toggled", &self.is_toggled())
+			.finish()
+	}
+}
+
+/// Snapshot of the key state of all 256 virtual keys, indexed by virtual key.
+///
+/// Being Pod, two snapshots can be compared byte-wise with `dataview::bytes`.
+#[derive(Copy, Clone, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct KeyboardState([KeyState; 256]);
+unsafe impl Pod for KeyboardState {}
+impl KeyboardState {
+	/// Returns the key state of the virtual key.
+	#[inline]
+	pub fn get(&self, vk: VirtualKey) -> KeyState {
+		self.0[vk.0 as usize]
+	}
+	/// Sets the key state of the virtual key.
+	#[inline]
+	pub fn set(&mut self, vk: VirtualKey, state: KeyState) {
+		self.0[vk.0 as usize] = state;
+	}
+	/// Iterator over the keys which are down.
+	pub fn down_keys(&self) -> impl '_ + Clone + Iterator<Item = VirtualKey> {
+		self.0.iter().enumerate().filter(|(_, state)| state.is_down()).map(|(vk, _)| VirtualKey(vk as u8))
+	}
+}
+impl Default for KeyboardState {
+	fn default() -> KeyboardState {
+		KeyboardState([KeyState(0); 256])
+	}
+}
+impl ops::Index<VirtualKey> for KeyboardState {
+	type Output = KeyState;
+	#[inline]
+	fn index(&self, vk: VirtualKey) -> &KeyState {
+		&self.0[vk.0 as usize]
+	}
+}
+impl ops::IndexMut<VirtualKey> for KeyboardState {
+	#[inline]
+	fn index_mut(&mut self, vk: VirtualKey) -> &mut KeyState {
+		&mut self.0[vk.0 as usize]
+	}
+}
+impl fmt::Debug for KeyboardState {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_set().entries(self.down_keys()).finish()
+	}
+}
+
+/// Snapshot of the key state of all 256 virtual keys.
+///
+/// Unlike [`async_keyboard_state`] this reflects the state as seen by the calling thread's message queue:
+/// it only changes as the thread removes keyboard messages from its queue and lags behind the physical keyboard.
+/// The snapshot is consistent, all keys are read at once and include the toggle state.
+///
+/// See [GetKeyboardState function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646299.aspx) for more information.
+pub fn keyboard_state() -> crate::Result<KeyboardState> {
+	let mut state = KeyboardState::default();
+	if unsafe { GetKeyboardState(state.0.as_mut_ptr() as *mut u8) } == FALSE {
+		return Err(crate::error::ErrorCode::last());
+	}
+	Ok(state)
+}
+
+/// Edge detection over polled key state.
+///
+/// Watches a set of keys and snapshots their state every time [`update`](KeyTracker::update) is called,
+/// the queries compare the last two snapshots. Keys which are not watched are never pressed or held.
+///
+/// Every update reads all keys with a single `GetKeyboardState` call.
+///
+/// ```no_run
+/// use external::vk::{KeyTracker, VirtualKey};
+/// use external::wndclass::sleep;
+///
+/// let mut keys = KeyTracker::new(&[VirtualKey::XBUTTON1, VirtualKey::F8]);
+/// let mut enabled = true;
+/// loop {
+///     keys.update().unwrap();
+///     if keys.pressed(VirtualKey::F8) {
+///         enabled = !enabled;
+///     }
+///     if enabled && keys.held(VirtualKey::XBUTTON1) {
+///         if let Some(held) = keys.held_duration(VirtualKey::XBUTTON1) {
+///             println!("trigger held for {:?}", held);
+///         }
+///     }
+///     if keys.released(VirtualKey::XBUTTON1) {
+///         println!("trigger released");
+///     }
+///     sleep(10);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct KeyTracker {
+	keys: Vec<TrackedKey>,
+	time: Option<Instant>,
+}
+#[derive(Copy, Clone, Debug)]
+struct TrackedKey {
+	vk: VirtualKey,
+	previous: bool,
+	current: bool,
+	since: Option<Instant>,
+}
+impl KeyTracker {
+	/// Creates a tracker watching the keys.
+	pub fn new(keys: &[VirtualKey]) -> KeyTracker {
+		let mut tracker = KeyTracker::default();
+		for &vk in keys {
+			tracker.watch(vk);
+		}
+		tracker
+	}
+	/// Starts watching the key, it is not held until the next update.
+	pub fn watch(&mut self, vk: VirtualKey) {
+		if self.find(vk).is_none() {
+			self.keys.push(TrackedKey { vk, previous: false, current: false, since: None });
+		}
+	}
+	/// Stops watching the key.
+	pub fn unwatch(&mut self, vk: VirtualKey) {
+		self.keys.retain(|key| key.vk != vk);
+	}
+	/// Snapshots the state of the watched keys.
+	pub fn update(&mut self) -> crate::Result<()> {
+		// Synchronizes the thread's key state with the keyboard when the thread does not receive keyboard messages
+		VirtualKey::NONE.state();
+		let state = keyboard_state()?;
+		self.apply(&state, Instant::now());
+		Ok(())
+	}
+	fn apply(&mut self, state: &KeyboardState, now: Instant) {
+		for key in &mut self.keys {
+			key.previous = key.current;
+			key.current = state[key.vk].is_down();
+			if !key.current {
+				key.since = None;
+			}
+			else if !key.previous {
+				key.since = Some(now);
+			}
+		}
+		self.time = Some(now);
+	}
+	fn find(&self, vk: VirtualKey) -> Option<&TrackedKey> {
+		self.keys.iter().find(|key| key.vk == vk)
+	}
+	/// Returns if the key went down since the previous update.
+	pub fn pressed(&self, vk: VirtualKey) -> bool {
+		self.find(vk).map(|key| key.current && !key.previous).unwrap_or(false)
+	}
+	/// Returns if the key went up since the previous update.
+	pub fn released(&self, vk: VirtualKey) -> bool {
+		self.find(vk).map(|key| !key.current && key.previous).unwrap_or(false)
+	}
+	/// Returns if the key is down as of the last update.
+	pub fn held(&self, vk: VirtualKey) -> bool {
+		self.find(vk).map(|key| key.current).unwrap_or(false)
+	}
+	/// Returns how long the key has been down as of the last update.
+	///
+	/// The duration is measured between the updates, it is zero for the update where the key was pressed.
+	pub fn held_duration(&self, vk: VirtualKey) -> Option<Duration> {
+		let since = self.find(vk)?.since?;
+		Some(self.time? - since)
+	}
+}
+
+/// Gets the active keyboard layout of the current thread.
+#[inline]
+pub fn current_layout() -> KeyboardLayout {
+	KeyboardLayout::current()
+}
 impl VirtualKey {
 	/// Gets the name of a virtual key if there is one.
 	#[inline]
@@ -201,6 +1103,12 @@ impl VirtualKey {
 			VirtualKey::PRINT_SCREEN => "PRINT_SCREEN",
 			VirtualKey::INSERT => "INSERT",
 			VirtualKey::DELETE => "DELETE",
+			VirtualKey::HELP => "HELP",
+
+			VirtualKey::LWIN => "LWIN",
+			VirtualKey::RWIN => "RWIN",
+			VirtualKey::APPS => "APPS",
+			VirtualKey::SLEEP => "SLEEP",
 
 			VirtualKey(b'0') => "0",
 			VirtualKey(b'1') => "1",
@@ -270,6 +1178,18 @@ impl VirtualKey {
 			VirtualKey::F10 => "F10",
 			VirtualKey::F11 => "F11",
 			VirtualKey::F12 => "F12",
+			VirtualKey::F13 => "F13",
+			VirtualKey::F14 => "F14",
+			VirtualKey::F15 => "F15",
+			VirtualKey::F16 => "F16",
+			VirtualKey::F17 => "F17",
+			VirtualKey::F18 => "F18",
+			VirtualKey::F19 => "F19",
+			VirtualKey::F20 => "F20",
+			VirtualKey::F21 => "F21",
+			VirtualKey::F22 => "F22",
+			VirtualKey::F23 => "F23",
+			VirtualKey::F24 => "F24",
 
 			VirtualKey::NUM_LOCK => "NUM_LOCK",
 			VirtualKey::SCROLL_LOCK => "SCROLL_LOCK",
@@ -281,6 +1201,39 @@ impl VirtualKey {
 			VirtualKey::LALT => "LALT",
 			VirtualKey::RALT => "RALT",
 
+			VirtualKey::BROWSER_BACK => "BROWSER_BACK",
+			VirtualKey::BROWSER_FORWARD => "BROWSER_FORWARD",
+			VirtualKey::BROWSER_REFRESH => "BROWSER_REFRESH",
+			VirtualKey::BROWSER_STOP => "BROWSER_STOP",
+			VirtualKey::BROWSER_SEARCH => "BROWSER_SEARCH",
+			VirtualKey::BROWSER_FAVORITES => "BROWSER_FAVORITES",
+			VirtualKey::BROWSER_HOME => "BROWSER_HOME",
+			VirtualKey::VOLUME_MUTE => "VOLUME_MUTE",
+			VirtualKey::VOLUME_DOWN => "VOLUME_DOWN",
+			VirtualKey::VOLUME_UP => "VOLUME_UP",
+			VirtualKey::MEDIA_NEXT_TRACK => "MEDIA_NEXT_TRACK",
+			VirtualKey::MEDIA_PREV_TRACK => "MEDIA_PREV_TRACK",
+			VirtualKey::MEDIA_STOP => "MEDIA_STOP",
+			VirtualKey::MEDIA_PLAY_PAUSE => "MEDIA_PLAY_PAUSE",
+			VirtualKey::LAUNCH_MAIL => "LAUNCH_MAIL",
+			VirtualKey::LAUNCH_MEDIA_SELECT => "LAUNCH_MEDIA_SELECT",
+			VirtualKey::LAUNCH_APP1 => "LAUNCH_APP1",
+			VirtualKey::LAUNCH_APP2 => "LAUNCH_APP2",
+
+			VirtualKey::OEM_1 => "OEM_1",
+			VirtualKey::OEM_PLUS => "OEM_PLUS",
+			VirtualKey::OEM_COMMA => "OEM_COMMA",
+			VirtualKey::OEM_MINUS => "OEM_MINUS",
+			VirtualKey::OEM_PERIOD => "OEM_PERIOD",
+			VirtualKey::OEM_2 => "OEM_2",
+			VirtualKey::OEM_3 => "OEM_3",
+			VirtualKey::OEM_4 => "OEM_4",
+			VirtualKey::OEM_5 => "OEM_5",
+			VirtualKey::OEM_6 => "OEM_6",
+			VirtualKey::OEM_7 => "OEM_7",
+			VirtualKey::OEM_8 => "OEM_8",
+			VirtualKey::OEM_102 => "OEM_102",
+
 			_ => return None,
 		})
 	}
@@ -308,6 +1261,9 @@ impl std::error::Error for VirtualKeyFromStrError {
 	}
 }
 
+/// Parses the name of the virtual key case-insensitively, see [`to_str`](VirtualKey::to_str).
+///
+/// Virtual keys without a name are parsed from their hexadecimal value, eg. `"0x07"`.
 impl std::str::FromStr for VirtualKey {
 	type Err = VirtualKeyFromStrError;
 	fn from_str(s: &str) -> Result<VirtualKey, VirtualKeyFromStrError> {
@@ -319,9 +1275,51 @@ impl std::str::FromStr for VirtualKey {
 				}
 			}
 		}
+		if s.len() > 2 && (s.starts_with("0x") || s.starts_with("0X")) {
+			if let Ok(vk) = u8::from_str_radix(&s[2..], 16) {
+				return Ok(VirtualKey(vk));
+			}
+		}
 		Err(VirtualKeyFromStrError {})
 	}
 }
+
+/// Serializes the name of the virtual key, or its numeric value if it has no name.
+#[cfg(feature = "serde")]
+impl serde::Serialize for VirtualKey {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self.to_str() {
+			Some(vk_str) => serializer.serialize_str(vk_str),
+			None => serializer.serialize_u8(self.0),
+		}
+	}
+}
+
+/// Deserializes the virtual key from its name as parsed by `FromStr` or from its numeric value.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VirtualKey {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<VirtualKey, D::Error> {
+		struct Visitor;
+		impl<'de> serde::de::Visitor<'de> for Visitor {
+			type Value = VirtualKey;
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("a virtual key name or code")
+			}
+			fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<VirtualKey, E> {
+				v.parse().map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+			}
+			fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<VirtualKey, E> {
+				if v <= 0xff { Ok(VirtualKey(v as u8)) }
+				else { Err(E::invalid_value(serde::de::Unexpected::Unsigned(v), &self)) }
+			}
+			fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<VirtualKey, E> {
+				if (0..=0xff).contains(&v) { Ok(VirtualKey(v as u8)) }
+				else { Err(E::invalid_value(serde::de::Unexpected::Signed(v), &self)) }
+			}
+		}
+		deserializer.deserialize_any(Visitor)
+	}
+}
 fn stricmp(a: &str, b: &str) -> bool {
 	if a.len() != b.len() {
 		return false;
@@ -352,9 +1350,31 @@ fn test_key_types() {
 fn test_vk_str() {
 	assert_eq!("xbutton1".parse(), Ok(VirtualKey::XBUTTON1));
 	assert_eq!("lalt".parse(), Ok(VirtualKey::LALT));
+	assert_eq!("oem_comma".parse(), Ok(VirtualKey::OEM_COMMA));
+	assert_eq!("F13".parse(), Ok(VirtualKey::F13));
+	assert_eq!("0x07".parse(), Ok(VirtualKey(0x07)));
+	for i in 0..=255 {
+		let vk = VirtualKey(i);
+		if let Some(vk_str) = vk.to_str() {
+			assert_eq!(vk_str.parse(), Ok(vk), "{}", vk_str);
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_vk_serde() {
+	use serde::de::{Deserialize, IntoDeserializer, value::Error};
+	let de = |s: &str| VirtualKey::deserialize(IntoDeserializer::<Error>::into_deserializer(s));
+	assert_eq!(de("lAlt"), Ok(VirtualKey::LALT));
+	assert_eq!(de("0x07"), Ok(VirtualKey(0x07)));
+	assert!(de("not a key").is_err());
+	assert_eq!(VirtualKey::deserialize(IntoDeserializer::<Error>::into_deserializer(0x41u8)), Ok(VirtualKey(b'A')));
+	assert!(VirtualKey::deserialize(IntoDeserializer::<Error>::into_deserializer(256u16)).is_err());
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_vk_scan_codes() {
 	for scan_code in 0..256 {
 		let vk = VirtualKey::from_scan_code(scan_code as u8);
@@ -362,6 +1382,38 @@ fn test_vk_scan_codes() {
 	}
 }
 
+#[test]
+#[allow(deprecated)]
+fn test_vk_scan_codes_in_layout() {
+	let layout = current_layout();
+	assert_eq!(layout, KeyboardLayout::of_thread(ThreadId::current()));
+	for scan_code in 0..256 {
+		let vk = VirtualKey::from_scan_code(scan_code as u8);
+		assert_eq!(vk, VirtualKey::from_scan_code_in(scan_code as u8, layout));
+		assert_eq!(vk.to_scan_code(), vk.to_scan_code_in(layout));
+	}
+}
+
+#[test]
+fn test_to_unicode() {
+	let state = KeyboardState::default();
+	match VirtualKey::SPACE.to_unicode_peek(VirtualKey::SPACE.scan_code().code(), &state, None) {
+		ToUnicodeResult::Chars(chars) => assert_eq!(chars.to_string(), " "),
+		result => panic!("unexpected {:?}", result),
+	}
+	assert_eq!(VirtualKey::NONE.to_unicode(0, &state, None), ToUnicodeResult::NoTranslation);
+}
+
+#[test]
+fn test_keyboard_state() {
+	let state = async_keyboard_state();
+	assert!(!state[VirtualKey::NONE.into_inner() as usize]);
+	let state = keyboard_state().unwrap();
+	assert!(!state[VirtualKey::NONE].is_down());
+	assert_eq!(state.down_keys().any(|vk| vk == VirtualKey::CAPS_LOCK), VirtualKey::CAPS_LOCK.state().is_down());
+	assert_eq!(state[VirtualKey::CAPS_LOCK].is_toggled(), VirtualKey::CAPS_LOCK.state().is_toggled());
+}
+
 #[test]
 #[ignore]
 fn print_table() {
@@ -383,3 +1435,120 @@ fn print_table() {
 	}
 	println!("];");
 }
+
+#[test]
+fn test_modifiers() {
+	let mods = Modifiers::from_vk(VirtualKey::LSHIFT) | Modifiers::from_vk(VirtualKey::RCTRL);
+	assert!(mods.shift() && mods.ctrl() && !mods.alt() && !mods.win());
+	assert!(mods.contains(Modifiers::RCTRL) && !mods.contains(Modifiers::CTRL));
+	assert_eq!(mods & !Modifiers::SHIFT, Modifiers::RCTRL);
+	assert_eq!(Modifiers::from_vk(VirtualKey::SPACE), Modifiers::NONE);
+	assert_eq!(format!("{:?}", mods), "Modifiers(LSHIFT | RCTRL)");
+	assert_eq!(format!("{}", mods), "Ctrl+Shift");
+	assert!(mods.matches(Modifiers::CTRL | Modifiers::SHIFT));
+	assert!(mods.matches(Modifiers::RCTRL | Modifiers::LSHIFT));
+	assert!(!mods.matches(Modifiers::LCTRL | Modifiers::SHIFT));
+	assert!(!mods.matches(Modifiers::CTRL));
+	assert!(!(mods | Modifiers::LALT).matches(Modifiers::CTRL | Modifiers::SHIFT));
+}
+
+#[test]
+fn test_key_chord() {
+	let chord: KeyChord = "ctrl+Shift+x".parse().unwrap();
+	assert_eq!(chord, KeyChord::new(Modifiers::CTRL | Modifiers::SHIFT, VirtualKey(b'X')));
+	assert_eq!(chord.to_string(), "Ctrl+Shift+X");
+	let chord: KeyChord = "RAlt + Win + Control + F1".parse().unwrap();
+	assert_eq!(chord, KeyChord::new(Modifiers::RALT | Modifiers::WIN | Modifiers::CTRL, VirtualKey::F1));
+	assert_eq!(chord.to_string(), "Ctrl+RAlt+Win+F1");
+	assert_eq!(chord.to_string().parse(), Ok(chord));
+	assert_eq!("0x07".parse::<KeyChord>().unwrap().to_string(), "0x07");
+
+	assert_eq!("ctrl+a+b".parse::<KeyChord>(), Err(KeyChordFromStrError::MultipleKeys(VirtualKey(b'A'), VirtualKey(b'B'))));
+	assert_eq!("ctrl+shift".parse::<KeyChord>(), Err(KeyChordFromStrError::MissingKey));
+	assert_eq!("ctrl++x".parse::<KeyChord>(), Err(KeyChordFromStrError::Empty));
+	assert_eq!("ctrl+foo".parse::<KeyChord>(), Err(KeyChordFromStrError::UnknownKey(String::from("foo"))));
+
+	let mut chord = KeyChord::new(Modifiers::NONE, VirtualKey::NONE);
+	assert!(!chord.just_pressed());
+}
+
+#[test]
+fn test_key_tracker() {
+	let mut keys = KeyTracker::new(&[VirtualKey::F1, VirtualKey::F2]);
+	let mut state = KeyboardState::default();
+	let start = Instant::now();
+
+	state.set(VirtualKey::F1, KeyState(0x80));
+	state.set(VirtualKey::F3, KeyState(0x80));
+	keys.apply(&state, start);
+	assert!(keys.pressed(VirtualKey::F1) && keys.held(VirtualKey::F1));
+	assert!(!keys.held(VirtualKey::F3), "unwatched keys are never held");
+	assert_eq!(keys.held_duration(VirtualKey::F1), Some(Duration::from_millis(0)));
+
+	keys.apply(&state, start + Duration::from_millis(50));
+	assert!(!keys.pressed(VirtualKey::F1) && keys.held(VirtualKey::F1));
+	assert_eq!(keys.held_duration(VirtualKey::F1), Some(Duration::from_millis(50)));
+
+	state.set(VirtualKey::F1, KeyState(0));
+	keys.apply(&state, start + Duration::from_millis(100));
+	assert!(keys.released(VirtualKey::F1) && !keys.held(VirtualKey::F1));
+	assert_eq!(keys.held_duration(VirtualKey::F1), None);
+
+	keys.unwatch(VirtualKey::F2);
+	keys.update().unwrap();
+	assert!(!keys.released(VirtualKey::F1));
+}
+
+#[test]
+fn test_primary_button() {
+	let swapped = refresh_swap_buttons();
+	assert_eq!(swap_buttons(), swapped);
+	assert_ne!(primary_button(), secondary_button());
+	assert_eq!(primary_button() == VirtualKey::RBUTTON, swapped);
+}
+
+#[test]
+fn test_display_name() {
+	// The names are localized, only check the extended keys are told apart from the numpad keys
+	let pairs = [
+		(VirtualKey::INSERT, VirtualKey::NUMPAD0),
+		(VirtualKey::DELETE, VirtualKey::DECIMAL),
+		(VirtualKey::HOME, VirtualKey::NUMPAD7),
+		(VirtualKey::END, VirtualKey::NUMPAD1),
+		(VirtualKey::PAGE_UP, VirtualKey::NUMPAD9),
+		(VirtualKey::PAGE_DOWN, VirtualKey::NUMPAD3),
+		(VirtualKey::LEFT, VirtualKey::NUMPAD4),
+		(VirtualKey::UP, VirtualKey::NUMPAD8),
+		(VirtualKey::RIGHT, VirtualKey::NUMPAD6),
+		(VirtualKey::DOWN, VirtualKey::NUMPAD2),
+		(VirtualKey::RCTRL, VirtualKey::LCTRL),
+		(VirtualKey::DIVIDE, VirtualKey::OEM_2),
+	];
+	for &(extended, other) in pairs.iter() {
+		assert!(extended.is_extended() && !other.is_extended());
+		let name = extended.display_name().unwrap();
+		assert_ne!(Some(&name), other.display_name().as_ref(), "{:?} vs {:?}", extended, other);
+	}
+	assert_eq!(VirtualKey::LBUTTON.display_name().as_deref(), Some("LBUTTON"));
+}
+
+#[test]
+fn test_scan_code() {
+	let rctrl = VirtualKey::RCTRL.scan_code();
+	assert_eq!(rctrl, ScanCode::new(0x1d, true));
+	assert_eq!(VirtualKey::LCTRL.scan_code(), ScanCode::new(0x1d, false));
+	assert_eq!(rctrl.to_vk(), VirtualKey::RCTRL);
+	assert_eq!(ScanCode::new(0x1d, false).to_vk(), VirtualKey::LCTRL);
+	assert_eq!(rctrl.input_flags(), KEYEVENTF_SCANCODE | KEYEVENTF_EXTENDEDKEY);
+	assert_eq!(VirtualKey::RETURN.scan_code().input_flags(), KEYEVENTF_SCANCODE);
+	for &vk in [VirtualKey::HOME, VirtualKey::LEFT, VirtualKey::INSERT, VirtualKey::DELETE].iter() {
+		let scan = vk.scan_code();
+		assert!(scan.is_extended(), "{:?}", vk);
+		assert_eq!(scan.to_vk(), vk);
+		assert_eq!(scan, vk.scan_code_in(current_layout()));
+	}
+	assert_eq!(VirtualKey::LBUTTON.scan_code(), ScanCode::default());
+	let pause = VirtualKey::PAUSE.scan_code();
+	assert!(pause.is_pause() && !pause.is_extended(), "{:?}", pause);
+	assert!(!rctrl.is_pause());
+}
@@ -3,7 +3,17 @@ Window handles.
 !*/
 
 mod window;
+mod window_class;
 mod window_enum;
+mod window_flash;
+mod window_monitor;
+mod window_pos;
+mod window_style;
 
 pub use self::window::*;
+pub use self::window_class::*;
 pub use self::window_enum::*;
+pub use self::window_flash::*;
+pub use self::window_monitor::*;
+pub use self::window_pos::*;
+pub use self::window_style::*;
@@ -1,16 +1,22 @@
-use std::{mem, ptr, slice};
+use std::{mem, ptr};
+use std::time::Duration;
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
 use crate::winapi::*;
-use crate::process::ProcessId;
+use crate::process::{Process, ProcessId};
+use crate::thread::WaitResult;
 use crate::thread::ThreadId;
+use crate::snap::{Color, Rect};
+use super::{AncestorKind, SetPosFlags, WindowPlacement, WindowStyle, WindowExStyle};
 use crate::error::ErrorCode;
-use crate::{Result, FromInner, IntoInner};
+use crate::{util, Result, FromInner, IntoInner};
 
 /// Abstracts a `HWND`.
 ///
 /// This is slightly special because `HWND` has no concept of ownership or anything so this abstraction doesn't try to create one.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+///
+/// Windows are compared and hashed by their handle value so they can be used as map keys.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Window(pub(super) HWND);
 impl_inner!(Window: HWND);
 impl Window {
@@ -24,6 +30,76 @@ impl Window {
 			else { Some(Window(hwnd)) }
 		}
 	}
+	/// Brings the thread that created this window into the foreground and activates the window.
+	///
+	/// The system restricts which processes may set the foreground window.
+	/// To work around this the input of the current foreground window's thread is attached while the window is brought to the top, restored if minimized and set as the foreground window.
+	///
+	/// Returns `ERROR_ACCESS_DENIED` if this window still isn't the foreground window afterwards, the caller may retry.
+	///
+	/// See [SetForegroundWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633539.aspx) for more information.
+	pub fn set_foreground(self) -> Result<()> {
+		unsafe {
+			let current_tid = GetCurrentThreadId();
+			let foreground_tid = GetWindowThreadProcessId(GetForegroundWindow(), ptr::null_mut());
+			let attached = foreground_tid != 0 && foreground_tid != current_tid &&
+				AttachThreadInput(current_tid, foreground_tid, TRUE) != FALSE;
+			BringWindowToTop(self.into_inner());
+			if self.is_minimized() {
+				self.restore();
+			}
+			SetForegroundWindow(self.into_inner());
+			if attached {
+				AttachThreadInput(current_tid, foreground_tid, FALSE);
+			}
+		}
+		if self.is_foreground() {
+			Ok(())
+		}
+		else {
+			Err(ErrorCode::from(ERROR_ACCESS_DENIED))
+		}
+	}
+	/// Brings this window to the top of the Z order and activates it if it is a top-level window.
+	///
+	/// Unlike [`set_foreground`](Window::set_foreground) this does not work around the foreground restrictions.
+	///
+	/// See [BringWindowToTop function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms632673.aspx) for more information.
+	pub fn bring_to_top(self) -> Result<()> {
+		unsafe {
+			if BringWindowToTop(self.into_inner()) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Get the window at the given point in screen coordinates.
+	///
+	/// Hidden and disabled windows are skipped, the result may be a child window such as a button.
+	///
+	/// See [WindowFromPoint function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633558.aspx) for more information.
+	pub fn from_point(x: i32, y: i32) -> Option<Window> {
+		unsafe {
+			let hwnd = WindowFromPoint(POINT { x, y });
+			if hwnd.is_null() { None }
+			else { Some(Window(hwnd)) }
+		}
+	}
+	/// Get the direct child of this window at the given point in client coordinates of this window.
+	///
+	/// Unlike [`from_point`](Window::from_point) the point is relative to the client area of this window, not the screen.
+	/// Group boxes are skipped and this window is returned if no child is at the point.
+	///
+	/// See [RealChildWindowFromPoint function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633537.aspx) for more information.
+	pub fn real_child_from_point(self, x: i32, y: i32) -> Option<Window> {
+		unsafe {
+			let hwnd = RealChildWindowFromPoint(self.into_inner(), POINT { x, y });
+			if hwnd.is_null() { None }
+			else { Some(Window(hwnd)) }
+		}
+	}
 	/// Get the desktop window.
 	///
 	/// See [GetDesktopWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633504.aspx) for more information.
@@ -41,26 +117,243 @@ impl Window {
 			IsWindow(self.into_inner()) != FALSE
 		}
 	}
-	/// Returns the class name of this window.
-	pub fn class(self) -> Result<OsString> {
+	/// Returns if this window is visible.
+	///
+	/// ```
+	/// use external::window::Window;
+	/// assert!(Window::desktop().is_visible());
+	/// ```
+	///
+	/// See [IsWindowVisible function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633530.aspx) for more information.
+	pub fn is_visible(self) -> bool {
 		unsafe {
-			// 260 ought to be enough for everyone.
-			let mut buf = mem::MaybeUninit::<[WCHAR; 260]>::uninit();
-			let ptr = buf.as_mut_ptr() as *mut WCHAR;
-			let len = RealGetWindowClassW(self.into_inner(), ptr, 260);
-			if len == 0 {
+			IsWindowVisible(self.into_inner()) != FALSE
+		}
+	}
+	/// Returns if this window is enabled for mouse and keyboard input.
+	///
+	/// See [IsWindowEnabled function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms646303.aspx) for more information.
+	pub fn is_enabled(self) -> bool {
+		unsafe {
+			IsWindowEnabled(self.into_inner()) != FALSE
+		}
+	}
+	/// Returns if this window is minimized.
+	///
+	/// See [IsIconic function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633527.aspx) for more information.
+	pub fn is_minimized(self) -> bool {
+		unsafe {
+			IsIconic(self.into_inner()) != FALSE
+		}
+	}
+	/// Returns if this window is maximized.
+	///
+	/// See [IsZoomed function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633531.aspx) for more information.
+	pub fn is_maximized(self) -> bool {
+		unsafe {
+			IsZoomed(self.into_inner()) != FALSE
+		}
+	}
+	/// Returns if this window is the foreground window.
+	pub fn is_foreground(self) -> bool {
+		unsafe {
+			GetForegroundWindow() == self.into_inner()
+		}
+	}
+	/// Returns if the thread of this window has stopped responding to messages.
+	///
+	/// See [IsHungAppWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633526.aspx) for more information.
+	pub fn is_hung(self) -> bool {
+		unsafe {
+			IsHungAppWindow(self.into_inner()) != FALSE
+		}
+	}
+	/// Returns the parent window of this child window.
+	///
+	/// Returns `None` for top-level windows, unlike `GetParent` this never returns the owner window.
+	///
+	/// See [GetAncestor function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633502.aspx) for more information.
+	pub fn parent(self) -> Option<Window> {
+		let parent = self.ancestor(AncestorKind::Parent)?;
+		if parent == Window::desktop() { None }
+		else { Some(parent) }
+	}
+	/// Returns the owner window of this window.
+	///
+	/// Returns `Ok(None)` if this window has no owner.
+	///
+	/// See [GetWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633515.aspx) for more information.
+	pub fn owner(self) -> Result<Option<Window>> {
+		unsafe {
+			SetLastError(0);
+			let hwnd = GetWindow(self.into_inner(), GW_OWNER);
+			if !hwnd.is_null() {
+				return Ok(Some(Window(hwnd)));
+			}
+			let error = ErrorCode::last();
+			if error.is_success() { Ok(None) }
+			else { Err(error) }
+		}
+	}
+	/// Returns the ancestor of this window.
+	///
+	/// See [GetAncestor function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633502.aspx) for more information.
+	pub fn ancestor(self, kind: AncestorKind) -> Option<Window> {
+		unsafe {
+			let hwnd = GetAncestor(self.into_inner(), kind.into_raw());
+			if hwnd.is_null() { None }
+			else { Some(Window(hwnd)) }
+		}
+	}
+	/// Returns the window styles.
+	///
+	/// See [GetWindowLong function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633584.aspx) for more information.
+	pub fn style(self) -> WindowStyle {
+		WindowStyle::from(self.get_long(GWL_STYLE) as DWORD)
+	}
+	/// Returns the extended window styles.
+	///
+	/// See [GetWindowLong function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633584.aspx) for more information.
+	pub fn exstyle(self) -> WindowExStyle {
+		WindowExStyle::from(self.get_long(GWL_EXSTYLE) as DWORD)
+	}
+	/// Sets the window styles and returns the previous styles.
+	///
+	/// The frame is recalculated so the change takes effect immediately.
+	///
+	/// See [SetWindowLong function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633591.aspx) for more information.
+	pub fn set_style(self, style: WindowStyle) -> Result<WindowStyle> {
+		let previous = self.set_long(GWL_STYLE, style.into_inner() as isize)?;
+		self.frame_changed()?;
+		Ok(WindowStyle::from(previous as DWORD))
+	}
+	/// Sets the extended window styles and returns the previous styles.
+	///
+	/// The frame is recalculated so the change takes effect immediately.
+	///
+	/// See [SetWindowLong function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633591.aspx) for more information.
+	pub fn set_exstyle(self, exstyle: WindowExStyle) -> Result<WindowExStyle> {
+		let previous = self.set_long(GWL_EXSTYLE, exstyle.into_inner() as isize)?;
+		self.frame_changed()?;
+		Ok(WindowExStyle::from(previous as DWORD))
+	}
+	/// Adds or removes the layered extended style.
+	///
+	/// Layered windows are composited differently, remove the style once the window is fully opaque again.
+	pub fn set_layered(self, layered: bool) -> Result<()> {
+		self.update_exstyle(WindowExStyle::LAYERED, layered)
+	}
+	/// Sets the opacity of this window, making it a layered window.
+	///
+	/// Setting the opacity back to `255` keeps the window layered, see [`set_layered`](Window::set_layered).
+	///
+	/// See [SetLayeredWindowAttributes function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633540.aspx) for more information.
+	pub fn set_opacity(self, alpha: u8) -> Result<()> {
+		self.set_layered(true)?;
+		unsafe {
+			if SetLayeredWindowAttributes(self.into_inner(), 0, alpha, LWA_ALPHA) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Makes all pixels of the given color transparent, making it a layered window.
+	///
+	/// See [SetLayeredWindowAttributes function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633540.aspx) for more information.
+	pub fn set_color_key(self, color: Color) -> Result<()> {
+		self.set_layered(true)?;
+		let color_key = color.red as COLORREF | (color.green as COLORREF) << 8 | (color.blue as COLORREF) << 16;
+		unsafe {
+			if SetLayeredWindowAttributes(self.into_inner(), color_key, 0, LWA_COLORKEY) == FALSE {
 				Err(ErrorCode::last())
 			}
 			else {
-				Ok(OsString::from_wide(slice::from_raw_parts(ptr, len as usize)))
+				Ok(())
 			}
 		}
 	}
+	/// Lets mouse input pass through this window to the windows underneath it.
+	///
+	/// This only has effect on layered windows.
+	pub fn set_click_through(self, click_through: bool) -> Result<()> {
+		self.update_exstyle(WindowExStyle::TRANSPARENT, click_through)
+	}
+	fn update_exstyle(self, flags: WindowExStyle, enable: bool) -> Result<()> {
+		let exstyle = self.exstyle();
+		let new = if enable { exstyle | flags } else { exstyle & !flags };
+		if new != exstyle {
+			self.set_exstyle(new)?;
+		}
+		Ok(())
+	}
+	fn frame_changed(self) -> Result<()> {
+		let flags = SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED;
+		unsafe {
+			if SetWindowPos(self.into_inner(), ptr::null_mut(), 0, 0, 0, 0, flags) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	#[cfg(target_pointer_width = "64")]
+	pub(super) fn get_long(self, index: i32) -> isize {
+		unsafe { GetWindowLongPtrW(self.into_inner(), index) }
+	}
+	#[cfg(target_pointer_width = "32")]
+	pub(super) fn get_long(self, index: i32) -> isize {
+		unsafe { GetWindowLongW(self.into_inner(), index) as isize }
+	}
+	fn set_long(self, index: i32, value: isize) -> Result<isize> {
+		unsafe {
+			SetLastError(0);
+			#[cfg(target_pointer_width = "64")]
+			let previous = SetWindowLongPtrW(self.into_inner(), index, value);
+			#[cfg(target_pointer_width = "32")]
+			let previous = SetWindowLongW(self.into_inner(), index, value as i32) as isize;
+			let error = ErrorCode::last();
+			if previous == 0 && !error.is_success() { Err(error) }
+			else { Ok(previous) }
+		}
+	}
+	/// Returns the real class name of this window.
+	///
+	/// For superclassed controls this is the name of the base system class, eg. `Button` for a superclassed button.
+	/// Use [`class_name`](Window::class_name) for the name the class was registered with.
+	///
+	/// See [RealGetWindowClass function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633538.aspx) for more information.
+	pub fn class(self) -> Result<OsString> {
+		grow_wide(|buf| self.class_wide(buf))
+	}
+	/// Returns the real class name of this window in the given buffer.
+	///
+	/// The class name is truncated if the buffer is too small.
 	pub fn class_wide<'a>(self, class_name: &'a mut [u16]) -> Result<&'a [u16]> {
 		unsafe {
+			SetLastError(0);
 			let len = RealGetWindowClassW(self.into_inner(), class_name.as_mut_ptr(), class_name.len() as u32);
-			if len == 0 { Err(ErrorCode::last()) }
-			else { Ok(&class_name[..len as usize]) }
+			wide_result(class_name, len as i32)
+		}
+	}
+	/// Returns the registered class name of this window.
+	///
+	/// This is the name passed to `RegisterClassEx`, which differs from [`class`](Window::class) for superclassed controls.
+	///
+	/// See [GetClassName function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633582.aspx) for more information.
+	pub fn class_name(self) -> Result<OsString> {
+		grow_wide(|buf| self.class_name_wide(buf))
+	}
+	/// Returns the registered class name of this window in the given buffer.
+	///
+	/// The class name is truncated if the buffer is too small.
+	pub fn class_name_wide(self, class_name: &mut [u16]) -> Result<&[u16]> {
+		unsafe {
+			SetLastError(0);
+			let len = GetClassNameW(self.into_inner(), class_name.as_mut_ptr(), class_name.len() as i32);
+			wide_result(class_name, len)
 		}
 	}
 	pub fn show(self, cmd: i32) {
@@ -68,6 +361,24 @@ impl Window {
 			ShowWindow(self.into_inner(), cmd);
 		}
 	}
+	/// Restores this window to its original size and position if it was minimized or maximized.
+	///
+	/// See [ShowWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633548.aspx) for more information.
+	pub fn restore(self) {
+		self.show(SW_RESTORE);
+	}
+	/// Minimizes this window.
+	///
+	/// See [ShowWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633548.aspx) for more information.
+	pub fn minimize(self) {
+		self.show(SW_MINIMIZE);
+	}
+	/// Maximizes this window.
+	///
+	/// See [ShowWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633548.aspx) for more information.
+	pub fn maximize(self) {
+		self.show(SW_MAXIMIZE);
+	}
 	pub fn update(self) -> Result<()> {
 		unsafe {
 			if UpdateWindow(self.into_inner()) == FALSE {
@@ -103,17 +414,51 @@ impl Window {
 		}
 	}
 	/// Returns the window title of this window.
+	///
+	/// An empty title is returned as an empty string, not an error.
+	///
+	/// See [GetWindowText function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633520.aspx) for more information.
 	pub fn title(self) -> Result<OsString> {
-		unsafe {
-			// 260 ought to be enough for everyone.
-			let mut buf = mem::MaybeUninit::<[WCHAR; 260]>::uninit();
-			let ptr = buf.as_mut_ptr() as *mut WCHAR;
-			let len = GetWindowTextW(self.into_inner(), ptr, 260);
+		let mut buf = Vec::new();
+		loop {
+			let len = unsafe {
+				SetLastError(0);
+				GetWindowTextLengthW(self.into_inner())
+			};
 			if len <= 0 {
+				let error = ErrorCode::last();
+				return if error.is_success() { Ok(OsString::new()) } else { Err(error) };
+			}
+			// Leave room for the nul terminator and one more to detect the title growing in between calls
+			buf.resize(len as usize + 2, 0);
+			let title = self.title_wide(&mut buf)?;
+			if title.len() <= len as usize {
+				return Ok(OsString::from_wide(title));
+			}
+		}
+	}
+	/// Returns the window title of this window in the given buffer.
+	///
+	/// The title is truncated if the buffer is too small.
+	pub fn title_wide(self, title: &mut [u16]) -> Result<&[u16]> {
+		unsafe {
+			SetLastError(0);
+			let len = GetWindowTextW(self.into_inner(), title.as_mut_ptr(), title.len() as i32);
+			wide_result(title, len)
+		}
+	}
+	/// Sets the window title of this window.
+	///
+	/// See [SetWindowText function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633546.aspx) for more information.
+	pub fn set_title(self, text: &str) -> Result<()> {
+		let mut text = text.encode_utf16().collect::<Vec<u16>>();
+		text.push(0);
+		unsafe {
+			if SetWindowTextW(self.into_inner(), text.as_ptr()) == FALSE {
 				Err(ErrorCode::last())
 			}
 			else {
-				Ok(OsString::from_wide(slice::from_raw_parts(ptr, len as usize)))
+				Ok(())
 			}
 		}
 	}
@@ -139,6 +484,261 @@ impl Window {
 			}
 		}
 	}
+	/// Retrieves the screen coordinates of the window's bounding rectangle.
+	///
+	/// On Windows 10 this includes the invisible resize borders, see [`Window::extended_frame_bounds`] for the visible frame.
+	///
+	/// See [GetWindowRect function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633519.aspx) for more information.
+	pub fn rect(self) -> Result<Rect> {
+		unsafe {
+			let mut rc = mem::MaybeUninit::<RECT>::uninit();
+			if GetWindowRect(self.into_inner(), rc.as_mut_ptr()) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(Rect::from(rc.assume_init()))
+			}
+		}
+	}
+	/// Retrieves the screen coordinates of the visible window frame as drawn by the desktop window manager.
+	///
+	/// Failures of the desktop window manager which are not system errors, eg. composition being disabled, are reported as `ERROR_NOT_SUPPORTED`.
+	///
+	/// See [DwmGetWindowAttribute function](https://msdn.microsoft.com/en-us/library/windows/desktop/aa969515.aspx) for more information.
+	pub fn extended_frame_bounds(self) -> Result<Rect> {
+		unsafe {
+			let mut rc = mem::MaybeUninit::<RECT>::uninit();
+			let hr = DwmGetWindowAttribute(self.into_inner(), DWMWA_EXTENDED_FRAME_BOUNDS, rc.as_mut_ptr() as LPVOID, mem::size_of::<RECT>() as DWORD);
+			if hr < 0 {
+				// Only HRESULTs wrapping a system error code translate back to it
+				Err(if HRESULT_FACILITY(hr) == FACILITY_WIN32 { ErrorCode::from(HRESULT_CODE(hr) as DWORD) } else { ErrorCode::from(ERROR_NOT_SUPPORTED) })
+			}
+			else {
+				Ok(Rect::from(rc.assume_init()))
+			}
+		}
+	}
+	/// Changes the size, position and Z order of this window.
+	///
+	/// See [SetWindowPos function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633545.aspx) for more information.
+	pub fn set_pos(self, x: i32, y: i32, width: i32, height: i32, flags: SetPosFlags) -> Result<()> {
+		let (insert_after, flags) = flags.into_raw();
+		unsafe {
+			if SetWindowPos(self.into_inner(), insert_after, x, y, width, height, flags) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Retrieves the show state and the restored, minimized and maximized positions of this window.
+	///
+	/// See [GetWindowPlacement function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633518.aspx) for more information.
+	pub fn placement(self) -> Result<WindowPlacement> {
+		unsafe {
+			let mut wp: WINDOWPLACEMENT = mem::zeroed();
+			wp.length = mem::size_of::<WINDOWPLACEMENT>() as UINT;
+			if GetWindowPlacement(self.into_inner(), &mut wp) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(WindowPlacement::from(wp))
+			}
+		}
+	}
+	/// Restores the show state and the restored, minimized and maximized positions of this window.
+	///
+	/// See [SetWindowPlacement function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633544.aspx) for more information.
+	pub fn set_placement(self, placement: &WindowPlacement) -> Result<()> {
+		let wp = WINDOWPLACEMENT::from(*placement);
+		unsafe {
+			if SetWindowPlacement(self.into_inner(), &wp) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Moves this window without changing its size, Z order or activation.
+	pub fn move_to(self, x: i32, y: i32) -> Result<()> {
+		self.set_pos(x, y, 0, 0, SetPosFlags::new().no_size().no_activate())
+	}
+	/// Resizes this window without changing its position, Z order or activation.
+	pub fn resize(self, width: i32, height: i32) -> Result<()> {
+		self.set_pos(0, 0, width, height, SetPosFlags::new().no_move().no_activate())
+	}
+	/// Sends a message to this window and waits until the window procedure has processed it.
+	///
+	/// Pointers passed in `wparam` or `lparam` are not marshaled across processes, except for the system messages which the system marshals itself.
+	/// The receiving process sees the same address in its own address space which most likely points to garbage.
+	///
+	/// Returns an error if the message could not be delivered, eg. when blocked by User Interface Privilege Isolation.
+	///
+	/// See [SendMessage function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644950.aspx) for more information.
+	pub fn send_message(self, msg: u32, wparam: usize, lparam: isize) -> Result<isize> {
+		unsafe {
+			SetLastError(0);
+			let result = SendMessageW(self.into_inner(), msg, wparam, lparam);
+			let error = ErrorCode::last();
+			if result == 0 && !error.is_success() {
+				Err(error)
+			}
+			else {
+				Ok(result)
+			}
+		}
+	}
+	/// Places a message in the message queue of the thread that created this window and returns without waiting.
+	///
+	/// Pointers passed in `wparam` or `lparam` are not marshaled across processes.
+	///
+	/// See [PostMessage function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644944.aspx) for more information.
+	pub fn post_message(self, msg: u32, wparam: usize, lparam: isize) -> Result<()> {
+		unsafe {
+			if PostMessageW(self.into_inner(), msg, wparam, lparam) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Sends a message to this window and waits at most the timeout for the window procedure to process it.
+	///
+	/// The `flags` are the `SMTO_*` constants, eg. `SMTO_ABORTIFHUNG` returns immediately if the receiving thread is hung.
+	/// Returns `ERROR_TIMEOUT` if the message was not processed in time.
+	///
+	/// Pointers passed in `wparam` or `lparam` are not marshaled across processes.
+	///
+	/// See [SendMessageTimeout function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644952.aspx) for more information.
+	pub fn send_message_timeout(self, msg: u32, wparam: usize, lparam: isize, flags: u32, timeout: Duration) -> Result<isize> {
+		unsafe {
+			let mut result = 0;
+			if SendMessageTimeoutW(self.into_inner(), msg, wparam, lparam, flags, util::timeout_millis(Some(timeout)), &mut result) == 0 {
+				let error = ErrorCode::last();
+				// Older systems do not set the last error on timeout
+				Err(if error.is_success() { ErrorCode::from(ERROR_TIMEOUT) } else { error })
+			}
+			else {
+				Ok(result as isize)
+			}
+		}
+	}
+	/// Politely asks this window to close by posting `WM_CLOSE`.
+	///
+	/// The application may prompt the user or ignore the request entirely.
+	pub fn close(self) -> Result<()> {
+		self.post_message(WM_CLOSE, 0, 0)
+	}
+	/// Asks the thread owning this window to exit its message loop by posting `WM_QUIT`.
+	///
+	/// See [PostThreadMessage function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644946.aspx) for more information.
+	pub fn quit(self, exit_code: i32) -> Result<()> {
+		let (thread_id, _) = self.thread_process_id();
+		unsafe {
+			if PostThreadMessageW(thread_id.into_inner(), WM_QUIT, exit_code as WPARAM, 0) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Destroys this window.
+	///
+	/// Only works for windows created by the calling thread, use [`close`](Window::close) for windows of other threads or processes.
+	///
+	/// See [DestroyWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms632682.aspx) for more information.
+	pub fn destroy(self) -> Result<()> {
+		unsafe {
+			if DestroyWindow(self.into_inner()) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(())
+			}
+		}
+	}
+	/// Asks this window to close and waits for its process to exit, terminating the process if it does not exit in time.
+	///
+	/// The process handle needs the `SYNCHRONIZE` and `TERMINATE` rights.
+	/// Returns `true` if the process exited on its own and `false` if it had to be terminated.
+	pub fn close_and_wait(self, process: &Process, timeout: Duration) -> Result<bool> {
+		self.close()?;
+		match process.wait(Some(timeout))? {
+			WaitResult::Timeout => {
+				process.terminate(1)?;
+				Ok(false)
+			},
+			_ => Ok(true),
+		}
+	}
+	/// Returns the dots per inch of this window.
+	///
+	/// The value depends on the DPI awareness of the calling process:
+	/// DPI unaware processes always get 96, system aware processes get the system DPI and only per-monitor aware processes get the DPI of the monitor this window is on.
+	/// Returns `ERROR_CALL_NOT_IMPLEMENTED` before Windows 10 version 1607.
+	///
+	/// See [GetDpiForWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/mt748624.aspx) for more information.
+	pub fn dpi(self) -> Result<u32> {
+		unsafe {
+			let get_dpi_for_window: unsafe extern "system" fn(HWND) -> UINT = match util::user32_proc(b"GetDpiForWindow\0") {
+				Some(address) => mem::transmute::<usize, unsafe extern "system" fn(HWND) -> UINT>(address),
+				None => return Err(ErrorCode::from(ERROR_CALL_NOT_IMPLEMENTED)),
+			};
+			let dpi = get_dpi_for_window(self.into_inner());
+			if dpi == 0 {
+				Err(ErrorCode::from(ERROR_INVALID_WINDOW_HANDLE))
+			}
+			else {
+				Ok(dpi)
+			}
+		}
+	}
+	/// Returns the scale factor of this window relative to 96 DPI, eg. `1.5` at 150%.
+	///
+	/// See [`dpi`](Window::dpi) for how the DPI awareness of the calling process affects the result.
+	pub fn scale_factor(self) -> Result<f32> {
+		self.dpi().map(|dpi| dpi as f32 / 96.0)
+	}
+	/// Convert the client-area coordinates of a specified point to physical screen coordinates.
+	///
+	/// Unlike [`client_to_screen`](Window::client_to_screen) the result is in physical pixels, matching what the mouse functions expect,
+	/// even when the calling process is not per-monitor DPI aware (see `PROCESS_DPI_AWARENESS`) and its coordinates are virtualized by the system.
+	/// For per-monitor aware processes both functions return the same coordinates.
+	///
+	/// See [LogicalToPhysicalPointForPerMonitorDPI function](https://msdn.microsoft.com/en-us/library/windows/desktop/dn384110.aspx) for more information.
+	pub fn client_to_screen_scaled(self, point: (i32, i32)) -> Result<(i32, i32)> {
+		let point = self.client_to_screen(point)?;
+		self.convert_point(b"LogicalToPhysicalPointForPerMonitorDPI\0", point)
+	}
+	/// Convert the physical screen coordinates of a specified point to client-area coordinates.
+	///
+	/// This is the inverse of [`client_to_screen_scaled`](Window::client_to_screen_scaled).
+	///
+	/// See [PhysicalToLogicalPointForPerMonitorDPI function](https://msdn.microsoft.com/en-us/library/windows/desktop/dn384112.aspx) for more information.
+	pub fn screen_to_client_scaled(self, point: (i32, i32)) -> Result<(i32, i32)> {
+		let point = self.convert_point(b"PhysicalToLogicalPointForPerMonitorDPI\0", point)?;
+		self.screen_to_client(point)
+	}
+	fn convert_point(self, name: &[u8], point: (i32, i32)) -> Result<(i32, i32)> {
+		unsafe {
+			let convert: unsafe extern "system" fn(HWND, *mut POINT) -> BOOL = match util::user32_proc(name) {
+				Some(address) => mem::transmute::<usize, unsafe extern "system" fn(HWND, *mut POINT) -> BOOL>(address),
+				// Before Windows 8.1 there is no per-monitor DPI and no conversion is needed
+				None => return Ok(point),
+			};
+			let mut pt = POINT { x: point.0, y: point.1 };
+			if convert(self.into_inner(), &mut pt) == FALSE {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok((pt.x, pt.y))
+			}
+		}
+	}
 	/// Convert the client-area coordinates of a specified point to screen coordinates.
 	///
 	/// See [ClientToScreen function](https://msdn.microsoft.com/en-us/library/vs/alm/dd183434.aspx) for more information.
@@ -168,3 +768,135 @@ impl Window {
 		}
 	}
 }
+
+// Zero length is only an error if the last error was set, an empty string otherwise.
+unsafe fn wide_result(buf: &[u16], len: i32) -> Result<&[u16]> {
+	if len <= 0 {
+		let error = ErrorCode::last();
+		if error.is_success() { Ok(&buf[..0]) }
+		else { Err(error) }
+	}
+	else {
+		Ok(&buf[..len as usize])
+	}
+}
+
+// Calls `f` with increasingly larger buffers until the result is no longer truncated.
+fn grow_wide<F: FnMut(&mut [u16]) -> Result<&[u16]>>(mut f: F) -> Result<OsString> {
+	let mut buf = vec![0u16; 256];
+	loop {
+		let len = f(&mut buf)?.len();
+		// A truncated result fills the buffer except for the nul terminator
+		if len + 1 < buf.len() || buf.len() >= 0x8000 {
+			return Ok(OsString::from_wide(&buf[..len]));
+		}
+		let new_len = buf.len() * 2;
+		buf.resize(new_len, 0);
+	}
+}
+
+#[cfg(test)]
+fn create_test_window() -> Window {
+	unsafe {
+		let class = wide_str!('S' 'T' 'A' 'T' 'I' 'C' 0);
+		let hwnd = CreateWindowExW(0, class.as_ptr(), ptr::null(), WS_OVERLAPPEDWINDOW, 0, 0, 200, 100, ptr::null_mut(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+		assert!(!hwnd.is_null());
+		Window(hwnd)
+	}
+}
+
+#[test]
+fn test_window_state() {
+	let window = create_test_window();
+	assert!(window.valid());
+	assert!(!window.is_visible());
+	assert!(!window.is_hung());
+	window.show(SW_SHOWNOACTIVATE);
+	assert!(window.is_visible());
+	window.minimize();
+	assert!(window.is_minimized());
+	window.maximize();
+	assert!(window.is_maximized());
+	window.restore();
+	assert!(!window.is_minimized() && !window.is_maximized());
+	window.destroy().unwrap();
+	assert!(!window.valid());
+}
+
+#[test]
+fn test_window_map_key() {
+	use std::collections::HashMap;
+	let mut windows = [Window::desktop(), Window::null()];
+	windows.sort();
+	assert_eq!(windows[0], Window::null());
+	let mut map = HashMap::new();
+	map.insert(Window::desktop(), 1);
+	assert_eq!(map.get(&Window::desktop()), Some(&1));
+}
+
+#[test]
+fn test_window_title() {
+	let window = create_test_window();
+	assert_eq!(window.title(), Ok(OsString::new()));
+	let long_title = "0123456789".repeat(50);
+	window.set_title(&long_title).unwrap();
+	assert_eq!(window.title(), Ok(OsString::from(&long_title)));
+	let mut buf = [0u16; 11];
+	assert_eq!(window.title_wide(&mut buf).unwrap().len(), 10);
+	window.destroy().unwrap();
+}
+
+#[test]
+fn test_window_class() {
+	let window = create_test_window();
+	assert_eq!(window.class(), Ok(OsString::from("Static")));
+	assert!(window.class_name().unwrap().to_string_lossy().eq_ignore_ascii_case("static"));
+	let mut buf = [0u16; 4];
+	assert_eq!(window.class_name_wide(&mut buf).unwrap().len(), 3);
+	window.destroy().unwrap();
+}
+
+#[test]
+fn test_window_layered() {
+	let window = create_test_window();
+	window.set_opacity(178).unwrap();
+	assert!(window.exstyle().is_layered());
+	window.set_click_through(true).unwrap();
+	assert!(window.exstyle().is_transparent());
+	window.set_click_through(false).unwrap();
+	window.set_layered(false).unwrap();
+	assert!(!window.exstyle().is_layered() && !window.exstyle().is_transparent());
+	window.destroy().unwrap();
+}
+
+#[test]
+fn test_window_class_info() {
+	let window = create_test_window();
+	let info = window.class_info().unwrap();
+	assert!(info.wndproc != 0 && window.wndproc() != 0);
+	assert!(window.class_atom().is_ok());
+	window.destroy().unwrap();
+}
+
+#[test]
+fn test_window_placement() {
+	let window = create_test_window();
+	window.show(SW_SHOWNOACTIVATE);
+	let placement = window.placement().unwrap();
+	window.minimize();
+	assert_eq!(window.placement().unwrap().show_cmd, SW_SHOWMINIMIZED as u32);
+	window.set_placement(&placement).unwrap();
+	let restored = window.placement().unwrap();
+	assert_eq!(restored.show_cmd, placement.show_cmd);
+	assert_eq!(restored.normal_rect, placement.normal_rect);
+	window.destroy().unwrap();
+}
+
+#[test]
+fn test_windows_of() {
+	let window = create_test_window();
+	assert!(super::windows_of_thread(ThreadId::current()).contains(&window));
+	assert!(super::windows_of_ex(ProcessId::current(), true, false).contains(&window));
+	assert!(!super::windows_of(ProcessId::current()).contains(&window));
+	window.destroy().unwrap();
+}
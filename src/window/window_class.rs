@@ -0,0 +1,90 @@
+use std::mem;
+use crate::winapi::*;
+use crate::window::Window;
+use crate::error::ErrorCode;
+use crate::{Result, IntoInner};
+
+/// Window class information.
+///
+/// See [WNDCLASSEX structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633577.aspx) for more information.
+#[derive(Copy, Clone, Debug)]
+pub struct WndClassInfo {
+	/// The class styles, the `CS_*` constants.
+	pub style: u32,
+	/// The window procedure of the class.
+	pub wndproc: usize,
+	/// The number of extra bytes allocated following the class structure.
+	pub class_extra: i32,
+	/// The number of extra bytes allocated following the window instance.
+	pub window_extra: i32,
+	/// The instance that registered the class.
+	pub instance: HMODULE,
+	/// The class icon.
+	pub icon: HICON,
+	/// The class small icon.
+	pub icon_small: HICON,
+	/// The class cursor.
+	pub cursor: HCURSOR,
+	/// The class background brush.
+	pub background: HBRUSH,
+}
+
+impl Window {
+	/// Returns the instance handle of the module that created this window.
+	///
+	/// See [GetWindowLongPtr function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633585.aspx) for more information.
+	pub fn instance(self) -> HMODULE {
+		self.get_long(GWLP_HINSTANCE) as HMODULE
+	}
+	/// Returns the window procedure of this window.
+	///
+	/// When this differs from the class window procedure the window has been subclassed.
+	///
+	/// See [GetWindowLongPtr function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633585.aspx) for more information.
+	pub fn wndproc(self) -> usize {
+		self.get_long(GWLP_WNDPROC) as usize
+	}
+	/// Returns the atom identifying the class of this window.
+	///
+	/// See [GetClassWord function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633582.aspx) for more information.
+	pub fn class_atom(self) -> Result<ATOM> {
+		unsafe {
+			let atom = GetClassWord(self.into_inner(), GCW_ATOM);
+			if atom == 0 {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(atom)
+			}
+		}
+	}
+	/// Returns information about the class of this window.
+	///
+	/// See [GetClassInfoEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633579.aspx) for more information.
+	pub fn class_info(self) -> Result<WndClassInfo> {
+		// Look up the class by its atom, superclassed windows report the base class with RealGetWindowClass
+		let atom = self.class_atom()?;
+		unsafe {
+			#[cfg(target_pointer_width = "64")]
+			let instance = GetClassLongPtrW(self.into_inner(), GCLP_HMODULE) as HINSTANCE;
+			#[cfg(target_pointer_width = "32")]
+			let instance = GetClassLongW(self.into_inner(), GCLP_HMODULE) as HINSTANCE;
+			let mut wc: WNDCLASSEXW = mem::zeroed();
+			wc.cbSize = mem::size_of::<WNDCLASSEXW>() as UINT;
+			if GetClassInfoExW(instance, atom as usize as LPCWSTR, &mut wc) == FALSE {
+				return Err(ErrorCode::last());
+			}
+			Ok(WndClassInfo {
+				style: wc.style,
+				wndproc: wc.lpfnWndProc.map_or(0, |wndproc| wndproc as usize),
+				class_extra: wc.cbClsExtra,
+				window_extra: wc.cbWndExtra,
+				instance: wc.hInstance,
+				icon: wc.hIcon,
+				icon_small: wc.hIconSm,
+				cursor: wc.hCursor,
+				background: wc.hbrBackground,
+			})
+		}
+	}
+}
@@ -1,36 +1,215 @@
-use std::{ptr};
+use std::{panic, ptr};
+use std::any::Any;
 use std::ffi::OsStr;
 use std::os::windows::ffi::OsStrExt;
 use crate::winapi::*;
 use crate::error::ErrorCode;
 use crate::window::Window;
-use crate::Result;
+use crate::process::ProcessId;
+use crate::thread::ThreadId;
+use crate::{Result, IntoInner};
 
 struct EnumWindowsContext<'a> {
 	callback: &'a mut dyn FnMut(Window) -> bool,
+	panic: Option<Box<dyn Any + Send>>,
 }
 #[allow(non_snake_case)]
 unsafe extern "system" fn thunk(hwnd: HWND, lParam: LPARAM) -> BOOL {
 	let context = &mut *(lParam as *mut EnumWindowsContext);
-	// We are called from an FFI context so if this panics 'undefined behaviour' happens.
-	// To solve this it should be wrapped in a `panic::catch_unwind` to catch panics but due to reasons I can't get this to work...
-	// So fuck it! >.>
-	if (context.callback)(Window(hwnd)) { TRUE }
-	else { FALSE }
+	// We are called from an FFI context so panics must not unwind through here.
+	// Catch the panic, stop the enumeration and resume unwinding once back in Rust.
+	let callback = &mut context.callback;
+	match panic::catch_unwind(panic::AssertUnwindSafe(|| callback(Window(hwnd)))) {
+		Ok(true) => TRUE,
+		Ok(false) => FALSE,
+		Err(payload) => {
+			context.panic = Some(payload);
+			FALSE
+		},
+	}
 }
 
 /// Enumerate all top-level windows.
 ///
+/// The callback returns `true` to continue the enumeration or `false` to stop it early.
+/// Returns `false` if the enumeration was stopped by the callback or failed.
+///
+/// A panic in the callback stops the enumeration and is resumed after `EnumWindows` returns.
+///
 /// See [EnumWindows function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633497.aspx) for more information.
 pub fn windows<F>(mut f: F) -> bool where F: FnMut(Window) -> bool {
 	let mut context = EnumWindowsContext {
 		callback: &mut f,
+		panic: None,
 	};
-	unsafe {
+	let result = unsafe {
 		EnumWindows(Some(thunk), &mut context as *mut _ as LPARAM) != FALSE
+	};
+	if let Some(payload) = context.panic {
+		panic::resume_unwind(payload);
+	}
+	result
+}
+
+/// Collects all top-level windows.
+///
+/// See [EnumWindows function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633497.aspx) for more information.
+pub fn top_level() -> Vec<Window> {
+	let mut windows = Vec::new();
+	self::windows(|window| {
+		windows.push(window);
+		true
+	});
+	windows
+}
+
+/// Finds the main window of a process.
+///
+/// The main window is a visible top-level window without an owner belonging to the process.
+/// When the process has several candidates the first one in Z order with a title wins,
+/// if none of them has a title the first candidate in Z order is returned.
+///
+/// All windows are inspected in a single `EnumWindows` pass.
+pub fn main_window_of(pid: ProcessId) -> Option<Window> {
+	let mut untitled = None;
+	let mut titled = None;
+	self::windows(|window| {
+		if window.thread_process_id().1 != pid || !window.is_visible() {
+			return true;
+		}
+		if window.owner() != Ok(None) {
+			return true;
+		}
+		if unsafe { GetWindowTextLengthW(window.0) } > 0 {
+			titled = Some(window);
+			return false;
+		}
+		if untitled.is_none() {
+			untitled = Some(window);
+		}
+		true
+	});
+	titled.or(untitled)
+}
+
+/// Collects the visible top-level windows belonging to a process.
+pub fn windows_of(pid: ProcessId) -> Vec<Window> {
+	windows_of_ex(pid, false, false)
+}
+
+/// Collects the top-level windows belonging to a process.
+///
+/// Hidden windows are only included if `include_hidden` is set.
+/// With `include_children` the descendants of every matching top-level window follow it in the result.
+///
+/// All top-level windows are inspected in a single `EnumWindows` pass.
+pub fn windows_of_ex(pid: ProcessId, include_hidden: bool, include_children: bool) -> Vec<Window> {
+	let mut result = Vec::new();
+	self::windows(|window| {
+		if window.thread_process_id().1 == pid && (include_hidden || window.is_visible()) {
+			result.push(window);
+			if include_children {
+				child_windows(window, |child| {
+					if include_hidden || child.is_visible() {
+						result.push(child);
+					}
+					true
+				});
+			}
+		}
+		true
+	});
+	result
+}
+
+/// Collects all the non-child windows created by a thread.
+///
+/// See [EnumThreadWindows function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633495.aspx) for more information.
+pub fn windows_of_thread(tid: ThreadId) -> Vec<Window> {
+	let mut result = Vec::new();
+	let mut f = |window| {
+		result.push(window);
+		true
+	};
+	let mut context = EnumWindowsContext {
+		callback: &mut f,
+		panic: None,
+	};
+	unsafe {
+		EnumThreadWindows(tid.into_inner(), Some(thunk), &mut context as *mut _ as LPARAM);
+	}
+	if let Some(payload) = context.panic {
+		panic::resume_unwind(payload);
+	}
+	result
+}
+
+/// Enumerate all descendants of the parent window.
+///
+/// Children of children are enumerated as well.
+/// The callback follows the same rules as [`windows`].
+///
+/// See [EnumChildWindows function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633494.aspx) for more information.
+pub fn child_windows<F>(parent: Window, mut f: F) -> bool where F: FnMut(Window) -> bool {
+	let mut context = EnumWindowsContext {
+		callback: &mut f,
+		panic: None,
+	};
+	let result = unsafe {
+		EnumChildWindows(parent.0, Some(thunk), &mut context as *mut _ as LPARAM) != FALSE
+	};
+	if let Some(payload) = context.panic {
+		panic::resume_unwind(payload);
+	}
+	result
+}
+
+impl Window {
+	/// Returns the direct children of this window.
+	pub fn children(self) -> Vec<Window> {
+		let mut children = Vec::new();
+		child_windows(self, |child| {
+			if child.parent() == Some(self) {
+				children.push(child);
+			}
+			true
+		});
+		children
+	}
+	/// Returns all the descendants of this window, recursively.
+	pub fn descendants(self) -> Vec<Window> {
+		let mut descendants = Vec::new();
+		child_windows(self, |child| {
+			descendants.push(child);
+			true
+		});
+		descendants
+	}
+	/// Finds the first descendant of this window with the given class name.
+	///
+	/// Class names are compared case insensitive.
+	pub fn descendant_by_class(self, class: &OsStr) -> Option<Window> {
+		let class = class.encode_wide().collect::<Vec<u16>>();
+		let mut buf = [0u16; 260];
+		let mut found = None;
+		child_windows(self, |child| {
+			match child.class_wide(&mut buf) {
+				Ok(name) if eq_ignore_ascii_case(name, &class) => {
+					found = Some(child);
+					false
+				},
+				_ => true,
+			}
+		});
+		found
 	}
 }
 
+fn eq_ignore_ascii_case(a: &[u16], b: &[u16]) -> bool {
+	let lower = |c: u16| if c >= b'A' as u16 && c <= b'Z' as u16 { c + 0x20 } else { c };
+	a.len() == b.len() && a.iter().zip(b).all(|(&a, &b)| lower(a) == lower(b))
+}
+
 /// Find a window by class name or window title.
 ///
 /// See [FindWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633499.aspx) for more information.
@@ -39,16 +218,8 @@ pub fn find<T: AsRef<OsStr>>(class: Option<&T>, title: Option<&T>) -> Result<Win
 }
 fn _find(class: Option<&OsStr>, title: Option<&OsStr>) -> Result<Window> {
 	// These memory allocations make me cry...
-	let class = class.map(|class| {
-		let mut vec = class.encode_wide().collect::<Vec<u16>>();
-		vec.push(0);
-		vec
-	});
-	let title = title.map(|title| {
-		let mut vec = title.encode_wide().collect::<Vec<u16>>();
-		vec.push(0);
-		vec
-	});
+	let class = class.map(to_wide_nul);
+	let title = title.map(to_wide_nul);
 	let wnd = unsafe {
 		FindWindowW(
 			class.as_ref().map_or(ptr::null(), |class| class.as_ptr()),
@@ -61,3 +232,118 @@ fn _find(class: Option<&OsStr>, title: Option<&OsStr>) -> Result<Window> {
 		Ok(Window(wnd))
 	}
 }
+
+fn to_wide_nul(s: &OsStr) -> Vec<u16> {
+	let mut vec = s.encode_wide().collect::<Vec<u16>>();
+	vec.push(0);
+	vec
+}
+
+impl Window {
+	/// Find a child window by class name or window title.
+	///
+	/// The search starts after the given child window, pass the previous result to find the next matching child.
+	///
+	/// See [FindWindowEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633500.aspx) for more information.
+	pub fn find_child(self, after: Option<Window>, class: Option<&OsStr>, title: Option<&OsStr>) -> Result<Window> {
+		let class = class.map(to_wide_nul);
+		let title = title.map(to_wide_nul);
+		find_child_wide(self, after, class.as_deref(), title.as_deref())
+	}
+	/// Find all the direct child windows by class name or window title.
+	pub fn find_children(self, class: Option<&OsStr>, title: Option<&OsStr>) -> Vec<Window> {
+		let class = class.map(to_wide_nul);
+		let title = title.map(to_wide_nul);
+		let mut children = Vec::new();
+		let mut after = None;
+		while let Ok(child) = find_child_wide(self, after, class.as_deref(), title.as_deref()) {
+			children.push(child);
+			after = Some(child);
+		}
+		children
+	}
+}
+fn find_child_wide(parent: Window, after: Option<Window>, class: Option<&[u16]>, title: Option<&[u16]>) -> Result<Window> {
+	let wnd = unsafe {
+		FindWindowExW(
+			parent.0,
+			after.map_or(ptr::null_mut(), |after| after.0),
+			class.map_or(ptr::null(), |class| class.as_ptr()),
+			title.map_or(ptr::null(), |title| title.as_ptr()))
+	};
+	if wnd.is_null() {
+		Err(ErrorCode::last())
+	}
+	else {
+		Ok(Window(wnd))
+	}
+}
+
+#[test]
+fn test_top_level() {
+	let windows = top_level();
+	assert!(!windows.is_empty());
+	let result = panic::catch_unwind(|| {
+		self::windows(|_| panic!("callback panic"));
+	});
+	assert!(result.is_err());
+}
+
+// Creates a hidden parent window with a child window and a STATIC grandchild.
+#[cfg(test)]
+fn create_test_tree() -> (Window, Window, Window) {
+	use crate::wndclass::WindowBuilder;
+	use crate::window::WindowStyle;
+	let parent = WindowBuilder::new().title("parent").create().unwrap();
+	let child = WindowBuilder::new().title("child").style(WindowStyle::CHILD).parent(parent).create().unwrap();
+	let grandchild = unsafe {
+		let class = wide_str!('S' 'T' 'A' 'T' 'I' 'C' 0);
+		let title = wide_str!('g' 'r' 'a' 'n' 'd' 'c' 'h' 'i' 'l' 'd' 0);
+		let hwnd = CreateWindowExW(0, class.as_ptr(), title.as_ptr(), WS_CHILD, 0, 0, 100, 20, child.0, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+		assert!(!hwnd.is_null());
+		Window(hwnd)
+	};
+	(parent, child, grandchild)
+}
+
+#[test]
+fn test_children() {
+	let (parent, child, grandchild) = create_test_tree();
+	assert_eq!(parent.children(), [child]);
+	assert_eq!(child.children(), [grandchild]);
+	let descendants = parent.descendants();
+	assert_eq!(descendants.len(), 2);
+	assert!(descendants.contains(&child) && descendants.contains(&grandchild));
+	assert_eq!(parent.descendant_by_class(OsStr::new("static")), Some(grandchild));
+	assert_eq!(parent.descendant_by_class(OsStr::new("Button")), None);
+	assert!(grandchild.descendants().is_empty());
+	parent.destroy().unwrap();
+}
+
+
+#[test]
+fn test_find_child() {
+	let (parent, child, grandchild) = create_test_tree();
+	let class = OsStr::new("WindowBuilder0");
+	assert_eq!(parent.find_child(None, Some(class), None), Ok(child));
+	assert_eq!(child.find_child(None, None, Some(OsStr::new("grandchild"))), Ok(grandchild));
+	assert_eq!(parent.find_children(Some(class), Some(OsStr::new("child"))), [child]);
+	// Only the direct children are searched
+	assert!(parent.find_child(None, Some(OsStr::new("STATIC")), None).is_err());
+	assert!(parent.find_child(Some(child), Some(class), None).is_err());
+	assert!(parent.find_children(None, Some(OsStr::new("grandchild"))).is_empty());
+	parent.destroy().unwrap();
+}
+
+#[test]
+fn test_main_window_of() {
+	use crate::wndclass::WindowBuilder;
+	let unowned = WindowBuilder::new().title("unowned").create().unwrap();
+	let owned = WindowBuilder::new().title("owned").parent(unowned).create().unwrap();
+	unowned.show(SW_SHOWNOACTIVATE);
+	owned.show(SW_SHOWNOACTIVATE);
+	// The owned window is above its owner in Z order
+	assert_eq!(owned.owner(), Ok(Some(unowned)));
+	assert_eq!(main_window_of(ProcessId::current()), Some(unowned));
+	unowned.destroy().unwrap();
+}
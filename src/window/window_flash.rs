@@ -0,0 +1,71 @@
+use std::mem;
+use std::time::Duration;
+use crate::winapi::*;
+use crate::window::Window;
+use crate::{util, Result, IntoInner};
+
+/// Create window flash flags using the builder pattern.
+///
+/// See [FLASHWINFO structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms679348.aspx) for more information.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct FlashFlags(DWORD);
+impl_inner!(FlashFlags: safe DWORD);
+impl FlashFlags {
+	pub const CAPTION: FlashFlags = FlashFlags(FLASHW_CAPTION);
+	pub const TRAY: FlashFlags = FlashFlags(FLASHW_TRAY);
+	pub const ALL: FlashFlags = FlashFlags(FLASHW_ALL);
+	pub const TIMER: FlashFlags = FlashFlags(FLASHW_TIMER);
+	pub const TIMERNOFG: FlashFlags = FlashFlags(FLASHW_TIMERNOFG);
+}
+impl FlashFlags {
+	pub const fn new() -> FlashFlags {
+		FlashFlags(0)
+	}
+	/// Flash the window caption.
+	pub const fn caption(self) -> FlashFlags {
+		FlashFlags(self.0 | FLASHW_CAPTION)
+	}
+	/// Flash the taskbar button.
+	pub const fn tray(self) -> FlashFlags {
+		FlashFlags(self.0 | FLASHW_TRAY)
+	}
+	/// Flash both the window caption and taskbar button.
+	pub const fn all(self) -> FlashFlags {
+		FlashFlags(self.0 | FLASHW_ALL)
+	}
+	/// Flash continuously until stopped.
+	pub const fn timer(self) -> FlashFlags {
+		FlashFlags(self.0 | FLASHW_TIMER)
+	}
+	/// Flash continuously until the window comes to the foreground.
+	pub const fn timer_no_fg(self) -> FlashFlags {
+		FlashFlags(self.0 | FLASHW_TIMERNOFG)
+	}
+}
+
+impl Window {
+	/// Flashes this window.
+	///
+	/// The `count` is the number of times to flash, `None` flashes until stopped when combined with one of the timer flags.
+	/// The `rate` is the time between flashes, `None` uses the default cursor blink rate.
+	///
+	/// Returns if the window caption was drawn as active before the call.
+	///
+	/// See [FlashWindowEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms679347.aspx) for more information.
+	pub fn flash(self, count: Option<u32>, rate: Option<Duration>, flags: FlashFlags) -> Result<bool> {
+		let mut info = FLASHWINFO {
+			cbSize: mem::size_of::<FLASHWINFO>() as UINT,
+			hwnd: self.into_inner(),
+			dwFlags: flags.into_inner(),
+			uCount: count.unwrap_or(0),
+			dwTimeout: rate.map_or(0, |rate| util::timeout_millis(Some(rate))),
+		};
+		unsafe {
+			Ok(FlashWindowEx(&mut info) != FALSE)
+		}
+	}
+	/// Stops flashing this window and restores it to its original state.
+	pub fn stop_flash(self) -> Result<bool> {
+		self.flash(None, None, FlashFlags(FLASHW_STOP))
+	}
+}
@@ -0,0 +1,57 @@
+use std::mem;
+use std::ffi::OsString;
+use std::os::windows::ffi::OsStringExt;
+use crate::winapi::*;
+use crate::snap::Rect;
+use crate::error::ErrorCode;
+use crate::window::Window;
+use crate::{util, Result, IntoInner};
+
+/// Display monitor information.
+///
+/// See [MONITORINFOEX structure](https://msdn.microsoft.com/en-us/library/windows/desktop/dd145066.aspx) for more information.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MonitorInfo {
+	/// The display monitor rectangle in virtual-screen coordinates.
+	pub rect: Rect,
+	/// The work area rectangle in virtual-screen coordinates, excludes the taskbar and docked toolbars.
+	pub work_area: Rect,
+	/// Whether this is the primary display monitor.
+	pub is_primary: bool,
+	/// The device name of the monitor, eg. `\\.\DISPLAY1`.
+	pub device: OsString,
+}
+
+impl Window {
+	/// Retrieves information about the monitor this window lives on.
+	///
+	/// The nearest monitor is used when the window does not intersect any monitor.
+	///
+	/// See [MonitorFromWindow function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd145064.aspx) for more information.
+	pub fn monitor(self) -> Result<MonitorInfo> {
+		unsafe {
+			let hmonitor = MonitorFromWindow(self.into_inner(), MONITOR_DEFAULTTONEAREST);
+			if hmonitor.is_null() {
+				return Err(ErrorCode::last());
+			}
+			let mut mi: MONITORINFOEXW = mem::zeroed();
+			mi.cbSize = mem::size_of::<MONITORINFOEXW>() as DWORD;
+			if GetMonitorInfoW(hmonitor, &mut mi as *mut _ as LPMONITORINFO) == FALSE {
+				return Err(ErrorCode::from(ERROR_INVALID_MONITOR_HANDLE));
+			}
+			Ok(MonitorInfo {
+				rect: Rect::from(mi.rcMonitor),
+				work_area: Rect::from(mi.rcWork),
+				is_primary: mi.dwFlags & MONITORINFOF_PRIMARY != 0,
+				device: OsString::from_wide(util::from_wchar_buf(&mi.szDevice)),
+			})
+		}
+	}
+}
+
+#[test]
+fn test_desktop_monitor() {
+	let monitor = Window::desktop().monitor().unwrap();
+	assert!(monitor.rect.width > 0 && monitor.rect.height > 0);
+	assert!(monitor.work_area.width <= monitor.rect.width);
+}
@@ -0,0 +1,151 @@
+use dataview::Pod;
+use crate::winapi::*;
+use super::Window;
+
+/// Where to place a window in the Z order.
+///
+/// See [SetWindowPos function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633545.aspx) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InsertAfter {
+	/// Places the window below the given window.
+	Window(Window),
+	/// Places the window at the top of the Z order.
+	Top,
+	/// Places the window at the bottom of the Z order.
+	Bottom,
+	/// Places the window above all non-topmost windows, it keeps its topmost position even when deactivated.
+	TopMost,
+	/// Places the window above all non-topmost windows, that is, behind all topmost windows.
+	NoTopMost,
+}
+impl InsertAfter {
+	pub(crate) fn into_hwnd(self) -> HWND {
+		match self {
+			InsertAfter::Window(window) => window.0,
+			InsertAfter::Top => HWND_TOP,
+			InsertAfter::Bottom => HWND_BOTTOM,
+			InsertAfter::TopMost => HWND_TOPMOST,
+			InsertAfter::NoTopMost => HWND_NOTOPMOST,
+		}
+	}
+}
+
+/// Create window positioning flags using the builder pattern.
+///
+/// Unless an [`InsertAfter`] position is given the Z order of the window is left untouched.
+///
+/// See [SetWindowPos function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633545.aspx) for more information.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SetPosFlags {
+	flags: UINT,
+	insert_after: Option<InsertAfter>,
+}
+impl SetPosFlags {
+	pub const fn new() -> SetPosFlags {
+		SetPosFlags { flags: 0, insert_after: None }
+	}
+	/// Retains the current size, ignores the width and height.
+	pub const fn no_size(self) -> SetPosFlags {
+		SetPosFlags { flags: self.flags | SWP_NOSIZE, ..self }
+	}
+	/// Retains the current position, ignores the x and y coordinates.
+	pub const fn no_move(self) -> SetPosFlags {
+		SetPosFlags { flags: self.flags | SWP_NOMOVE, ..self }
+	}
+	/// Retains the current Z order, ignores any insert after position.
+	pub const fn no_zorder(self) -> SetPosFlags {
+		SetPosFlags { flags: self.flags | SWP_NOZORDER, ..self }
+	}
+	/// Does not activate the window.
+	pub const fn no_activate(self) -> SetPosFlags {
+		SetPosFlags { flags: self.flags | SWP_NOACTIVATE, ..self }
+	}
+	/// Displays the window.
+	pub const fn show_window(self) -> SetPosFlags {
+		SetPosFlags { flags: self.flags | SWP_SHOWWINDOW, ..self }
+	}
+	/// Hides the window.
+	pub const fn hide_window(self) -> SetPosFlags {
+		SetPosFlags { flags: self.flags | SWP_HIDEWINDOW, ..self }
+	}
+	/// Places the window in the Z order.
+	pub const fn insert_after(self, insert_after: InsertAfter) -> SetPosFlags {
+		SetPosFlags { insert_after: Some(insert_after), ..self }
+	}
+	/// Places the window above all non-topmost windows.
+	pub const fn topmost(self) -> SetPosFlags {
+		self.insert_after(InsertAfter::TopMost)
+	}
+	pub(crate) fn into_raw(self) -> (HWND, UINT) {
+		match self.insert_after {
+			Some(insert_after) => (insert_after.into_hwnd(), self.flags),
+			None => (HWND_TOP, self.flags | SWP_NOZORDER),
+		}
+	}
+}
+
+/// Which ancestor to retrieve.
+///
+/// See [GetAncestor function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms633502.aspx) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AncestorKind {
+	/// The parent window, for top-level windows this is the desktop window.
+	Parent,
+	/// The root window by walking the chain of parent windows.
+	Root,
+	/// The owned root window by walking the chain of parent and owner windows.
+	RootOwner,
+}
+impl AncestorKind {
+	pub(crate) fn into_raw(self) -> UINT {
+		match self {
+			AncestorKind::Parent => GA_PARENT,
+			AncestorKind::Root => GA_ROOT,
+			AncestorKind::RootOwner => GA_ROOTOWNER,
+		}
+	}
+}
+
+/// Show state and positions of a window.
+///
+/// Plain data which can be saved and restored later with [`Window::set_placement`].
+///
+/// See [WINDOWPLACEMENT structure](https://msdn.microsoft.com/en-us/library/windows/desktop/ms632611.aspx) for more information.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct WindowPlacement {
+	/// The `WPF_*` flags.
+	pub flags: u32,
+	/// The show state of the window, the `SW_*` constants.
+	pub show_cmd: u32,
+	/// The top left corner of the window when minimized.
+	pub min_position: [i32; 2],
+	/// The top left corner of the window when maximized.
+	pub max_position: [i32; 2],
+	/// The left, top, right and bottom coordinates of the window when restored.
+	pub normal_rect: [i32; 4],
+}
+unsafe impl Pod for WindowPlacement {}
+impl From<WINDOWPLACEMENT> for WindowPlacement {
+	fn from(wp: WINDOWPLACEMENT) -> WindowPlacement {
+		WindowPlacement {
+			flags: wp.flags,
+			show_cmd: wp.showCmd,
+			min_position: [wp.ptMinPosition.x, wp.ptMinPosition.y],
+			max_position: [wp.ptMaxPosition.x, wp.ptMaxPosition.y],
+			normal_rect: [wp.rcNormalPosition.left, wp.rcNormalPosition.top, wp.rcNormalPosition.right, wp.rcNormalPosition.bottom],
+		}
+	}
+}
+impl From<WindowPlacement> for WINDOWPLACEMENT {
+	fn from(wp: WindowPlacement) -> WINDOWPLACEMENT {
+		WINDOWPLACEMENT {
+			length: std::mem::size_of::<WINDOWPLACEMENT>() as UINT,
+			flags: wp.flags,
+			showCmd: wp.show_cmd,
+			ptMinPosition: POINT { x: wp.min_position[0], y: wp.min_position[1] },
+			ptMaxPosition: POINT { x: wp.max_position[0], y: wp.max_position[1] },
+			rcNormalPosition: RECT { left: wp.normal_rect[0], top: wp.normal_rect[1], right: wp.normal_rect[2], bottom: wp.normal_rect[3] },
+		}
+	}
+}
@@ -0,0 +1,207 @@
+use std::{fmt, ops};
+use crate::winapi::*;
+
+/// Window styles.
+///
+/// See [Window Styles](https://msdn.microsoft.com/en-us/library/windows/desktop/ms632600.aspx) for more information.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub struct WindowStyle(DWORD);
+impl_inner!(WindowStyle: safe DWORD);
+impl WindowStyle {
+	pub const BORDER: WindowStyle = WindowStyle(WS_BORDER);
+	pub const CAPTION: WindowStyle = WindowStyle(WS_CAPTION);
+	pub const CHILD: WindowStyle = WindowStyle(WS_CHILD);
+	pub const CLIPCHILDREN: WindowStyle = WindowStyle(WS_CLIPCHILDREN);
+	pub const CLIPSIBLINGS: WindowStyle = WindowStyle(WS_CLIPSIBLINGS);
+	pub const DISABLED: WindowStyle = WindowStyle(WS_DISABLED);
+	pub const DLGFRAME: WindowStyle = WindowStyle(WS_DLGFRAME);
+	pub const HSCROLL: WindowStyle = WindowStyle(WS_HSCROLL);
+	pub const MAXIMIZE: WindowStyle = WindowStyle(WS_MAXIMIZE);
+	pub const MAXIMIZEBOX: WindowStyle = WindowStyle(WS_MAXIMIZEBOX);
+	pub const MINIMIZE: WindowStyle = WindowStyle(WS_MINIMIZE);
+	pub const MINIMIZEBOX: WindowStyle = WindowStyle(WS_MINIMIZEBOX);
+	pub const POPUP: WindowStyle = WindowStyle(WS_POPUP);
+	pub const SYSMENU: WindowStyle = WindowStyle(WS_SYSMENU);
+	pub const THICKFRAME: WindowStyle = WindowStyle(WS_THICKFRAME);
+	pub const VISIBLE: WindowStyle = WindowStyle(WS_VISIBLE);
+	pub const VSCROLL: WindowStyle = WindowStyle(WS_VSCROLL);
+
+	pub const OVERLAPPEDWINDOW: WindowStyle = WindowStyle(WS_OVERLAPPEDWINDOW);
+}
+impl WindowStyle {
+	/// Returns if all the given styles are present.
+	pub const fn contains(self, style: WindowStyle) -> bool {
+		self.0 & style.0 == style.0
+	}
+	pub const fn is_popup(self) -> bool {
+		self.0 & WS_POPUP != 0
+	}
+	pub const fn is_child(self) -> bool {
+		self.0 & WS_CHILD != 0
+	}
+	pub const fn is_visible(self) -> bool {
+		self.0 & WS_VISIBLE != 0
+	}
+	/// Returns if the window has neither a caption nor a sizing border.
+	pub const fn is_borderless(self) -> bool {
+		self.0 & (WS_CAPTION | WS_THICKFRAME) == 0
+	}
+}
+impl ops::BitOr for WindowStyle {
+	type Output = WindowStyle;
+	fn bitor(self, rhs: WindowStyle) -> WindowStyle {
+		WindowStyle(self.0 | rhs.0)
+	}
+}
+impl ops::BitAnd for WindowStyle {
+	type Output = WindowStyle;
+	fn bitand(self, rhs: WindowStyle) -> WindowStyle {
+		WindowStyle(self.0 & rhs.0)
+	}
+}
+impl ops::Not for WindowStyle {
+	type Output = WindowStyle;
+	fn not(self) -> WindowStyle {
+		WindowStyle(!self.0)
+	}
+}
+
+static STYLE_NAMES: [(DWORD, &str); 16] = [
+	(WS_POPUP, "POPUP"),
+	(WS_CHILD, "CHILD"),
+	(WS_MINIMIZE, "MINIMIZE"),
+	(WS_VISIBLE, "VISIBLE"),
+	(WS_DISABLED, "DISABLED"),
+	(WS_CLIPSIBLINGS, "CLIPSIBLINGS"),
+	(WS_CLIPCHILDREN, "CLIPCHILDREN"),
+	(WS_MAXIMIZE, "MAXIMIZE"),
+	(WS_BORDER, "BORDER"),
+	(WS_DLGFRAME, "DLGFRAME"),
+	(WS_VSCROLL, "VSCROLL"),
+	(WS_HSCROLL, "HSCROLL"),
+	(WS_SYSMENU, "SYSMENU"),
+	(WS_THICKFRAME, "THICKFRAME"),
+	(WS_MINIMIZEBOX, "MINIMIZEBOX"),
+	(WS_MAXIMIZEBOX, "MAXIMIZEBOX"),
+];
+
+impl fmt::Debug for WindowStyle {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		debug_flags(f, "WindowStyle", self.0, &STYLE_NAMES)
+	}
+}
+
+//----------------------------------------------------------------
+
+/// Extended window styles.
+///
+/// See [Extended Window Styles](https://msdn.microsoft.com/en-us/library/windows/desktop/ff700543.aspx) for more information.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub struct WindowExStyle(DWORD);
+impl_inner!(WindowExStyle: safe DWORD);
+impl WindowExStyle {
+	pub const ACCEPTFILES: WindowExStyle = WindowExStyle(WS_EX_ACCEPTFILES);
+	pub const APPWINDOW: WindowExStyle = WindowExStyle(WS_EX_APPWINDOW);
+	pub const CLIENTEDGE: WindowExStyle = WindowExStyle(WS_EX_CLIENTEDGE);
+	pub const COMPOSITED: WindowExStyle = WindowExStyle(WS_EX_COMPOSITED);
+	pub const DLGMODALFRAME: WindowExStyle = WindowExStyle(WS_EX_DLGMODALFRAME);
+	pub const LAYERED: WindowExStyle = WindowExStyle(WS_EX_LAYERED);
+	pub const NOACTIVATE: WindowExStyle = WindowExStyle(WS_EX_NOACTIVATE);
+	pub const TOOLWINDOW: WindowExStyle = WindowExStyle(WS_EX_TOOLWINDOW);
+	pub const TOPMOST: WindowExStyle = WindowExStyle(WS_EX_TOPMOST);
+	pub const TRANSPARENT: WindowExStyle = WindowExStyle(WS_EX_TRANSPARENT);
+	pub const WINDOWEDGE: WindowExStyle = WindowExStyle(WS_EX_WINDOWEDGE);
+}
+impl WindowExStyle {
+	/// Returns if all the given styles are present.
+	pub const fn contains(self, style: WindowExStyle) -> bool {
+		self.0 & style.0 == style.0
+	}
+	pub const fn is_layered(self) -> bool {
+		self.0 & WS_EX_LAYERED != 0
+	}
+	pub const fn is_topmost(self) -> bool {
+		self.0 & WS_EX_TOPMOST != 0
+	}
+	pub const fn is_toolwindow(self) -> bool {
+		self.0 & WS_EX_TOOLWINDOW != 0
+	}
+	/// Returns if mouse input passes through the window.
+	pub const fn is_transparent(self) -> bool {
+		self.0 & WS_EX_TRANSPARENT != 0
+	}
+}
+impl ops::BitOr for WindowExStyle {
+	type Output = WindowExStyle;
+	fn bitor(self, rhs: WindowExStyle) -> WindowExStyle {
+		WindowExStyle(self.0 | rhs.0)
+	}
+}
+impl ops::BitAnd for WindowExStyle {
+	type Output = WindowExStyle;
+	fn bitand(self, rhs: WindowExStyle) -> WindowExStyle {
+		WindowExStyle(self.0 & rhs.0)
+	}
+}
+impl ops::Not for WindowExStyle {
+	type Output = WindowExStyle;
+	fn not(self) -> WindowExStyle {
+		WindowExStyle(!self.0)
+	}
+}
+
+static EXSTYLE_NAMES: [(DWORD, &str); 11] = [
+	(WS_EX_DLGMODALFRAME, "DLGMODALFRAME"),
+	(WS_EX_TOPMOST, "TOPMOST"),
+	(WS_EX_ACCEPTFILES, "ACCEPTFILES"),
+	(WS_EX_TRANSPARENT, "TRANSPARENT"),
+	(WS_EX_TOOLWINDOW, "TOOLWINDOW"),
+	(WS_EX_WINDOWEDGE, "WINDOWEDGE"),
+	(WS_EX_CLIENTEDGE, "CLIENTEDGE"),
+	(WS_EX_APPWINDOW, "APPWINDOW"),
+	(WS_EX_LAYERED, "LAYERED"),
+	(WS_EX_COMPOSITED, "COMPOSITED"),
+	(WS_EX_NOACTIVATE, "NOACTIVATE"),
+];
+
+impl fmt::Debug for WindowExStyle {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		debug_flags(f, "WindowExStyle", self.0, &EXSTYLE_NAMES)
+	}
+}
+
+//----------------------------------------------------------------
+
+fn debug_flags(f: &mut fmt::Formatter, ty: &str, flags: DWORD, names: &[(DWORD, &str)]) -> fmt::Result {
+	f.write_str(ty)?;
+	f.write_str("(")?;
+	let mut rest = flags;
+	let mut first = true;
+	for &(flag, name) in names {
+		if flags & flag == flag {
+			if !first {
+				f.write_str(" | ")?;
+			}
+			f.write_str(name)?;
+			rest &= !flag;
+			first = false;
+		}
+	}
+	if rest != 0 || first {
+		if !first {
+			f.write_str(" | ")?;
+		}
+		write!(f, "{:#x}", rest)?;
+	}
+	f.write_str(")")
+}
+
+#[test]
+fn test_debug_styles() {
+	let style = WindowStyle::POPUP | WindowStyle::VISIBLE;
+	assert_eq!(format!("{:?}", style), "WindowStyle(POPUP | VISIBLE)");
+	assert!(style.is_popup() && !style.is_child() && style.is_borderless());
+	let exstyle = WindowExStyle::LAYERED | WindowExStyle::TOPMOST | WindowExStyle::from(0x80000000);
+	assert_eq!(format!("{:?}", exstyle), "WindowExStyle(TOPMOST | LAYERED | 0x80000000)");
+	assert_eq!(format!("{:?}", WindowExStyle::default()), "WindowExStyle(0x0)");
+}
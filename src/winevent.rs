@@ -0,0 +1,249 @@
+/*!
+Windows event hooks.
+
+Notifies about accessibility events such as the foreground window changing or the keyboard focus moving.
+
+Just like the [hook module](../hook/index.html) the callbacks are context-less and are invoked from the message loop of the thread which registered the hook.
+The registering thread must run a message loop for the callbacks to be invoked.
+
+# Examples
+
+Use the provided `win_event_hook!` to create the hooks instead of implementing the trait manually.
+
+```
+# #[macro_use] extern crate external; fn main() {
+use external::winevent;
+
+win_event_hook! {
+    /// A function with the given name which takes the range of events to hook is created.
+    /// This function registers the hook and returns the registration result.
+    pub fn foreground_changed(event: &external::winevent::WinEvent) {
+        println!("foreground: {:?}", event.window);
+    }
+}
+
+let _hook = foreground_changed(winevent::EVENT_SYSTEM_FOREGROUND, winevent::EVENT_SYSTEM_FOREGROUND);
+# }
+```
+
+Alternatively register a closure with [`WinEventHook::register`], the closure is kept in thread local storage of the registering thread.
+
+```no_run
+use external::winevent::{self, WinEventHook};
+
+let _hook = WinEventHook::register((winevent::EVENT_SYSTEM_MINIMIZESTART, winevent::EVENT_SYSTEM_MINIMIZEEND), |event, window, _, _| {
+    println!("{:#x}: {:?}", event, window);
+}).unwrap();
+external::wndclass::run();
+```
+
+# Message pump
+
+Use [`run`](crate::wndclass::run), [`pump_until`](crate::wndclass::pump_until) or [`pump_while`](crate::wndclass::pump_while) on the registering thread,
+they sleep until a message arrives.
+!*/
+
+use std::{panic, ptr};
+use std::cell::RefCell;
+use crate::winapi::*;
+use crate::error::ErrorCode;
+use crate::thread::ThreadId;
+use crate::window::Window;
+use crate::FromInner;
+
+pub use crate::winapi::{
+	EVENT_SYSTEM_FOREGROUND,
+	EVENT_SYSTEM_MINIMIZESTART,
+	EVENT_SYSTEM_MINIMIZEEND,
+	EVENT_OBJECT_CREATE,
+	EVENT_OBJECT_DESTROY,
+	EVENT_OBJECT_SHOW,
+	EVENT_OBJECT_HIDE,
+	EVENT_OBJECT_FOCUS,
+	EVENT_OBJECT_LOCATIONCHANGE,
+	EVENT_OBJECT_NAMECHANGE,
+};
+
+/// Windows event callback arguments.
+///
+/// See [WinEventProc callback function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd373885.aspx) for more information.
+#[derive(Copy, Clone, Debug)]
+pub struct WinEvent {
+	/// The event that occurred, eg. `EVENT_SYSTEM_FOREGROUND`.
+	pub event: u32,
+	/// The window that generated the event.
+	pub window: Option<Window>,
+	/// The object associated with the event, `OBJID_WINDOW` for the window itself.
+	pub object_id: i32,
+	/// Whether the event was triggered by the object or one of its children, `CHILDID_SELF` for the object itself.
+	pub child_id: i32,
+	/// The thread that generated the event.
+	pub thread_id: ThreadId,
+	/// The time in milliseconds the event was generated.
+	pub time: u32,
+}
+
+/// User callbacks.
+pub trait WinEventHandler: Sized {
+	/// The callback to invoke.
+	fn invoke(event: &WinEvent);
+	/// Unsafe thunk to your Rust callback.
+	///
+	/// Panics are caught before they unwind into the system, the payload is kept for [`take_panic`](crate::hook::take_panic).
+	///
+	/// # Safety
+	///
+	/// Must only be called by the system as the `WINEVENTPROC` of a registered hook.
+	unsafe extern "system" fn thunk(_hook: HWINEVENTHOOK, event: DWORD, hwnd: HWND, object_id: LONG, child_id: LONG, thread_id: DWORD, time: DWORD) {
+		let event = WinEvent {
+			event,
+			window: if hwnd.is_null() { None } else { Some(Window::from_inner(hwnd)) },
+			object_id,
+			child_id,
+			thread_id: ThreadId::from_inner(thread_id),
+			time,
+		};
+		if let Err(payload) = panic::catch_unwind(|| Self::invoke(&event)) {
+			crate::hook::PANIC.with(|slot| *slot.borrow_mut() = Some(payload));
+		}
+	}
+	/// Registers the hook for the range of events, inclusive.
+	fn register(event_min: u32, event_max: u32) -> Result<WinEventHook, ErrorCode> {
+		unsafe {
+			let hook = SetWinEventHook(event_min, event_max, ptr::null_mut(), Some(Self::thunk), 0, 0, WINEVENT_OUTOFCONTEXT);
+			if hook.is_null() {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(WinEventHook(hook))
+			}
+		}
+	}
+}
+
+/// Setup a windows event hook callback.
+///
+/// See the [winevent module](winevent/index.html)'s documentation for more information.
+#[macro_export]
+macro_rules! win_event_hook {
+	(
+		$(#[$meta:meta])*
+		$vis:vis fn $name:ident($arg:ident: &$ty:ty) $body:tt
+	) => {
+		$(#[$meta])*
+		$vis fn $name(event_min: u32, event_max: u32) -> Result<$crate::winevent::WinEventHook, $crate::error::ErrorCode> {
+			enum T {}
+			impl $crate::winevent::WinEventHandler for T {
+				fn invoke($arg: &$ty) $body
+			}
+			<T as $crate::winevent::WinEventHandler>::register(event_min, event_max)
+		}
+	};
+}
+
+/// The windows event hook registration.
+///
+/// The hook is unhooked when this instance goes out of scope, it must be dropped on the registering thread.
+///
+/// See [SetWinEventHook function](https://msdn.microsoft.com/en-us/library/windows/desktop/dd373640.aspx) for more information.
+pub struct WinEventHook(HWINEVENTHOOK);
+impl WinEventHook {
+	/// Registers a closure for the range of events, inclusive.
+	///
+	/// The closure receives the event, the window that generated it, the object id and the child id.
+	/// The window is null for events not associated with a window.
+	/// It is invoked from the message loop of the registering thread.
+	/// Panics are caught, retrieve them with [`take_panic`](crate::hook::take_panic).
+	pub fn register<F>(range: (u32, u32), f: F) -> Result<WinEventHook, ErrorCode> where F: FnMut(u32, Window, i32, i32) + 'static {
+		let hook = unsafe { SetWinEventHook(range.0, range.1, ptr::null_mut(), Some(closure_thunk), 0, 0, WINEVENT_OUTOFCONTEXT) };
+		if hook.is_null() {
+			return Err(ErrorCode::last());
+		}
+		CLOSURES.with(|closures| closures.borrow_mut().push((hook, Some(Box::new(f)))));
+		Ok(WinEventHook(hook))
+	}
+	/// Returns the raw hook handle.
+	pub fn as_raw(&self) -> HWINEVENTHOOK {
+		self.0
+	}
+}
+impl Drop for WinEventHook {
+	fn drop(&mut self) {
+		unsafe {
+			UnhookWinEvent(self.0);
+		}
+		let f = CLOSURES.with(|closures| {
+			let mut closures = closures.borrow_mut();
+			closures.iter().position(|&(hook, _)| hook == self.0).map(|position| closures.remove(position))
+		});
+		// Drop the closure outside of the borrow
+		drop(f);
+	}
+}
+
+type Closure = Box<dyn FnMut(u32, Window, i32, i32)>;
+
+thread_local! {
+	static CLOSURES: RefCell<Vec<(HWINEVENTHOOK, Option<Closure>)>> = RefCell::new(Vec::new());
+}
+
+unsafe extern "system" fn closure_thunk(hook: HWINEVENTHOOK, event: DWORD, hwnd: HWND, object_id: LONG, child_id: LONG, _thread_id: DWORD, _time: DWORD) {
+	// Take the closure out while calling it, allowing it to register or unregister hooks
+	let f = CLOSURES.with(|closures| {
+		closures.borrow_mut().iter_mut().find(|(other, _)| *other == hook).and_then(|(_, f)| f.take())
+	});
+	let mut f = match f {
+		Some(f) => f,
+		None => return,
+	};
+	let result = panic::catch_unwind(panic::AssertUnwindSafe(|| f(event, Window::from_inner(hwnd), object_id, child_id)));
+	// Put it back unless it was unhooked in the meantime
+	let f = CLOSURES.with(|closures| {
+		match closures.borrow_mut().iter_mut().find(|(other, _)| *other == hook) {
+			Some((_, slot)) => {
+				*slot = Some(f);
+				None
+			},
+			None => Some(f),
+		}
+	});
+	drop(f);
+	if let Err(payload) = result {
+		crate::hook::PANIC.with(|slot| *slot.borrow_mut() = Some(payload));
+	}
+}
+
+#[test]
+fn test_register_closure() {
+	use std::rc::Rc;
+	use crate::wndclass::{pump_once, WindowBuilder};
+
+	let created = Rc::new(RefCell::new(Vec::new()));
+	let hook = WinEventHook::register((EVENT_OBJECT_CREATE, EVENT_OBJECT_CREATE), {
+		let created = created.clone();
+		move |_, window, object_id, _| if object_id == OBJID_WINDOW {
+			created.borrow_mut().push(window);
+		}
+	}).unwrap();
+	let window = WindowBuilder::new().create().unwrap();
+	pump_once();
+	assert!(created.borrow().contains(&window));
+	let _ = window.destroy();
+	drop(hook);
+	CLOSURES.with(|closures| assert!(closures.borrow().is_empty()));
+}
+
+#[test]
+fn test_thunk_panic() {
+	enum Panicky {}
+	impl WinEventHandler for Panicky {
+		fn invoke(_event: &WinEvent) {
+			panic!("win event panic");
+		}
+	}
+	unsafe {
+		Panicky::thunk(ptr::null_mut(), EVENT_SYSTEM_FOREGROUND, ptr::null_mut(), 0, 0, 0, 0);
+	}
+	let payload = crate::hook::take_panic().expect("panic was not caught");
+	assert_eq!(payload.downcast_ref::<&str>(), Some(&"win event panic"));
+}
@@ -2,9 +2,16 @@
 !*/
 
 use std::{ptr, mem, panic};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 use crate::winapi::*;
-use crate::window::Window;
+use crate::window::{Window, WindowStyle, WindowExStyle, SetPosFlags};
+use crate::draw::Canvas;
+use crate::snap::Color;
 use crate::error::ErrorCode;
+use crate::util::timeout_millis;
 use crate::{Result, FromInner, IntoInner};
 
 pub static CLASS_NAME: [u16; 6] = wide_str!('C' 'l' 'a' 's' 's' 0);
@@ -99,6 +106,483 @@ pub trait WndClass {
 	}
 }
 
+//----------------------------------------------------------------
+
+/// Window procedure closure used by [`WindowBuilder`].
+///
+/// Receives the window, message, `wParam` and `lParam`, returning `None` passes the message on to `DefWindowProcW`.
+pub type WndProcFn = dyn FnMut(Window, UINT, WPARAM, LPARAM) -> Option<LRESULT>;
+
+struct WndProcState {
+	f: RefCell<Box<WndProcFn>>,
+	depth: Cell<u32>,
+	destroyed: Cell<bool>,
+}
+
+thread_local! {
+	// Hands the window procedure state from `WindowBuilder::create` over to `WM_NCCREATE`.
+	static PENDING: Cell<*mut WndProcState> = const { Cell::new(ptr::null_mut()) };
+}
+
+static BUILDER_CLASS_NAME: [u16; 15] = wide_str!('W' 'i' 'n' 'd' 'o' 'w' 'B' 'u' 'i' 'l' 'd' 'e' 'r' '0' 0);
+
+/// Creates a window with a closure as its window procedure.
+///
+/// The window class is registered on first use with a window procedure which forwards to the closure.
+/// The closure is stored in `GWLP_USERDATA` on `WM_NCCREATE` and dropped on `WM_NCDESTROY`,
+/// don't overwrite the user data of windows created this way.
+///
+/// Messages sent to the window while the closure is already running, eg. by calling `DestroyWindow` from within the closure,
+/// are not passed to the closure again but handled by `DefWindowProcW` instead.
+/// Prefer posting messages such as [`Window::close`] from within the closure.
+///
+/// ```no_run
+/// use external::wndclass::{WindowBuilder, pump_thread};
+///
+/// let window = WindowBuilder::new()
+///     .title("Hello")
+///     .size(400, 300)
+///     .wndproc(|_window, msg, _wparam, _lparam| {
+///         if msg == 0x0002/*WM_DESTROY*/ {
+///             external::wndclass::post_quit_message(0);
+///         }
+///         None
+///     })
+///     .create()
+///     .unwrap();
+/// window.show(5/*SW_SHOW*/);
+/// pump_thread();
+/// ```
+pub struct WindowBuilder {
+	class_name: Vec<u16>,
+	title: Vec<u16>,
+	x: i32,
+	y: i32,
+	width: i32,
+	height: i32,
+	style: WindowStyle,
+	exstyle: WindowExStyle,
+	parent: Option<Window>,
+	wndproc: Option<Box<WndProcFn>>,
+}
+impl WindowBuilder {
+	/// Creates a builder for an overlapped window with default position and size.
+	pub fn new() -> WindowBuilder {
+		WindowBuilder {
+			class_name: BUILDER_CLASS_NAME.to_vec(),
+			title: vec![0],
+			x: CW_USEDEFAULT,
+			y: CW_USEDEFAULT,
+			width: CW_USEDEFAULT,
+			height: CW_USEDEFAULT,
+			style: WindowStyle::OVERLAPPEDWINDOW,
+			exstyle: WindowExStyle::default(),
+			parent: None,
+			wndproc: None,
+		}
+	}
+	/// Sets the name of the window class, registered automatically when the window is created.
+	///
+	/// If a class with this name was already registered by other code its window procedure is used instead of the closure.
+	pub fn class_name(mut self, class_name: &str) -> WindowBuilder {
+		self.class_name = class_name.encode_utf16().chain(Some(0)).collect();
+		self
+	}
+	/// Sets the window title.
+	pub fn title(mut self, title: &str) -> WindowBuilder {
+		self.title = title.encode_utf16().chain(Some(0)).collect();
+		self
+	}
+	/// Sets the position of the window, in client coordinates of the parent for child windows.
+	pub fn position(mut self, x: i32, y: i32) -> WindowBuilder {
+		self.x = x;
+		self.y = y;
+		self
+	}
+	/// Sets the size of the window including its frame.
+	pub fn size(mut self, width: i32, height: i32) -> WindowBuilder {
+		self.width = width;
+		self.height = height;
+		self
+	}
+	/// Sets the window style, defaults to `WindowStyle::OVERLAPPEDWINDOW`.
+	pub fn style(mut self, style: WindowStyle) -> WindowBuilder {
+		self.style = style;
+		self
+	}
+	/// Sets the extended window style.
+	pub fn exstyle(mut self, exstyle: WindowExStyle) -> WindowBuilder {
+		self.exstyle = exstyle;
+		self
+	}
+	/// Sets the parent or owner window.
+	pub fn parent(mut self, parent: Window) -> WindowBuilder {
+		self.parent = Some(parent);
+		self
+	}
+	/// Sets the window procedure closure.
+	///
+	/// Return `Some(result)` to handle the message or `None` to pass it on to `DefWindowProcW`.
+	///
+	/// The closure is not reentered: messages sent to the window while the closure is running,
+	/// eg. `WM_DESTROY` when it calls `DestroyWindow` or messages sent by `SetWindowPos`, go straight to `DefWindowProcW`.
+	/// Post a message instead to handle it in the closure.
+	///
+	/// A panic in the closure is caught and the message is passed on to `DefWindowProcW`,
+	/// the payload can be retrieved with [`take_panic`](crate::hook::take_panic).
+	pub fn wndproc<F>(mut self, f: F) -> WindowBuilder where F: FnMut(Window, UINT, WPARAM, LPARAM) -> Option<LRESULT> + 'static {
+		self.wndproc = Some(Box::new(f));
+		self
+	}
+	/// Registers the window class if needed and creates the window.
+	///
+	/// See [CreateWindowEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms632680.aspx) for more information.
+	pub fn create(self) -> Result<Window> {
+		unsafe {
+			let instance = crate::module::image_base();
+			let class = WNDCLASSEXW {
+				cbSize: mem::size_of::<WNDCLASSEXW>() as u32,
+				style: CS_VREDRAW | CS_HREDRAW,
+				lpfnWndProc: Some(builder_wnd_proc),
+				cbClsExtra: 0,
+				cbWndExtra: 0,
+				hInstance: instance,
+				hIcon: ptr::null_mut(),
+				hCursor: LoadCursorW(ptr::null_mut(), IDC_ARROW),
+				hbrBackground: (COLOR_WINDOW + 1) as HBRUSH,
+				lpszMenuName: ptr::null(),
+				lpszClassName: self.class_name.as_ptr(),
+				hIconSm: ptr::null_mut(),
+			};
+			if RegisterClassExW(&class) == 0 {
+				let err = ErrorCode::last();
+				if err != ErrorCode::from(ERROR_CLASS_ALREADY_EXISTS) {
+					return Err(err);
+				}
+			}
+			let state = self.wndproc.map(|f| Box::into_raw(Box::new(WndProcState {
+				f: RefCell::new(f),
+				depth: Cell::new(0),
+				destroyed: Cell::new(false),
+			})));
+			let previous = PENDING.with(|pending| pending.replace(state.unwrap_or(ptr::null_mut())));
+			let hwnd = CreateWindowExW(
+				self.exstyle.into_inner(),
+				self.class_name.as_ptr(),
+				self.title.as_ptr(),
+				self.style.into_inner(),
+				self.x, self.y, self.width, self.height,
+				self.parent.map(|parent| parent.into_inner()).unwrap_or(ptr::null_mut()),
+				ptr::null_mut(),
+				instance,
+				ptr::null_mut(),
+			);
+			// Creation failed before WM_NCCREATE took ownership of the closure
+			let leftover = PENDING.with(|pending| pending.replace(previous));
+			if !leftover.is_null() {
+				drop(Box::from_raw(leftover));
+			}
+			if hwnd.is_null() {
+				Err(ErrorCode::last())
+			}
+			else {
+				Ok(Window::from_inner(hwnd))
+			}
+		}
+	}
+}
+impl Default for WindowBuilder {
+	fn default() -> WindowBuilder {
+		WindowBuilder::new()
+	}
+}
+
+#[cfg(target_pointer_width = "64")]
+unsafe fn get_user_data(hwnd: HWND) -> *mut WndProcState {
+	GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut WndProcState
+}
+#[cfg(target_pointer_width = "32")]
+unsafe fn get_user_data(hwnd: HWND) -> *mut WndProcState {
+	GetWindowLongW(hwnd, GWLP_USERDATA) as *mut WndProcState
+}
+#[cfg(target_pointer_width = "64")]
+unsafe fn set_user_data(hwnd: HWND, state: *mut WndProcState) {
+	SetWindowLongPtrW(hwnd, GWLP_USERDATA, state as isize);
+}
+#[cfg(target_pointer_width = "32")]
+unsafe fn set_user_data(hwnd: HWND, state: *mut WndProcState) {
+	SetWindowLongW(hwnd, GWLP_USERDATA, state as i32);
+}
+
+#[allow(non_snake_case)]
+unsafe extern "system" fn builder_wnd_proc(hwnd: HWND, msg: UINT, wParam: WPARAM, lParam: LPARAM) -> LRESULT {
+	if msg == WM_NCCREATE {
+		let state = PENDING.with(|pending| pending.replace(ptr::null_mut()));
+		if !state.is_null() {
+			set_user_data(hwnd, state);
+		}
+	}
+	let state = get_user_data(hwnd);
+	if state.is_null() {
+		return DefWindowProcW(hwnd, msg, wParam, lParam);
+	}
+	(*state).depth.set((*state).depth.get() + 1);
+	let result = match (*state).f.try_borrow_mut() {
+		// A panic cannot unwind across the system, store it and let DefWindowProcW handle the message
+		Ok(mut f) => match panic::catch_unwind(panic::AssertUnwindSafe(|| f(Window::from_inner(hwnd), msg, wParam, lParam))) {
+			Ok(result) => result,
+			Err(payload) => {
+				crate::hook::PANIC.with(|slot| *slot.borrow_mut() = Some(payload));
+				None
+			},
+		},
+		// Reentrant message, the closure is already running further up the stack
+		Err(_) => None,
+	};
+	let result = match result {
+		Some(result) => result,
+		None => DefWindowProcW(hwnd, msg, wParam, lParam),
+	};
+	if msg == WM_NCDESTROY {
+		set_user_data(hwnd, ptr::null_mut());
+		(*state).destroyed.set(true);
+	}
+	// The outermost call frees the state once the window is gone
+	(*state).depth.set((*state).depth.get() - 1);
+	if (*state).destroyed.get() && (*state).depth.get() == 0 {
+		drop(Box::from_raw(state));
+	}
+	result
+}
+
+//----------------------------------------------------------------
+
+/// Message-only window.
+///
+/// Created by [`message_window`], the window is destroyed and its class unregistered on drop.
+/// Must be dropped on the thread which created it.
+#[derive(Debug)]
+pub struct MessageWindow {
+	window: Window,
+	class_name: Vec<u16>,
+}
+impl MessageWindow {
+	/// Returns the window handle, eg. for `RegisterHotKey` or `RegisterRawInputDevices`.
+	pub fn window(&self) -> Window {
+		self.window
+	}
+}
+impl Drop for MessageWindow {
+	fn drop(&mut self) {
+		unsafe {
+			DestroyWindow(self.window.into_inner());
+			UnregisterClassW(self.class_name.as_ptr(), crate::module::image_base());
+		}
+	}
+}
+
+/// Creates a message-only window with a closure as its window procedure.
+///
+/// Message-only windows are invisible, not enumerated and only receive messages sent or posted to them directly.
+/// The window procedure closure behaves the same as for [`WindowBuilder::wndproc`].
+///
+/// See [Message-Only Windows](https://msdn.microsoft.com/en-us/library/windows/desktop/ms632599.aspx#message_only) for more information.
+pub fn message_window<F>(f: F) -> Result<MessageWindow> where F: FnMut(Window, UINT, WPARAM, LPARAM) -> Option<LRESULT> + 'static {
+	static COUNTER: AtomicUsize = AtomicUsize::new(0);
+	let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+	let class_name = format!("MessageWindow{}", id);
+	let window = WindowBuilder::new()
+		.class_name(&class_name)
+		.style(WindowStyle::default())
+		.parent(unsafe { Window::from_inner(HWND_MESSAGE) })
+		.wndproc(f)
+		.create()?;
+	let class_name = class_name.encode_utf16().chain(Some(0)).collect();
+	Ok(MessageWindow { window, class_name })
+}
+
+//----------------------------------------------------------------
+
+struct BackBuffer {
+	hdc: HDC,
+	hbmp: HBITMAP,
+	old: HGDIOBJ,
+	width: i32,
+	height: i32,
+}
+impl BackBuffer {
+	fn new(width: i32, height: i32) -> Result<BackBuffer> {
+		unsafe {
+			let screen = GetDC(ptr::null_mut());
+			if screen.is_null() {
+				return Err(ErrorCode::last());
+			}
+			let hdc = CreateCompatibleDC(screen);
+			let hbmp = if hdc.is_null() { ptr::null_mut() } else { CreateCompatibleBitmap(screen, width, height) };
+			let err = ErrorCode::last();
+			ReleaseDC(ptr::null_mut(), screen);
+			if hbmp.is_null() {
+				if !hdc.is_null() {
+					DeleteDC(hdc);
+				}
+				return Err(err);
+			}
+			let old = SelectObject(hdc, hbmp as HGDIOBJ);
+			Ok(BackBuffer { hdc, hbmp, old, width, height })
+		}
+	}
+}
+impl Drop for BackBuffer {
+	fn drop(&mut self) {
+		unsafe {
+			SelectObject(self.hdc, self.old);
+			DeleteObject(self.hbmp as HGDIOBJ);
+			DeleteDC(self.hdc);
+		}
+	}
+}
+
+/// Click-through overlay window tracking a target window.
+///
+/// The overlay is a topmost, layered, transparent tool window covering the visible frame of the target.
+/// Black is the transparent color key, anything drawn in black is not visible.
+///
+/// Drawing is double buffered: draw on the canvas returned by [`begin_frame`](Overlay::begin_frame) and present it with [`end_frame`](Overlay::end_frame).
+/// Call [`follow`](Overlay::follow) every tick to keep the overlay positioned over the target.
+///
+/// The overlay window belongs to the thread which created it, this thread must pump messages, eg. with [`pump_once`].
+/// The overlay window is destroyed on drop or when the target window goes away.
+pub struct Overlay {
+	window: Window,
+	target: Window,
+	destroyed: Rc<Cell<bool>>,
+	back_buffer: Option<BackBuffer>,
+	canvas: Option<Canvas>,
+}
+impl Overlay {
+	/// Creates an overlay window over the target window.
+	pub fn over(target: Window) -> Result<Overlay> {
+		let rect = target.extended_frame_bounds().or_else(|_| target.rect())?;
+		let destroyed = Rc::new(Cell::new(false));
+		let window = {
+			let destroyed = destroyed.clone();
+			WindowBuilder::new()
+				.class_name("Overlay")
+				.style(WindowStyle::POPUP)
+				.exstyle(WindowExStyle::TOPMOST | WindowExStyle::LAYERED | WindowExStyle::TRANSPARENT | WindowExStyle::TOOLWINDOW | WindowExStyle::NOACTIVATE)
+				.position(rect.left, rect.top)
+				.size(rect.width, rect.height)
+				.wndproc(move |_, msg, _, _| match msg {
+					WM_NCHITTEST => Some(HTTRANSPARENT),
+					WM_ERASEBKGND => Some(1),
+					WM_DESTROY => {
+						destroyed.set(true);
+						None
+					},
+					_ => None,
+				})
+				.create()?
+		};
+		let overlay = Overlay { window, target, destroyed, back_buffer: None, canvas: None };
+		overlay.window.set_color_key(Color::default())?;
+		overlay.window.show(SW_SHOWNOACTIVATE);
+		Ok(overlay)
+	}
+	/// Returns the overlay window.
+	pub fn window(&self) -> Window {
+		self.window
+	}
+	/// Returns the target window.
+	pub fn target(&self) -> Window {
+		self.target
+	}
+	/// Returns if the overlay window still exists.
+	pub fn is_alive(&self) -> bool {
+		!self.destroyed.get()
+	}
+	/// Repositions the overlay over the target window, call this every tick.
+	///
+	/// The overlay is hidden while the target is minimized or hidden.
+	/// Returns `Ok(false)` once the target or the overlay window has been destroyed, the overlay window is destroyed as well.
+	pub fn follow(&mut self) -> Result<bool> {
+		if self.destroyed.get() || !self.target.valid() {
+			self.destroy();
+			return Ok(false);
+		}
+		if self.target.is_minimized() || !self.target.is_visible() {
+			self.window.show(SW_HIDE);
+			return Ok(true);
+		}
+		let rect = self.target.extended_frame_bounds().or_else(|_| self.target.rect())?;
+		let flags = SetPosFlags::new().no_activate().show_window().topmost();
+		self.window.set_pos(rect.left, rect.top, rect.width, rect.height, flags)?;
+		Ok(true)
+	}
+	/// Starts a new frame, returns the cleared canvas to draw on.
+	///
+	/// The canvas has the size of the overlay window.
+	pub fn begin_frame(&mut self) -> Result<&Canvas> {
+		let (width, height) = self.window.client_area()?;
+		let width = width.max(1);
+		let height = height.max(1);
+		if self.back_buffer.as_ref().map(|back| back.width != width || back.height != height).unwrap_or(true) {
+			// Drop the canvas before the device context it refers to
+			self.canvas = None;
+			self.back_buffer = None;
+			let back = BackBuffer::new(width, height)?;
+			self.canvas = Some(unsafe { Canvas::from_hdc(back.hdc) });
+			self.back_buffer = Some(back);
+		}
+		let back = self.back_buffer.as_ref().unwrap();
+		unsafe {
+			let rect = RECT { left: 0, top: 0, right: width, bottom: height };
+			FillRect(back.hdc, &rect, GetStockObject(BLACK_BRUSH as i32) as HBRUSH);
+		}
+		Ok(self.canvas.as_ref().unwrap())
+	}
+	/// Presents the frame drawn since [`begin_frame`](Overlay::begin_frame).
+	pub fn end_frame(&mut self) -> Result<()> {
+		let back = match &self.back_buffer {
+			Some(back) => back,
+			None => return Ok(()),
+		};
+		unsafe {
+			let hwnd = self.window.into_inner();
+			let hdc = GetDC(hwnd);
+			if hdc.is_null() {
+				return Err(ErrorCode::last());
+			}
+			let success = BitBlt(hdc, 0, 0, back.width, back.height, back.hdc, 0, 0, SRCCOPY) != FALSE;
+			let err = ErrorCode::last();
+			ReleaseDC(hwnd, hdc);
+			if success { Ok(()) } else { Err(err) }
+		}
+	}
+	fn destroy(&mut self) {
+		self.canvas = None;
+		self.back_buffer = None;
+		if !self.destroyed.get() {
+			unsafe {
+				DestroyWindow(self.window.into_inner());
+			}
+		}
+	}
+}
+impl Drop for Overlay {
+	fn drop(&mut self) {
+		self.destroy();
+	}
+}
+
+/// Posts `WM_QUIT` to the message queue of the current thread.
+///
+/// See [PostQuitMessage function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms644945.aspx) for more information.
+pub fn post_quit_message(exit_code: i32) {
+	unsafe {
+		PostQuitMessage(exit_code);
+	}
+}
+
 pub fn pump_once() -> bool {
 	unsafe {
 		let mut msg: MSG = mem::zeroed();
@@ -120,8 +604,150 @@ pub fn pump_thread() {
 	}
 }
 
+/// Result of [`pump_until`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PumpResult {
+	/// `WM_QUIT` was received with the given exit code.
+	Quit(i32),
+	/// The deadline was reached.
+	Timeout,
+}
+
+// Dispatches all pending messages, returns the exit code if `WM_QUIT` was received.
+fn dispatch_pending() -> Option<i32> {
+	unsafe {
+		let mut msg: MSG = mem::zeroed();
+		while PeekMessageW(&mut msg, ptr::null_mut(), 0, 0, PM_REMOVE) != FALSE {
+			if msg.message == WM_QUIT {
+				return Some(msg.wParam as i32);
+			}
+			TranslateMessage(&msg);
+			DispatchMessageW(&msg);
+		}
+		None
+	}
+}
+
+// Sleeps until a message arrives or the timeout elapses, returns false on timeout.
+fn wait_message(timeout: Option<Duration>) -> bool {
+	unsafe {
+		MsgWaitForMultipleObjectsEx(0, ptr::null(), timeout_millis(timeout), QS_ALLINPUT, MWMO_INPUTAVAILABLE) != WAIT_TIMEOUT
+	}
+}
+
+/// Pumps messages on the current thread until `WM_QUIT` is received or the deadline is reached.
+///
+/// A deadline of `None` pumps until `WM_QUIT`.
+/// The thread sleeps while there are no messages to process.
+///
+/// See [MsgWaitForMultipleObjectsEx function](https://msdn.microsoft.com/en-us/library/windows/desktop/ms684245.aspx) for more information.
+pub fn pump_until(deadline: Option<Instant>) -> PumpResult {
+	loop {
+		if let Some(exit_code) = dispatch_pending() {
+			return PumpResult::Quit(exit_code);
+		}
+		let timeout = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+		if timeout == Some(Duration::from_secs(0)) || !wait_message(timeout) {
+			return PumpResult::Timeout;
+		}
+	}
+}
+
+/// Pumps messages on the current thread while the condition holds.
+///
+/// The condition is checked after every batch of dispatched messages, it is meant to be changed by the message handlers or hooks running on this thread.
+/// Returns the exit code if `WM_QUIT` was received or `None` when the condition no longer holds.
+pub fn pump_while<F: FnMut() -> bool>(mut cond: F) -> Option<i32> {
+	loop {
+		if let Some(exit_code) = dispatch_pending() {
+			return Some(exit_code);
+		}
+		if !cond() {
+			return None;
+		}
+		wait_message(None);
+	}
+}
+
+/// Pumps messages on the current thread until `WM_QUIT` is received and returns its exit code.
+///
+/// See [`post_quit_message`] to end the loop from a message handler or hook.
+pub fn run() -> i32 {
+	loop {
+		if let PumpResult::Quit(exit_code) = pump_until(None) {
+			return exit_code;
+		}
+	}
+}
+
 pub fn sleep(ms: u32) {
 	unsafe {
 		Sleep(ms);
 	}
 }
+
+#[test]
+fn test_message_window() {
+	use std::rc::Rc;
+	let received = Rc::new(Cell::new(0));
+	let window = {
+		let received = received.clone();
+		message_window(move |_, msg, wparam, _| {
+			if msg == WM_USER {
+				received.set(wparam);
+				Some(42)
+			}
+			else {
+				None
+			}
+		}).unwrap()
+	};
+	assert_eq!(window.window().send_message(WM_USER, 13, 0), Ok(42));
+	assert_eq!(received.get(), 13);
+	// The closure is dropped together with the window
+	drop(window);
+	assert_eq!(Rc::strong_count(&received), 1);
+}
+
+#[test]
+fn test_wndproc_panic() {
+	let window = message_window(|_, msg, _, _| {
+		if msg == WM_USER {
+			panic!("wndproc panic");
+		}
+		None
+	}).unwrap();
+	assert_eq!(window.window().send_message(WM_USER, 0, 0), Ok(0));
+	let payload = crate::hook::take_panic().expect("panic was not caught");
+	assert_eq!(payload.downcast_ref::<&str>(), Some(&"wndproc panic"));
+}
+
+#[test]
+fn test_pump() {
+	let start = Instant::now();
+	assert_eq!(pump_until(Some(start + Duration::from_millis(20))), PumpResult::Timeout);
+	assert!(start.elapsed() >= Duration::from_millis(20));
+	let mut count = 0;
+	assert_eq!(pump_while(|| { count += 1; count < 3 && post_message_self() }), None);
+	post_quit_message(7);
+	assert_eq!(run(), 7);
+}
+
+#[cfg(test)]
+fn post_message_self() -> bool {
+	unsafe { PostThreadMessageW(GetCurrentThreadId(), WM_USER, 0, 0) != FALSE }
+}
+
+#[test]
+fn test_overlay() {
+	let target = WindowBuilder::new().size(300, 200).create().unwrap();
+	target.show(SW_SHOWNOACTIVATE);
+	let mut overlay = Overlay::over(target).unwrap();
+	assert!(overlay.follow().unwrap());
+	let canvas = overlay.begin_frame().unwrap();
+	canvas.line(0, 0, 100, 100, Color { red: 255, ..Color::default() }, 2).unwrap();
+	overlay.end_frame().unwrap();
+	target.destroy().unwrap();
+	assert!(!overlay.follow().unwrap());
+	assert!(!overlay.window().valid());
+}
@@ -16,15 +16,159 @@ fn test_query_working_set_ex() {
 #[test]
 fn test_vm_allocations() {
 	let process = Process::current();
-	let mut buffer = [0; 256];
 	println!();
-	for (address, _, ty) in process.vm_allocations() {
-		if ty == MemoryType::IMAGE {
-			let s = match process.get_mapped_file_name_wide(address, &mut buffer) {
-				Ok(s) => String::from_utf16_lossy(s),
-				Err(err) => err.to_string(),
-			};
-			println!("{:#x} {}", address, s);
+	for allocation in process.vm_allocations() {
+		if allocation.ty == MemoryType::IMAGE {
+			println!("{:#x} {:?}", allocation.base, allocation.file_name);
 		}
 	}
 }
+
+#[test]
+fn test_memory_map() {
+	let process = Process::current();
+	let local = 42u32;
+	let address = IntPtr::from_usize(&local as *const _ as usize);
+	let region = process.memory_map().find(|region| region.range.contains(&address)).unwrap();
+	assert_eq!(region.state, RegionState::Commit);
+	assert!(region.protect.is_writable());
+	assert_eq!(region.ty, MemoryType::PRIVATE);
+}
+
+#[test]
+fn test_vm_write_checked() {
+	let process = Process::current();
+	let page = process.vm_alloc(IntPtr::NULL, 0x1000, AllocType::COMMIT, Protect::READONLY).unwrap();
+	let ptr = page.cast::<u32>();
+	// ERROR_WRITE_PROTECT
+	assert_eq!(process.vm_write_checked(ptr, &42), Err(ErrorCode::from(19)));
+	process.vm_protect(page, 0x1000, Protect::READWRITE).unwrap();
+	process.vm_write_checked(ptr, &42).unwrap();
+	assert_eq!(process.vm_read(ptr), Ok(42));
+	process.vm_free(page, 0, FreeType::RELEASE).unwrap();
+}
+
+#[test]
+fn test_process_times() {
+	let times = Process::current().times().unwrap();
+	assert_eq!(times.exit, None);
+	assert!(times.creation <= std::time::SystemTime::now());
+	assert!(times.run_duration() < std::time::Duration::from_secs(3600));
+}
+
+#[test]
+fn test_vm_scatter_write() {
+	let process = Process::current();
+	let page = process.vm_alloc(IntPtr::NULL, 0x1000, AllocType::COMMIT, Protect::READWRITE).unwrap();
+	let second = IntPtr::from_usize(page.into_usize() + 0x10);
+	process.vm_scatter_write(&[(page, &[0x90, 0x90]), (second, &[0xCC])]).unwrap();
+	assert_eq!(process.vm_read(page.cast::<[u8; 2]>()), Ok([0x90, 0x90]));
+	assert_eq!(process.vm_read(second.cast::<u8>()), Ok(0xCC));
+	let result = process.vm_scatter_write(&[(page, &[0xC3]), (IntPtr::NULL, &[0xC3])]);
+	assert_eq!(result.map_err(|(index, _)| index), Err(1));
+}
+
+#[test]
+fn test_is_alive() {
+	assert!(Process::current().is_alive());
+}
+
+#[test]
+fn test_read_vfunc() {
+	let process = Process::current();
+	let vtable = [0x1111usize, 0x2222, 0x3333];
+	let object = [vtable.as_ptr() as usize, 0];
+	let object = IntPtr::from_usize(object.as_ptr() as usize);
+	assert_eq!(process.read_ptr(object), Ok(IntPtr::from_usize(vtable.as_ptr() as usize)));
+	assert_eq!(process.read_vfunc(object, 2), Ok(IntPtr::from_usize(0x3333)));
+}
+
+#[test]
+fn test_suspend_all_threads() {
+	use std::sync::Arc;
+	use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+	use std::{thread, time::Duration};
+
+	let counter = Arc::new(AtomicUsize::new(0));
+	let stop = Arc::new(AtomicBool::new(false));
+	let worker = {
+		let counter = counter.clone();
+		let stop = stop.clone();
+		thread::spawn(move || while !stop.load(Ordering::Relaxed) {
+			counter.fetch_add(1, Ordering::Relaxed);
+		})
+	};
+	thread::sleep(Duration::from_millis(10));
+
+	let guard = Process::current().suspend_all_threads().unwrap();
+	let before = counter.load(Ordering::Relaxed);
+	thread::sleep(Duration::from_millis(50));
+	assert_eq!(counter.load(Ordering::Relaxed), before);
+	drop(guard);
+
+	thread::sleep(Duration::from_millis(50));
+	assert!(counter.load(Ordering::Relaxed) > before);
+	stop.store(true, Ordering::Relaxed);
+	worker.join().unwrap();
+}
+
+#[test]
+fn test_read_at_module() {
+	let process = Process::current();
+	let mut cache = ModuleCache::new();
+	assert_eq!(cache.read_at_module::<[u8; 2]>(&process, "NTDLL.DLL", 0), Ok(*b"MZ"));
+	// ERROR_MOD_NOT_FOUND
+	assert_eq!(cache.read_at_module::<u8>(&process, "does_not_exist.dll", 0), Err(ErrorCode::from(126)));
+}
+
+#[test]
+fn test_module_cache() {
+	let process = Process::current();
+	let mut cache = ModuleCache::new();
+	let base = process.module_base("ntdll.dll").unwrap();
+	assert_eq!(cache.module_base(&process, "NTDLL.DLL"), Ok(base));
+	assert_eq!(cache.read_at_module::<[u8; 2]>(&process, "ntdll.dll", 0), Ok(*b"MZ"));
+	cache.invalidate("Ntdll.dll");
+	assert_eq!(cache.module_base(&process, "ntdll.dll"), Ok(base));
+	cache.clear();
+	// ERROR_MOD_NOT_FOUND
+	assert_eq!(cache.module_base(&process, "does_not_exist.dll"), Err(ErrorCode::from(126)));
+}
+
+#[test]
+fn test_attach_limited() {
+	let process = Process::attach_limited(ProcessId::current()).unwrap();
+	assert!(process.full_image_name().is_ok());
+	assert!(process.times().is_ok());
+	assert!(process.is_wow64().is_ok());
+}
+
+#[test]
+fn test_wait() {
+	let process = Process::current();
+	assert_eq!(process.wait(Some(std::time::Duration::from_millis(0))), Ok(WaitResult::Timeout));
+	assert_eq!(process.wait_millis(0), Ok(0x102)); // WAIT_TIMEOUT
+}
+
+#[test]
+fn test_resolve_rel() {
+	let process = Process::current();
+	// call -0x10
+	let call = [0xE8u8, 0xF0, 0xFF, 0xFF, 0xFF];
+	let addr = call.as_ptr() as usize;
+	assert_eq!(process.resolve_rel(IntPtr::from_usize(addr), 5, 1), Ok(IntPtr::from_usize(addr + 5 - 0x10)));
+	// mov dword ptr [rip+0x100], 1
+	let mov = [0xC7u8, 0x05, 0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00];
+	let addr = mov.as_ptr() as usize;
+	assert_eq!(process.resolve_rip(IntPtr::from_usize(addr), 2, 10), Ok(IntPtr::from_usize(addr + 10 + 0x100)));
+}
+
+#[test]
+fn test_read() {
+	let process = Process::current();
+	let values = [1u32, 2, 3, 4];
+	let ptr = IntPtr::<u32>::from_usize(values.as_ptr() as usize);
+	assert_eq!(process.read(ptr), Ok(1));
+	assert_eq!(process.read(Array(ptr, 4)), Ok(values.to_vec()));
+	assert_eq!(process.read(Array(ptr, 0)), Ok(Vec::new()));
+}